@@ -1,4 +1,4 @@
-use super::{tile_map, TileMap};
+use super::TileMap;
 use std::fmt;
 
 /*
@@ -59,15 +59,27 @@ impl BlockingMap {
         self.height = tiles.map_height;
 
         for (index, tile) in tiles.to_iter().enumerate() {
-            match tile {
-                tile_map::Tile::StoneWall | tile_map::Tile::Empty | tile_map::Tile::ClosedDoor => {
-                    self.map[index] = true
-                }
-                _ => {}
+            if !tile.is_passable() {
+                self.map[index] = true
             }
         }
     }
 
+    /// returns true if the location lies within the map.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::blocking_map::BlockingMap;
+    /// let mut subject = BlockingMap::new();
+    /// subject.width = 3;
+    /// subject.height = 2;
+    /// assert!(subject.is_in_bounds(2, 1));
+    /// assert!(!subject.is_in_bounds(3, 1));
+    /// assert!(!subject.is_in_bounds(0, -1));
+    /// ```
+    pub fn is_in_bounds(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
     #[inline]
     pub fn is_blocked_at(&self, x: i32, y: i32) -> bool {
         self.map[y as usize * self.width + x as usize]
@@ -1,5 +1,6 @@
 use super::*;
 use regex::Regex;
+use std::collections::HashSet;
 
 pub struct Player {
     pub id: u64,
@@ -9,12 +10,26 @@ pub struct Player {
     pub character_type: CharacterType,
     pub mounting_points: MountingPointMap,
     pub external_inventory: Option<Vec<Item>>,
-    pub endorsements: HashMap<String, bool>,
-    pub activity_guard: Option<Guard>,
-    pub activity_timer: Option<timer::Timer>,
+    endorsements: HashMap<String, bool>, // derived from endorsement_sources
+    endorsement_sources: HashMap<EndorsementSource, HashSet<String>>,
+    pub activity: Option<u64>, // id of the scheduled event that completes the activity
+    pub skills: SkillSet,
+    pub bump_to_interact: bool, // walking into a door or facility uses it
+    pub moving: bool,           // the activity is a step to the next tile
+    pub queued_step: Option<(Direction, MoveCommandMode)>, // taken once the step under way is over
+    pub step_sequence: Option<u64>, // of the numbered command that started the step under way
+    pub queued_sequence: Option<u64>, // of the numbered command that queued the next step
 }
 
 impl Player {
+    /// how many tiles away the player can see, in any direction.
+    pub const SIGHT_RADIUS: i32 = 10;
+    /// the speed of a player with nothing equipped that changes it.
+    pub const BASE_SPEED: i32 = 10;
+    /// how long a step onto floor takes at base speed, in milliseconds.
+    pub const WALKING_STEP: u64 = 250;
+    pub const SNEAKING_STEP: u64 = 600;
+
     pub fn new() -> Player {
         let mut player = Player {
             id: 1,
@@ -25,8 +40,14 @@ impl Player {
             mounting_points: MountingPointMap::new(),
             external_inventory: None,
             endorsements: HashMap::new(),
-            activity_guard: None,
-            activity_timer: None,
+            endorsement_sources: HashMap::new(),
+            activity: None,
+            skills: SkillSet::new(),
+            bump_to_interact: false,
+            moving: false,
+            queued_step: None,
+            step_sequence: None,
+            queued_sequence: None,
         };
         // temporary.  Not sure where this belongs once saving is in place.
         player.endorse_with(":newb");
@@ -37,23 +58,118 @@ impl Player {
         self.id
     }
 
+    /// returns the player's speed: the base speed, changed by whatever they have equipped.
+    pub fn speed(&self, items: &ItemList) -> i32 {
+        let equipment = self.mounting_points.to_vec_of_items(items);
+        let bonus: i32 = equipment
+            .iter()
+            .map(|item| item.item_type.speed() as i32)
+            .sum();
+
+        (Self::BASE_SPEED + bonus).max(1)
+    }
+
+    /// returns how many milliseconds a step onto the tile takes in the mode.
+    ///   Sneaking is slower than walking, and difficult terrain slower than floor.
+    pub fn step_duration(
+        &self,
+        mode: MoveCommandMode,
+        tile: tile_map::Tile,
+        items: &ItemList,
+    ) -> u64 {
+        let step = match mode {
+            MoveCommandMode::Sneak => Self::SNEAKING_STEP,
+            _ => Self::WALKING_STEP,
+        };
+
+        step * tile.movement_cost() as u64 * Self::BASE_SPEED as u64
+            / (100 * self.speed(items) as u64)
+    }
+
+    /// returns true if x,y is within sight of the player and not hidden behind a wall or door.
+    pub fn can_see(&self, x: i32, y: i32, map: &TileMap) -> bool {
+        (x - self.x).abs() <= Self::SIGHT_RADIUS
+            && (y - self.y).abs() <= Self::SIGHT_RADIUS
+            && map.has_line_of_sight(self.x, self.y, x, y)
+    }
+
     pub fn is_endorsed_with<S: ToString>(&self, endorsement: S) -> bool {
         self.endorsements.contains_key(&endorsement.to_string())
     }
 
+    /// grants a base endorsement; one that does not depend upon equipment, status or skill.
     pub fn endorse_with<S: ToString>(&mut self, endorsement: S) {
-        self.endorsements.insert(endorsement.to_string(), true);
+        self.endorse_from(EndorsementSource::Base, endorsement);
+    }
+
+    pub fn unendorse_with<S: ToString>(&mut self, endorsement: S) {
+        self.unendorse_from(EndorsementSource::Base, endorsement);
+    }
+
+    pub fn endorse_from<S: ToString>(&mut self, source: EndorsementSource, endorsement: S) {
+        self.endorsement_sources
+            .entry(source)
+            .or_default()
+            .insert(endorsement.to_string());
+        self.recompute_endorsements();
+    }
+
+    pub fn unendorse_from<S: ToString>(&mut self, source: EndorsementSource, endorsement: S) {
+        if let Some(endorsements) = self.endorsement_sources.get_mut(&source) {
+            endorsements.remove(&endorsement.to_string());
+        }
+        self.recompute_endorsements();
     }
 
+    /// replaces everything granted by source with endorsements.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::character::*;
+    /// let mut subject = Player::new();
+    /// subject.endorse_with(":can_swim");
+    /// subject.set_endorsements_from(EndorsementSource::Equipment, vec![":can_swim", ":can_dig"]);
+    /// subject.set_endorsements_from(EndorsementSource::Equipment, Vec::<String>::new());
+    /// assert!(subject.is_endorsed_with(":can_swim"));
+    /// assert!(!subject.is_endorsed_with(":can_dig"));
+    /// ```
+    pub fn set_endorsements_from<I, S>(&mut self, source: EndorsementSource, endorsements: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        self.endorsement_sources.insert(
+            source,
+            endorsements.into_iter().map(|e| e.to_string()).collect(),
+        );
+        self.recompute_endorsements();
+    }
+
+    /// removes the endorsements granted by every source.
     pub fn clear_endorsements(&mut self) {
-        self.endorsements.clear();
+        self.endorsement_sources.clear();
+        self.recompute_endorsements();
     }
 
-    pub fn unendorse_with<S: ToString>(&mut self, endorsement: S) {
-        self.endorsements.remove(&endorsement.to_string());
+    /// rebuilds the player's endorsements from all of their sources.
+    pub fn recompute_endorsements(&mut self) {
+        self.endorsements = self
+            .endorsement_sources
+            .values()
+            .flatten()
+            .map(|e| (e.clone(), true))
+            .collect();
     }
 }
 
+/// where an endorsement comes from.  An endorsement is held as long as any source grants it.
+#[derive(Debug, Hash, Eq, PartialEq, Copy, Clone)]
+pub enum EndorsementSource {
+    Base,
+    Equipment,
+    StatusEffect,
+    Skills,
+}
+
 pub enum CharacterFacing {
     Up,
     Upright,
@@ -69,16 +185,18 @@ pub struct Character {
     pub y: i32,
     pub facing: Direction,
     pub character_type: CharacterType,
+    pub aware_of_player: bool,
 }
 
 impl Character {
-    fn new(id: u64, x: i32, y: i32, facing: Direction, character_type: CharacterType) -> Self {
+    pub fn new(id: u64, x: i32, y: i32, facing: Direction, character_type: CharacterType) -> Self {
         Character {
             id,
             x,
             y,
             facing,
             character_type,
+            aware_of_player: false,
         }
     }
     fn read_character(string: &str, re: &Regex) -> Character {
@@ -109,7 +227,7 @@ impl Character {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Hash, Eq, PartialEq, Copy, Clone)]
 pub enum CharacterType {
     Player,
     Rat,
@@ -117,13 +235,22 @@ pub enum CharacterType {
 }
 
 impl CharacterType {
-    fn from_symbol(symbol: &str) -> CharacterType {
+    pub fn from_symbol(symbol: &str) -> CharacterType {
         match &symbol[..] {
             "R" => CharacterType::Rat,
             "S" => CharacterType::Spider,
             _ => panic!("unknown symbol"),
         }
     }
+
+    pub fn from_name(name: &str) -> CharacterType {
+        match &name.to_lowercase()[..] {
+            "player" => CharacterType::Player,
+            "rat" => CharacterType::Rat,
+            "spider" => CharacterType::Spider,
+            _ => panic!("unknown character type: {}", name),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -143,6 +270,24 @@ impl CharacterList {
     pub fn add(&mut self, character: Character) {
         self.characters.push(character);
     }
+
+    pub fn get(&self, id: u64) -> Option<&Character> {
+        self.characters.iter().find(|c| c.id == id)
+    }
+
+    pub fn get_mut(&mut self, id: u64) -> Option<&mut Character> {
+        self.characters.iter_mut().find(|c| c.id == id)
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, Character> {
+        self.characters.iter_mut()
+    }
+
+    /// takes the character out of the list, returning it if it was there.
+    pub fn remove(&mut self, id: u64) -> Option<Character> {
+        let index = self.characters.iter().position(|c| c.id == id)?;
+        Some(self.characters.remove(index))
+    }
 }
 
 pub struct CharacterListIterator<'a> {
@@ -201,4 +346,29 @@ mod endorsements {
 
         assert!(!subject.is_endorsed_with(":an_endorsement"));
     }
+
+    #[test]
+    fn an_endorsement_is_held_while_any_source_grants_it() {
+        let mut subject = Player::new();
+
+        subject.endorse_from(EndorsementSource::StatusEffect, ":fleet_footed");
+        subject.endorse_from(EndorsementSource::Skills, ":fleet_footed");
+
+        subject.unendorse_from(EndorsementSource::StatusEffect, ":fleet_footed");
+        assert!(subject.is_endorsed_with(":fleet_footed"));
+
+        subject.unendorse_from(EndorsementSource::Skills, ":fleet_footed");
+        assert!(!subject.is_endorsed_with(":fleet_footed"));
+    }
+
+    #[test]
+    fn unendorsing_one_source_leaves_the_others_alone() {
+        let mut subject = Player::new();
+
+        subject.endorse_from(EndorsementSource::Equipment, ":can_pick_apples");
+        subject.unendorse_with(":can_pick_apples");
+
+        assert!(subject.is_endorsed_with(":can_pick_apples"));
+        assert!(subject.is_endorsed_with(":newb"));
+    }
 }
@@ -0,0 +1,69 @@
+use std::time::Instant;
+
+/// The source of game time.  All times are in milliseconds since the clock was started.
+#[derive(Debug, Clone)]
+pub enum GameClock {
+    /// only advances when told to.  Used for testing and replaying.
+    Virtual(u64),
+    /// follows the wall clock.  Used for play.
+    ///   The time only changes when synchronized, so it stands still while a command is handled.
+    RealTime(Instant, u64),
+}
+
+impl GameClock {
+    /// returns a virtual clock starting at time 0.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::clock::GameClock;
+    /// let subject = GameClock::new_virtual();
+    /// assert_eq!(subject.now(), 0);
+    /// ```
+    pub fn new_virtual() -> Self {
+        GameClock::Virtual(0)
+    }
+
+    /// returns a real-time clock starting now.
+    pub fn new_real_time() -> Self {
+        GameClock::RealTime(Instant::now(), 0)
+    }
+
+    /// returns true if the clock must be advanced by hand.
+    pub fn is_virtual(&self) -> bool {
+        matches!(self, GameClock::Virtual(_))
+    }
+
+    /// returns the number of milliseconds elapsed since the clock started.
+    pub fn now(&self) -> u64 {
+        match self {
+            GameClock::Virtual(now) => *now,
+            GameClock::RealTime(_, now) => *now,
+        }
+    }
+
+    /// catches a real-time clock up with the wall clock.  Does nothing to a virtual clock.
+    pub fn synchronize(&mut self) {
+        if let GameClock::RealTime(started, now) = self {
+            *now = started.elapsed().as_millis() as u64;
+        }
+    }
+
+    /// moves a virtual clock forward.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::clock::GameClock;
+    /// let mut subject = GameClock::new_virtual();
+    /// subject.advance(1500);
+    /// assert_eq!(subject.now(), 1500);
+    /// ```
+    /// ```should_panic
+    /// # use muframework::game::clock::GameClock;
+    /// let mut subject = GameClock::new_real_time();
+    /// subject.advance(1500);
+    /// ```
+    pub fn advance(&mut self, millis: u64) {
+        match self {
+            GameClock::Virtual(now) => *now += millis,
+            GameClock::RealTime(_, _) => panic!("a real-time clock cannot be advanced"),
+        }
+    }
+}
@@ -0,0 +1,278 @@
+use super::*;
+
+/// removes a creature from the level, leaving whatever its loot table drops where it fell.
+///   Only a creature next to the player can be killed, and the player gains Combat experience.
+pub struct KillCommand<'a> {
+    character_id: u64,
+    player: &'a mut Player,
+    characters: &'a mut CharacterList,
+    obstacles: &'a mut BlockingMap,
+    items: &'a mut ItemList,
+    dropped: Vec<(Item, i32, i32)>,
+    level_reached: Option<u8>,
+}
+
+impl<'a> KillCommand<'a> {
+    /// the Combat experience gained for each creature killed.
+    pub const EXPERIENCE: u32 = 40;
+
+    pub fn new(
+        character_id: u64,
+        player: &'a mut Player,
+        characters: &'a mut CharacterList,
+        obstacles: &'a mut BlockingMap,
+        items: &'a mut ItemList,
+    ) -> KillCommand<'a> {
+        KillCommand {
+            character_id,
+            player,
+            characters,
+            obstacles,
+            items,
+            dropped: vec![],
+            level_reached: None,
+        }
+    }
+}
+
+impl<'a> CommandHandler for KillCommand<'a> {
+    fn can_perform(&self) -> Result<(), Rejection> {
+        match self.characters.get(self.character_id) {
+            Some(character)
+                if (character.x - self.player.x).abs() <= 1
+                    && (character.y - self.player.y).abs() <= 1 =>
+            {
+                Ok(())
+            }
+            _ => Err(Rejection::OutOfReach(self.character_id)),
+        }
+    }
+
+    fn perform_execute(
+        &mut self,
+        _update_tx: Option<&GameUpdateSender>,
+        _command_tx: Option<&CommandSender>,
+    ) {
+        let character = match self.characters.remove(self.character_id) {
+            Some(character) => character,
+            None => return,
+        };
+        self.obstacles.unblock_at(character.x, character.y);
+
+        let loot = match self.items.loot_tables.table_for(character.character_type) {
+            Some(table) => with_random(|rng| {
+                self.items
+                    .loot_tables
+                    .spawn(table, &self.items.item_types, rng)
+            }),
+            None => vec![],
+        };
+
+        for item in loot {
+            self.items.bundle(&item, character.x, character.y);
+            self.dropped.push((item, character.x, character.y));
+        }
+
+        self.level_reached = self
+            .player
+            .skills
+            .gain_experience(Skill::Combat, Self::EXPERIENCE);
+    }
+
+    fn announce(&self, update_tx: &GameUpdateSender) {
+        GameUpdate::send(Some(update_tx), CharacterExited(self.character_id));
+
+        for (item, x, y) in self.dropped.iter() {
+            GameUpdate::send(
+                Some(update_tx),
+                ItemAdded {
+                    id: item.id,
+                    x: *x,
+                    y: *y,
+                    description: item.description(),
+                    class: item.class(),
+                },
+            );
+        }
+
+        if let Some(level) = self.level_reached {
+            GameUpdate::send(Some(update_tx), SkillLevelIncreased(Skill::Combat, level));
+        }
+    }
+}
+
+#[cfg(test)]
+mod kill_command {
+    use super::*;
+
+    fn level() -> (CharacterList, BlockingMap, ItemList) {
+        let mut characters = Character::read_in_characters(&mut vec![
+            "8,7".to_string(),
+            "R 3,4".to_string(),
+            "S 5,6".to_string(),
+        ]);
+        let mut map = TileMap::new();
+        map.set_dimensions(10, 10, tile_map::Tile::DirtFloor);
+        let mut obstacles = BlockingMap::new();
+        obstacles.refresh(&map);
+        Level::introduce_other_characters(&characters, &mut obstacles, None);
+
+        let mut item_types = ItemTypeList::new();
+        item_types.insert("apple".into(), ItemType::new(ItemClass::Food, "Apple"));
+        let mut items = ItemList::new(Some(item_types));
+        items.loot_tables = LootTableList::read_in_loot_tables(&vec![
+            "rat_loot {".into(),
+            "    dropped_by: Rat".into(),
+            "    1 apple 2-3".into(),
+            "}".into(),
+        ]);
+
+        // the rat at 3,4 is 100 and the spider at 5,6 is 101.
+        for character in characters.iter().collect::<Vec<Character>>() {
+            let id = match character.character_type {
+                CharacterType::Rat => 100,
+                _ => 101,
+            };
+            characters.get_mut(character.id).unwrap().id = id;
+        }
+        (characters, obstacles, items)
+    }
+
+    fn next_to_both() -> Player {
+        let mut player = Player::new();
+        player.x = 4;
+        player.y = 5;
+        player
+    }
+
+    #[test]
+    fn a_creature_drops_its_loot_where_it_dies() {
+        let (mut characters, mut obstacles, mut items) = level();
+        let mut player = next_to_both();
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        KillCommand::new(
+            100,
+            &mut player,
+            &mut characters,
+            &mut obstacles,
+            &mut items,
+        )
+        .execute(Some(&sender), None);
+
+        assert!(characters.get(100).is_none());
+        assert!(!obstacles.is_blocked_at(3, 4));
+        let dropped = items.find_nth_at(3, 4, 0).cloned();
+        match dropped {
+            Some(ItemState::Bundle(item, 3, 4)) => {
+                assert_eq!(item.raw_description(), "Apple");
+                assert!((2..=3).contains(&item.quantity));
+            }
+            other => panic!("Unexpected loot: {:?}", other),
+        }
+        assert!(matches!(receiver.try_recv(), Ok(CharacterExited(100))));
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(ItemAdded { x: 3, y: 4, .. })
+        ));
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn a_creature_without_loot_drops_nothing() {
+        let (mut characters, mut obstacles, mut items) = level();
+        let mut player = next_to_both();
+
+        KillCommand::new(
+            101,
+            &mut player,
+            &mut characters,
+            &mut obstacles,
+            &mut items,
+        )
+        .execute(None, None);
+
+        assert!(characters.get(101).is_none());
+        assert!(items.is_empty());
+    }
+
+    #[test]
+    fn killing_a_creature_trains_combat() {
+        let (mut characters, mut obstacles, mut items) = level();
+        let mut player = next_to_both();
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        KillCommand::new(
+            101,
+            &mut player,
+            &mut characters,
+            &mut obstacles,
+            &mut items,
+        )
+        .execute(Some(&sender), None);
+        assert_eq!(
+            player.skills.experience(Skill::Combat),
+            KillCommand::EXPERIENCE
+        );
+
+        characters.add(Character::new(
+            102,
+            3,
+            5,
+            Direction::Down,
+            CharacterType::Rat,
+        ));
+        KillCommand::new(
+            102,
+            &mut player,
+            &mut characters,
+            &mut obstacles,
+            &mut items,
+        )
+        .execute(Some(&sender), None);
+        KillCommand::new(
+            100,
+            &mut player,
+            &mut characters,
+            &mut obstacles,
+            &mut items,
+        )
+        .execute(Some(&sender), None);
+
+        let level_ups: Vec<GameUpdate> = receiver
+            .try_iter()
+            .filter(|u| matches!(u, SkillLevelIncreased(_, _)))
+            .collect();
+        assert_eq!(
+            format!("{:?}", level_ups),
+            "[SkillLevelIncreased(Combat, 2)]"
+        );
+    }
+
+    #[test]
+    fn a_creature_out_of_reach_cannot_be_killed() {
+        let (mut characters, mut obstacles, mut items) = level();
+        let mut player = next_to_both();
+        player.x = 6;
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        KillCommand::new(
+            100,
+            &mut player,
+            &mut characters,
+            &mut obstacles,
+            &mut items,
+        )
+        .execute(Some(&sender), None);
+
+        assert!(characters.get(100).is_some());
+        assert!(obstacles.is_blocked_at(3, 4));
+        assert!(items.is_empty());
+        assert_eq!(player.skills.experience(Skill::Combat), 0);
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(CommandRejected(Rejection::OutOfReach(100)))
+        ));
+        assert!(receiver.try_recv().is_err());
+    }
+}
@@ -1,29 +1,63 @@
 use super::*;
 
+/// returns true if a character or an item is in the doorway at x,y.
+pub fn doorway_is_occupied(x: i32, y: i32, obstacles: &BlockingMap, items: &ItemList) -> bool {
+    obstacles.is_blocked_at(x, y) || items.find_nth_at(x, y, 0).is_some()
+}
+
 pub struct OpenDoorCommand<'a> {
     x: i32,
     y: i32,
+    player: &'a Player,
+    inventory: Option<&'a Inventory>, // of whoever opens the door
+    item_types: &'a ItemTypeList,
     obstacles: &'a mut BlockingMap,
     map: &'a mut TileMap,
+    scheduler: &'a mut Scheduler,
 }
 
 impl<'a> OpenDoorCommand<'a> {
     pub fn new(
         x: i32,
         y: i32,
+        player: &'a Player,
+        inventory: Option<&'a Inventory>,
+        item_types: &'a ItemTypeList,
         obstacles: &'a mut BlockingMap,
         map: &'a mut TileMap,
+        scheduler: &'a mut Scheduler,
     ) -> OpenDoorCommand<'a> {
         OpenDoorCommand {
             x,
             y,
+            player,
+            inventory,
+            item_types,
             obstacles,
             map,
+            scheduler,
         }
     }
 }
 
 impl<'a> CommandHandler for OpenDoorCommand<'a> {
+    /// a locked door opens only for someone carrying its key, and any door only for someone
+    ///   meeting its requirement.
+    fn can_perform(&self) -> Result<(), Rejection> {
+        match self.map.at(self.x, self.y) {
+            tile_map::Tile::ClosedDoor => match self.map.doors.at(self.x, self.y) {
+                Some(door) if !door.can_be_opened_with(self.inventory, self.item_types) => {
+                    Err(Rejection::DoorLocked(self.x, self.y))
+                }
+                Some(door) if !door.requirement.is_met_by(self.player, self.inventory) => {
+                    Err(Rejection::RequirementNotMet("open the door".into()))
+                }
+                _ => Ok(()),
+            },
+            _ => Err(Rejection::NothingToOpen(self.x, self.y)),
+        }
+    }
+
     fn perform_execute(
         &mut self,
         _update_tx: Option<&GameUpdateSender>,
@@ -32,38 +66,78 @@ impl<'a> CommandHandler for OpenDoorCommand<'a> {
         self.map
             .set_tile_at(self.x, self.y, tile_map::Tile::OpenDoor);
         self.obstacles.unblock_at(self.x, self.y);
+
+        if let Some(door) = self.map.doors.at_mut(self.x, self.y) {
+            if let Some(delay) = door.closes_after {
+                if let Some(pending_close) = door.pending_close {
+                    self.scheduler.cancel(pending_close);
+                }
+                door.pending_close = Some(
+                    self.scheduler
+                        .schedule(delay, vec![GameEvent::CloseDoor(door.id)]),
+                );
+            }
+        }
     }
     fn announce(&self, update_tx: &std::sync::mpsc::Sender<GameUpdate>) {
         update_tx
-            .send(TileChangedAt(self.x, self.y, tile_map::Tile::OpenDoor))
+            .send(TileChangedAt(
+                self.x,
+                self.y,
+                tile_map::Tile::OpenDoor,
+                self.map.chunk_version_at(self.x, self.y),
+            ))
             .unwrap();
+        GameUpdate::send(
+            Some(update_tx),
+            Message(MessageCategory::System, "The door opens.".into()),
+        );
     }
 }
 
 pub struct CloseDoorCommand<'a> {
     x: i32,
     y: i32,
+    items: &'a ItemList,
     obstacles: &'a mut BlockingMap,
     map: &'a mut TileMap,
+    scheduler: &'a mut Scheduler,
 }
 
 impl<'a> CloseDoorCommand<'a> {
     pub fn new(
         x: i32,
         y: i32,
+        items: &'a ItemList,
         obstacles: &'a mut BlockingMap,
         map: &'a mut TileMap,
+        scheduler: &'a mut Scheduler,
     ) -> CloseDoorCommand<'a> {
         CloseDoorCommand {
             x,
             y,
+            items,
             obstacles,
             map,
+            scheduler,
         }
     }
 }
 
 impl<'a> CommandHandler for CloseDoorCommand<'a> {
+    /// a door cannot be closed on someone standing in it, or on something lying in it.
+    fn can_perform(&self) -> Result<(), Rejection> {
+        match self.map.at(self.x, self.y) {
+            tile_map::Tile::OpenDoor
+                if doorway_is_occupied(self.x, self.y, self.obstacles, self.items) =>
+            {
+                Err(Rejection::Blocked(self.x, self.y))
+            }
+            tile_map::Tile::OpenDoor => Ok(()),
+            _ => Err(Rejection::NothingToClose(self.x, self.y)),
+        }
+    }
+
     fn perform_execute(
         &mut self,
         _update_tx: Option<&GameUpdateSender>,
@@ -72,11 +146,189 @@ impl<'a> CommandHandler for CloseDoorCommand<'a> {
         self.map
             .set_tile_at(self.x, self.y, tile_map::Tile::ClosedDoor);
         self.obstacles.block_at(self.x, self.y);
+
+        if let Some(door) = self.map.doors.at_mut(self.x, self.y) {
+            if let Some(pending_close) = door.pending_close.take() {
+                self.scheduler.cancel(pending_close);
+            }
+        }
     }
 
     fn announce(&self, update_tx: &std::sync::mpsc::Sender<GameUpdate>) {
         update_tx
-            .send(TileChangedAt(self.x, self.y, tile_map::Tile::ClosedDoor))
+            .send(TileChangedAt(
+                self.x,
+                self.y,
+                tile_map::Tile::ClosedDoor,
+                self.map.chunk_version_at(self.x, self.y),
+            ))
             .unwrap();
+        GameUpdate::send(
+            Some(update_tx),
+            Message(MessageCategory::System, "The door closes.".into()),
+        );
+    }
+}
+
+#[cfg(test)]
+mod door_commands {
+    use super::*;
+
+    // test.map has an open door at 19,7.
+    #[test]
+    fn an_open_door_cannot_be_opened() {
+        let (player, mut map, mut obstacles, _, _, items, ..) =
+            GameState::initialize_game("maps/test.map", None);
+        let mut scheduler = Scheduler::new(GameClock::new_virtual());
+
+        let command = OpenDoorCommand::new(
+            19,
+            7,
+            &player,
+            None,
+            &items.item_types,
+            &mut obstacles,
+            &mut map,
+            &mut scheduler,
+        );
+
+        assert_eq!(command.can_perform(), Err(Rejection::NothingToOpen(19, 7)));
+    }
+
+    #[test]
+    fn a_door_cannot_be_closed_on_someone_standing_in_it() {
+        let (_player, mut map, mut obstacles, _, _, items, ..) =
+            GameState::initialize_game("maps/test.map", None);
+        let mut scheduler = Scheduler::new(GameClock::new_virtual());
+        obstacles.block_at(19, 7);
+
+        let mut command =
+            CloseDoorCommand::new(19, 7, &items, &mut obstacles, &mut map, &mut scheduler);
+        assert_eq!(command.can_perform(), Err(Rejection::Blocked(19, 7)));
+
+        command.execute(None, None);
+        assert_eq!(map.at(19, 7), tile_map::Tile::OpenDoor);
+    }
+
+    #[test]
+    fn a_door_cannot_be_closed_on_something_lying_in_it() {
+        let (_player, mut map, mut obstacles, _, _, mut items, ..) =
+            GameState::initialize_game("maps/test.map", None);
+        let mut scheduler = Scheduler::new(GameClock::new_virtual());
+        items.bundle(&Item::spawn(ItemClass::Food, "Apple"), 19, 7);
+
+        let command =
+            CloseDoorCommand::new(19, 7, &items, &mut obstacles, &mut map, &mut scheduler);
+
+        assert_eq!(command.can_perform(), Err(Rejection::Blocked(19, 7)));
+    }
+
+    #[test]
+    fn a_wall_cannot_be_closed() {
+        let (_player, mut map, mut obstacles, _, _, items, ..) =
+            GameState::initialize_game("maps/test.map", None);
+        let mut scheduler = Scheduler::new(GameClock::new_virtual());
+
+        let command = CloseDoorCommand::new(0, 0, &items, &mut obstacles, &mut map, &mut scheduler);
+
+        assert_eq!(command.can_perform(), Err(Rejection::NothingToClose(0, 0)));
+    }
+
+    fn locked_door() -> (TileMap, BlockingMap, ItemList, Inventory) {
+        let mut map = TileMap::new();
+        map.set_dimensions(5, 5, tile_map::Tile::DirtFloor);
+        map.set_tile_at(2, 2, tile_map::Tile::ClosedDoor);
+        map.doors = DoorList::read_in_doors(&map, &["2,2 { key: brass_key }".into()]);
+        let mut obstacles = BlockingMap::new();
+        obstacles.refresh(&map);
+
+        let mut item_types = ItemTypeList::new();
+        item_types.insert(
+            "brass_key".into(),
+            ItemType::new(ItemClass::Tool, "Brass Key"),
+        );
+        let items = ItemList::new(Some(item_types));
+
+        (map, obstacles, items, Inventory::new(1))
+    }
+
+    #[test]
+    fn a_locked_door_opens_only_for_the_holder_of_its_key() {
+        let (mut map, mut obstacles, items, mut inventory) = locked_door();
+        let mut scheduler = Scheduler::new(GameClock::new_virtual());
+        let player = Player::new();
+
+        let command = OpenDoorCommand::new(
+            2,
+            2,
+            &player,
+            Some(&inventory),
+            &items.item_types,
+            &mut obstacles,
+            &mut map,
+            &mut scheduler,
+        );
+        assert_eq!(command.can_perform(), Err(Rejection::DoorLocked(2, 2)));
+
+        inventory.force_accept(&Item::spawn_from_type("brass_key", 1, &items.item_types));
+        OpenDoorCommand::new(
+            2,
+            2,
+            &player,
+            Some(&inventory),
+            &items.item_types,
+            &mut obstacles,
+            &mut map,
+            &mut scheduler,
+        )
+        .execute(None, None);
+
+        assert_eq!(map.at(2, 2), tile_map::Tile::OpenDoor);
+        assert!(!obstacles.is_blocked_at(2, 2));
+    }
+
+    #[test]
+    fn a_door_opens_only_for_those_meeting_its_requirement() {
+        let mut map = TileMap::new();
+        map.set_dimensions(5, 5, tile_map::Tile::DirtFloor);
+        map.set_tile_at(2, 2, tile_map::Tile::ClosedDoor);
+        map.doors = DoorList::read_in_doors(&map, &["2,2 { requires: skill(combat, 2) }".into()]);
+        let mut obstacles = BlockingMap::new();
+        obstacles.refresh(&map);
+        let items = ItemList::new(None);
+        let mut scheduler = Scheduler::new(GameClock::new_virtual());
+        let mut player = Player::new();
+
+        let command = OpenDoorCommand::new(
+            2,
+            2,
+            &player,
+            None,
+            &items.item_types,
+            &mut obstacles,
+            &mut map,
+            &mut scheduler,
+        );
+        assert_eq!(
+            command.can_perform(),
+            Err(Rejection::RequirementNotMet("open the door".into()))
+        );
+
+        player
+            .skills
+            .gain_experience(Skill::Combat, Skill::experience_for_level(2));
+        OpenDoorCommand::new(
+            2,
+            2,
+            &player,
+            None,
+            &items.item_types,
+            &mut obstacles,
+            &mut map,
+            &mut scheduler,
+        )
+        .execute(None, None);
+
+        assert_eq!(map.at(2, 2), tile_map::Tile::OpenDoor);
     }
 }
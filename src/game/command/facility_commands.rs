@@ -1,6 +1,3 @@
-extern crate chrono;
-extern crate timer;
-
 pub use super::*;
 pub use std::sync::mpsc::channel;
 
@@ -58,63 +55,63 @@ impl<'a> CommandHandler for OpenChestCommand<'a> {
 
 pub struct ActivateAppleTreeCommand<'a> {
     player: &'a mut Player,
+    requirement: Requirement,
+    inventory: Option<&'a Inventory>,
+    scheduler: &'a mut Scheduler,
 }
 
 impl<'a> ActivateAppleTreeCommand<'a> {
-    pub fn new(player: &'a mut Player) -> Self {
-        Self { player }
+    /// # Arguments
+    /// * requirement - the tree's declared requirement
+    /// * inventory - the player's inventory, against which the requirement is checked
+    pub fn new(
+        player: &'a mut Player,
+        requirement: Requirement,
+        inventory: Option<&'a Inventory>,
+        scheduler: &'a mut Scheduler,
+    ) -> Self {
+        Self {
+            player,
+            requirement,
+            inventory,
+            scheduler,
+        }
     }
 }
 
 impl<'a> CommandHandler for ActivateAppleTreeCommand<'a> {
-    fn can_perform(&self) -> bool {
-        self.player.is_endorsed_with(":can_pick_apples")
+    fn can_perform(&self) -> Result<(), Rejection> {
+        if self.requirement.is_met_by(self.player, self.inventory) {
+            Ok(())
+        } else {
+            Err(Rejection::RequirementNotMet("pick apples".into()))
+        }
     }
     fn perform_execute(
         &mut self,
-        update_tx: Option<&GameUpdateSender>,
-        command_tx: Option<&std::sync::mpsc::Sender<Command>>,
+        _update_tx: Option<&GameUpdateSender>,
+        _command_tx: Option<&std::sync::mpsc::Sender<Command>>,
     ) {
-        let timer = timer::Timer::new();
-
-        // unwrap senders to avoid thread sending problems
-        let command_sender = command_tx.unwrap().clone();
-        let update_sender = update_tx.unwrap().clone();
-
         let player_inventory_id = self.player.inventory_id();
 
-        self.player.activity_guard = Some(
-            timer.schedule_repeating(chrono::Duration::seconds(60), move || {
-                Self::complete_activity(player_inventory_id, &update_sender, &command_sender)
-            }),
-        );
-        self.player.activity_timer = Some(timer);
+        self.player.activity = Some(self.scheduler.schedule_repeating(
+            Self::DURATION as u64,
+            vec![
+                Command::SpawnItem(player_inventory_id, ItemClass::Food, "Apple".into()).into(),
+                Command::RefreshInventory.into(),
+                GameEvent::GainExperience(Skill::Harvesting, Self::EXPERIENCE),
+            ],
+        ));
     }
 
     fn announce(&self, update_tx: &std::sync::mpsc::Sender<GameUpdate>) {
-        Self::start_activity(update_tx)
+        GameUpdate::send(Some(update_tx), GameUpdate::ActivityStarted(Self::DURATION));
     }
 }
 
 impl<'a> ActivateAppleTreeCommand<'a> {
-    fn start_activity(update_tx: &GameUpdateSender) {
-        GameUpdate::send(Some(update_tx), GameUpdate::ActivityStarted(60000));
-    }
-
-    fn complete_activity(
-        player_inventory_id: u64,
-        update_sender: &GameUpdateSender,
-        command_sender: &CommandSender,
-    ) {
-        GameUpdate::send(Some(&update_sender), GameUpdate::ActivityExpired());
-
-        Command::send(
-            Some(&command_sender),
-            Command::SpawnItem(player_inventory_id, ItemClass::Food, "Apple".into()),
-        );
-
-        Command::send(Some(&command_sender), Command::RefreshInventory);
-
-        Self::start_activity(&update_sender);
-    }
+    /// the time it takes to pick an apple in milliseconds.
+    pub const DURATION: u32 = 60000;
+    /// the Harvesting experience gained for each apple picked.
+    pub const EXPERIENCE: u32 = 25;
 }
@@ -2,6 +2,7 @@ use super::*;
 
 pub struct PickupCommand<'a> {
     item_id: u64,
+    description: Option<String>, // of the item as it lay
     inventory: &'a mut Inventory,
     items: &'a mut ItemList,
 }
@@ -14,6 +15,7 @@ impl<'a> PickupCommand<'a> {
     ) -> PickupCommand<'a> {
         PickupCommand {
             item_id,
+            description: items.get_as_item(item_id).map(|item| item.description()),
             inventory,
             items,
         }
@@ -21,6 +23,19 @@ impl<'a> PickupCommand<'a> {
 }
 
 impl<'a> CommandHandler for PickupCommand<'a> {
+    fn can_perform(&self) -> Result<(), Rejection> {
+        match self.items.get(self.item_id) {
+            Some(ItemState::Bundle(item, _, _)) => {
+                if self.inventory.can_accept(&item) {
+                    Ok(())
+                } else {
+                    Err(Rejection::InventoryFull(self.inventory.id()))
+                }
+            }
+            _ => Err(Rejection::ItemNotBundled(self.item_id)),
+        }
+    }
+
     fn perform_execute(
         &mut self,
         _update_tx: Option<&GameUpdateSender>,
@@ -34,21 +49,31 @@ impl<'a> CommandHandler for PickupCommand<'a> {
         GameUpdate::send(
             Some(update_tx),
             GameUpdate::InventoryUpdated(self.inventory.to_vec()),
-        )
+        );
+        if let Some(description) = &self.description {
+            announce_loot(
+                format!("You pick up {}.", description.to_lowercase()),
+                update_tx,
+            );
+        }
     }
 }
 
 pub struct DropCommand<'a> {
     item: &'a Item,
+    quantity: u8,
     x: i32,
     y: i32,
     inventory: &'a mut Inventory,
     items: &'a mut ItemList,
+    dropped: Option<Item>,
 }
 
 impl<'a> DropCommand<'a> {
+    /// drops quantity of the item's stack at x,y.
     pub fn new(
         item: &'a Item,
+        quantity: u8,
         x: i32,
         y: i32,
         inventory: &'a mut Inventory,
@@ -56,39 +81,74 @@ impl<'a> DropCommand<'a> {
     ) -> DropCommand<'a> {
         DropCommand {
             item,
+            quantity,
             x,
             y,
             inventory,
             items,
+            dropped: None,
         }
     }
 }
 
 impl<'a> CommandHandler for DropCommand<'a> {
+    fn can_perform(&self) -> Result<(), Rejection> {
+        let held = self
+            .inventory
+            .items
+            .get(&self.item.id)
+            .ok_or(Rejection::ItemNotHeld(self.item.id))?;
+
+        if self.quantity == 0 || self.quantity > held.quantity {
+            return Err(Rejection::InvalidQuantity(self.item.id, self.quantity));
+        }
+        Ok(())
+    }
+
     fn perform_execute(
         &mut self,
         _update_tx: Option<&GameUpdateSender>,
         _command_tx: Option<&std::sync::mpsc::Sender<Command>>,
     ) {
-        self.inventory
-            .release_item_at(self.x, self.y, &self.item, &mut self.items);
+        let dropped = match self
+            .inventory
+            .take_from_stack(self.item.id, self.quantity, self.items)
+        {
+            Some(part) => {
+                self.items.add_item_to_bundle_at(self.x, self.y, &part);
+                part
+            }
+            None => {
+                let whole = self.inventory[self.item.id].clone();
+                self.inventory
+                    .release_item_at(self.x, self.y, &whole, self.items);
+                whole
+            }
+        };
+        self.dropped = Some(dropped);
     }
 
     fn announce(&self, update_tx: &std::sync::mpsc::Sender<GameUpdate>) {
+        let dropped = self.dropped.as_ref().unwrap_or(self.item);
+
         GameUpdate::send(
             Some(update_tx),
             GameUpdate::ItemAdded {
-                id: self.item.id,
+                id: dropped.id,
                 x: self.x,
                 y: self.y,
-                description: self.item.description(),
-                class: self.item.class(),
+                description: dropped.description(),
+                class: dropped.class(),
             },
         );
         GameUpdate::send(
             Some(update_tx),
             GameUpdate::InventoryUpdated(self.inventory.to_vec()),
-        )
+        );
+        announce_loot(
+            format!("You drop {}.", dropped.description().to_lowercase()),
+            update_tx,
+        );
     }
 }
 
@@ -119,6 +179,54 @@ impl<'a> EquipCommand<'a> {
 }
 
 impl<'a> CommandHandler for EquipCommand<'a> {
+    fn can_perform(&self) -> Result<(), Rejection> {
+        if !self.inventory.holds(self.item.id) {
+            return Err(Rejection::ItemNotHeld(self.item.id));
+        }
+
+        if ItemClassSpecifier::fitting_for(self.item, self.item_class_specifiers).is_empty() {
+            return Err(Rejection::CannotEquip(self.item.id));
+        }
+
+        // everything unmounted goes back into the inventory, where the item leaves a slot.
+        let conflicts = self
+            .player
+            .mounting_points
+            .conflicts_for(self.item, self.item_class_specifiers);
+        let free_slots = self
+            .inventory
+            .capacity()
+            .saturating_sub(self.inventory.count())
+            + 1;
+        if conflicts.len() > free_slots {
+            return Err(Rejection::InventoryFull(self.inventory.id()));
+        }
+
+        let requirement = self.item.item_type.requirement();
+        if requirement.is_met_by(self.player, Some(self.inventory)) {
+            return Ok(());
+        }
+
+        // a skill too low to meet the requirement is named, as the clearest reason there is.
+        let skills = &self.player.skills;
+        match requirement
+            .skill_levels()
+            .into_iter()
+            .find(|(skill, level)| !skills.meets(*skill, *level))
+        {
+            Some((skill, level)) => Err(Rejection::SkillTooLow {
+                description: self.item.raw_description(),
+                skill,
+                required: level,
+                current: skills.level(skill),
+            }),
+            None => Err(Rejection::RequirementNotMet(format!(
+                "equip {}",
+                self.item.raw_description()
+            ))),
+        }
+    }
+
     fn perform_execute(
         &mut self,
         _update_tx: Option<&GameUpdateSender>,
@@ -135,8 +243,7 @@ impl<'a> CommandHandler for EquipCommand<'a> {
             );
         }
         let player_mounting_points = self.player.mounting_points.clone();
-        player_mounting_points
-            .endorse(self.player, &self.items)
+        player_mounting_points.endorse(self.player, self.items)
     }
 
     fn announce(&self, update_tx: &std::sync::mpsc::Sender<GameUpdate>) {
@@ -150,11 +257,16 @@ impl<'a> CommandHandler for EquipCommand<'a> {
             Some(update_tx),
             GameUpdate::InventoryUpdated(self.inventory.to_vec()),
         );
+        announce_loot(
+            format!("You equip {}.", self.item.description().to_lowercase()),
+            update_tx,
+        );
     }
 }
 
 pub struct UnequipCommand<'a> {
     item_id: u64,
+    description: Option<String>, // of the item as it was equipped
     inventory: &'a mut Inventory,
     player: &'a mut Player,
     items: &'a mut ItemList,
@@ -169,6 +281,7 @@ impl<'a> UnequipCommand<'a> {
     ) -> UnequipCommand<'a> {
         UnequipCommand {
             item_id,
+            description: items.get_as_item(item_id).map(|item| item.description()),
             inventory,
             player,
             items,
@@ -177,6 +290,14 @@ impl<'a> UnequipCommand<'a> {
 }
 
 impl<'a> CommandHandler for UnequipCommand<'a> {
+    fn can_perform(&self) -> Result<(), Rejection> {
+        if self.player.mounting_points.to_vec().contains(&self.item_id) {
+            Ok(())
+        } else {
+            Err(Rejection::ItemNotEquipped(self.item_id))
+        }
+    }
+
     fn perform_execute(
         &mut self,
         _update_tx: Option<&GameUpdateSender>,
@@ -186,8 +307,10 @@ impl<'a> CommandHandler for UnequipCommand<'a> {
             .mounting_points
             .unmount_item_by_id(self.item_id, self.inventory, self.items);
 
-        self.player.clear_endorsements();
-        self.player.mounting_points.clone().endorse(&mut self.player,self.items);
+        self.player
+            .mounting_points
+            .clone()
+            .endorse(self.player, self.items);
     }
 
     fn announce(&self, update_tx: &GameUpdateSender) {
@@ -200,21 +323,36 @@ impl<'a> CommandHandler for UnequipCommand<'a> {
         GameUpdate::send(
             Some(update_tx),
             GameUpdate::InventoryUpdated(self.inventory.to_vec()),
-        )
+        );
+        if let Some(description) = &self.description {
+            announce_loot(
+                format!("You unequip {}.", description.to_lowercase()),
+                update_tx,
+            );
+        }
     }
 }
 
+fn announce_loot(message: String, update_tx: &GameUpdateSender) {
+    GameUpdate::send(
+        Some(update_tx),
+        GameUpdate::Message(MessageCategory::Loot, message),
+    );
+}
+
+/// moves a whole stack from one inventory to another, merging it into stacks of its type
+///   there where it can.  Leaves it where it is if there is no room for it.
 fn transfer_an_item<'a>(
     item: &'a Item,
     source_id: u64,
     destination_id: u64,
     inventories: &'a mut InventoryList,
     items: &'a mut ItemList,
-) -> bool {
+) {
     {
         let dest_inventory = &mut (inventories.get_mut(&destination_id).unwrap());
-        if dest_inventory.is_full() {
-            return false;
+        if !dest_inventory.can_accept(item) {
+            return;
         }
         dest_inventory.accept_stack_unmut(&item, items);
     }
@@ -223,7 +361,6 @@ fn transfer_an_item<'a>(
         let src_inventory = &mut (inventories.get_mut(&source_id).unwrap());
         src_inventory.release_item(&item.id);
     }
-    true
 }
 
 fn announce_transfer(
@@ -259,6 +396,7 @@ fn announce_transfer(
 #[derive(Debug)]
 pub struct TransferItemCommand<'a> {
     item: &'a Item,
+    quantity: u8,
     source_id: u64,
     destination_id: u64,
     inventories: &'a mut InventoryList,
@@ -266,8 +404,10 @@ pub struct TransferItemCommand<'a> {
 }
 
 impl<'a> TransferItemCommand<'a> {
+    /// moves quantity of the item's stack from one inventory to another.
     pub fn new(
         item: &'a Item,
+        quantity: u8,
         source_id: u64,
         destination_id: u64,
         inventories: &'a mut InventoryList,
@@ -275,6 +415,7 @@ impl<'a> TransferItemCommand<'a> {
     ) -> Self {
         Self {
             item,
+            quantity,
             source_id,
             destination_id,
             inventories,
@@ -284,18 +425,63 @@ impl<'a> TransferItemCommand<'a> {
 }
 
 impl<'a> CommandHandler for TransferItemCommand<'a> {
+    fn can_perform(&self) -> Result<(), Rejection> {
+        let held = self
+            .inventories
+            .get(&self.source_id)
+            .and_then(|source| source.items.get(&self.item.id))
+            .ok_or(Rejection::ItemNotHeld(self.item.id))?;
+
+        if self.quantity == 0 || self.quantity > held.quantity {
+            return Err(Rejection::InvalidQuantity(self.item.id, self.quantity));
+        }
+
+        if let Some(container_id) = self.items.container_of(self.item.id) {
+            if Inventory::encloses(
+                container_id,
+                self.destination_id,
+                self.items,
+                self.inventories,
+            ) {
+                return Err(Rejection::ContainerIntoItself(self.item.id));
+            }
+        }
+
+        let mut moving = held.clone();
+        moving.quantity = self.quantity;
+        if !self
+            .inventories
+            .get(&self.destination_id)
+            .is_some_and(|destination| destination.can_accept(&moving))
+        {
+            return Err(Rejection::InventoryFull(self.destination_id));
+        }
+        Ok(())
+    }
+
     fn perform_execute(
         &mut self,
         _update_tx: Option<&GameUpdateSender>,
         _command_tx: Option<&std::sync::mpsc::Sender<Command>>,
     ) {
-        transfer_an_item(
-            self.item,
-            self.source_id,
-            self.destination_id,
-            self.inventories,
-            self.items,
-        );
+        let source = self.inventories.get_mut(&self.source_id).unwrap();
+
+        match source.take_from_stack(self.item.id, self.quantity, self.items) {
+            Some(mut part) => {
+                let destination = self.inventories.get_mut(&self.destination_id).unwrap();
+                destination.accept_stack(&mut part, self.items);
+            }
+            None => {
+                let whole = source[self.item.id].clone();
+                transfer_an_item(
+                    &whole,
+                    self.source_id,
+                    self.destination_id,
+                    self.inventories,
+                    self.items,
+                );
+            }
+        }
     }
     fn announce(&self, update_tx: &std::sync::mpsc::Sender<GameUpdate>) {
         announce_transfer(
@@ -339,15 +525,28 @@ impl<'a> CommandHandler for TransferAllCommand<'a> {
         let src_inventory = self.inventories.get_mut(&self.source_id).unwrap();
 
         for (_id, item) in &src_inventory.items.clone() {
-            if !transfer_an_item(
+            if self
+                .items
+                .container_of(item.id)
+                .is_some_and(|container_id| {
+                    Inventory::encloses(
+                        container_id,
+                        self.destination_id,
+                        self.items,
+                        self.inventories,
+                    )
+                })
+            {
+                continue;
+            }
+            // a stack that does not fit stays behind, though later ones may still merge.
+            transfer_an_item(
                 &item,
                 self.source_id,
                 self.destination_id,
                 &mut self.inventories,
                 &mut self.items,
-            ) {
-                break;
-            };
+            );
         }
     }
     fn announce(&self, update_tx: &std::sync::mpsc::Sender<GameUpdate>) {
@@ -360,6 +559,198 @@ impl<'a> CommandHandler for TransferAllCommand<'a> {
     }
 }
 
+pub struct OpenContainerCommand<'a> {
+    item_id: u64,
+    player: &'a mut Player,
+    items: &'a ItemList,
+    inventories: &'a InventoryList,
+}
+
+impl<'a> OpenContainerCommand<'a> {
+    /// opens a container the player holds or wears as their external inventory.
+    pub fn new(
+        item_id: u64,
+        player: &'a mut Player,
+        items: &'a ItemList,
+        inventories: &'a InventoryList,
+    ) -> Self {
+        Self {
+            item_id,
+            player,
+            items,
+            inventories,
+        }
+    }
+
+    fn container(&self) -> Option<&'a Inventory> {
+        let inventories = self.inventories;
+        inventories.get(&self.items.container_of(self.item_id)?)
+    }
+}
+
+impl<'a> CommandHandler for OpenContainerCommand<'a> {
+    fn can_perform(&self) -> Result<(), Rejection> {
+        let held_by_player = match self.items.get(self.item_id) {
+            Some(ItemState::Stored(_, inventory_id))
+            | Some(ItemState::Equipped(_, inventory_id)) => {
+                inventory_id == self.player.inventory_id()
+            }
+            _ => false,
+        };
+        if !held_by_player {
+            return Err(Rejection::ItemNotHeld(self.item_id));
+        }
+
+        match self.container() {
+            Some(_) => Ok(()),
+            None => Err(Rejection::NotAContainer(self.item_id)),
+        }
+    }
+
+    fn perform_execute(
+        &mut self,
+        _update_tx: Option<&GameUpdateSender>,
+        _command_tx: Option<&std::sync::mpsc::Sender<Command>>,
+    ) {
+        self.player.external_inventory = self.container().map(|container| container.to_vec());
+    }
+
+    fn announce(&self, update_tx: &std::sync::mpsc::Sender<GameUpdate>) {
+        if let Some(container) = self.container() {
+            GameUpdate::send(
+                Some(update_tx),
+                GameUpdate::ExternalInventoryOpened(container.to_vec(), container.id()),
+            );
+        }
+    }
+}
+
+/// announces the inventory as the player's own or as the external inventory they have open.
+fn announce_inventory(
+    inventory: &Inventory,
+    player_inventory_id: u64,
+    update_tx: &GameUpdateSender,
+) {
+    let update = if inventory.id() == player_inventory_id {
+        GameUpdate::InventoryUpdated(inventory.to_vec())
+    } else {
+        GameUpdate::ExternalInventoryUpdated(inventory.to_vec())
+    };
+    GameUpdate::send(Some(update_tx), update);
+}
+
+pub struct SplitStackCommand<'a> {
+    item_id: u64,
+    quantity: u8,
+    inventory: &'a mut Inventory,
+    items: &'a mut ItemList,
+    player_inventory_id: u64,
+}
+
+impl<'a> SplitStackCommand<'a> {
+    /// splits quantity items off a stack into a new stack in the same inventory.
+    pub fn new(
+        item_id: u64,
+        quantity: u8,
+        inventory: &'a mut Inventory,
+        items: &'a mut ItemList,
+        player_inventory_id: u64,
+    ) -> Self {
+        Self {
+            item_id,
+            quantity,
+            inventory,
+            items,
+            player_inventory_id,
+        }
+    }
+}
+
+impl<'a> CommandHandler for SplitStackCommand<'a> {
+    fn can_perform(&self) -> Result<(), Rejection> {
+        let held = self
+            .inventory
+            .items
+            .get(&self.item_id)
+            .ok_or(Rejection::ItemNotHeld(self.item_id))?;
+
+        if self.quantity == 0 || self.quantity >= held.quantity {
+            return Err(Rejection::InvalidQuantity(self.item_id, self.quantity));
+        }
+        if self.inventory.is_full() {
+            return Err(Rejection::InventoryFull(self.inventory.id()));
+        }
+        Ok(())
+    }
+
+    fn perform_execute(
+        &mut self,
+        _update_tx: Option<&GameUpdateSender>,
+        _command_tx: Option<&std::sync::mpsc::Sender<Command>>,
+    ) {
+        self.inventory
+            .split_stack(self.item_id, self.quantity, self.items);
+    }
+
+    fn announce(&self, update_tx: &std::sync::mpsc::Sender<GameUpdate>) {
+        announce_inventory(self.inventory, self.player_inventory_id, update_tx);
+    }
+}
+
+pub struct MergeStacksCommand<'a> {
+    item_id: u64,
+    into_id: u64,
+    inventory: &'a mut Inventory,
+    items: &'a mut ItemList,
+    player_inventory_id: u64,
+}
+
+impl<'a> MergeStacksCommand<'a> {
+    /// moves as much of one stack as fits into another stack of the same type.
+    pub fn new(
+        item_id: u64,
+        into_id: u64,
+        inventory: &'a mut Inventory,
+        items: &'a mut ItemList,
+        player_inventory_id: u64,
+    ) -> Self {
+        Self {
+            item_id,
+            into_id,
+            inventory,
+            items,
+            player_inventory_id,
+        }
+    }
+}
+
+impl<'a> CommandHandler for MergeStacksCommand<'a> {
+    fn can_perform(&self) -> Result<(), Rejection> {
+        for id in [self.item_id, self.into_id] {
+            if !self.inventory.holds(id) {
+                return Err(Rejection::ItemNotHeld(id));
+            }
+        }
+        if !self.inventory.can_merge(self.item_id, self.into_id) {
+            return Err(Rejection::CannotMerge(self.item_id, self.into_id));
+        }
+        Ok(())
+    }
+
+    fn perform_execute(
+        &mut self,
+        _update_tx: Option<&GameUpdateSender>,
+        _command_tx: Option<&std::sync::mpsc::Sender<Command>>,
+    ) {
+        self.inventory
+            .merge_stacks(self.item_id, self.into_id, self.items);
+    }
+
+    fn announce(&self, update_tx: &std::sync::mpsc::Sender<GameUpdate>) {
+        announce_inventory(self.inventory, self.player_inventory_id, update_tx);
+    }
+}
+
 #[cfg(test)]
 mod pickup_command {
     use super::*;
@@ -428,6 +819,32 @@ mod pickup_command {
             ))
         );
     }
+
+    #[test]
+    fn it_is_rejected_when_the_item_is_not_on_the_ground() {
+        let mut inventory = Inventory::new(1);
+        let mut items = ItemList::new(None);
+
+        let mut command = PickupCommand::new(1776, &mut inventory, &mut items);
+
+        assert_eq!(command.can_perform(), Err(Rejection::ItemNotBundled(1776)));
+        command.execute(None, None);
+        assert!(inventory.is_empty());
+    }
+
+    #[test]
+    fn it_is_rejected_when_the_inventory_is_full() {
+        let mut inventory = Inventory::new(1);
+        let mut items = ItemList::new(None);
+        for id in 0..27 {
+            inventory.force_accept(&Item::new(id, ItemType::new(ItemClass::Tool, "Shovel"), 1));
+        }
+        items.add_new_item_to_bundle_at(10, 15, 1776, ItemClass::Potion, "A Red Bubbling Potion");
+
+        let command = PickupCommand::new(1776, &mut inventory, &mut items);
+
+        assert_eq!(command.can_perform(), Err(Rejection::InventoryFull(1)));
+    }
 }
 
 #[cfg(test)]
@@ -445,7 +862,7 @@ mod drop_command {
         );
         inventory.accept_stack(&mut item, &mut items);
 
-        let mut command = DropCommand::new(&item, 10, 15, &mut inventory, &mut items);
+        let mut command = DropCommand::new(&item, 1, 10, 15, &mut inventory, &mut items);
 
         let (sender, receiver) = std::sync::mpsc::channel();
 
@@ -487,7 +904,7 @@ mod drop_command {
         );
         inventory.accept_stack(&mut item, &mut items);
 
-        let mut command = DropCommand::new(&item, 10, 15, &mut inventory, &mut items);
+        let mut command = DropCommand::new(&item, 1, 10, 15, &mut inventory, &mut items);
 
         command.execute(None, None);
 
@@ -509,12 +926,188 @@ mod drop_command {
         );
         inventory.accept_stack(&mut item, &mut items);
 
-        let mut command = DropCommand::new(&item, 10, 15, &mut inventory, &mut items);
+        let mut command = DropCommand::new(&item, 1, 10, 15, &mut inventory, &mut items);
 
         command.execute(None, None);
 
         assert_eq!(inventory.count(), 0);
     }
+
+    #[test]
+    fn it_is_rejected_when_the_item_is_not_held() {
+        let mut inventory = Inventory::new(1);
+        let mut items = ItemList::new(None);
+        let item = Item::new(
+            1776,
+            ItemType::new(ItemClass::Potion, "A Red Bubbling Potion"),
+            1,
+        );
+
+        let mut command = DropCommand::new(&item, 1, 10, 15, &mut inventory, &mut items);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        command.execute(Some(&sender), None);
+
+        match receiver.try_recv() {
+            Ok(CommandRejected(Rejection::ItemNotHeld(1776))) => {}
+            other => panic!("Unexpected response: {:?}", other),
+        }
+        assert!(items.is_empty());
+    }
+
+    #[test]
+    fn dropping_part_of_a_stack_leaves_the_rest_held() {
+        let mut inventory = Inventory::new(1);
+        let mut items = ItemList::new(None);
+        let item = Item::new(1776, ItemType::new(ItemClass::Food, "Apple"), 10);
+        inventory.accept(&item, &mut items);
+
+        let mut command = DropCommand::new(&item, 4, 10, 15, &mut inventory, &mut items);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        command.execute(Some(&sender), None);
+
+        let dropped_id = match receiver.try_recv() {
+            Ok(ItemAdded {
+                id, description, ..
+            }) => {
+                assert_eq!(description, "4 Apples");
+                id
+            }
+            other => panic!("Unexpected response: {:?}", other),
+        };
+        assert_ne!(dropped_id, 1776);
+        assert_eq!(inventory[1776].quantity, 6);
+        assert_eq!(
+            items.get(dropped_id),
+            Some(ItemState::Bundle(
+                Item::new(dropped_id, item.item_type.clone(), 4),
+                10,
+                15
+            ))
+        );
+    }
+
+    #[test]
+    fn dropping_more_than_is_held_is_rejected() {
+        let mut inventory = Inventory::new(1);
+        let mut items = ItemList::new(None);
+        let item = Item::new(1776, ItemType::new(ItemClass::Food, "Apple"), 10);
+        inventory.accept(&item, &mut items);
+
+        let command = DropCommand::new(&item, 11, 10, 15, &mut inventory, &mut items);
+
+        assert_eq!(
+            command.can_perform(),
+            Err(Rejection::InvalidQuantity(1776, 11))
+        );
+    }
+}
+
+#[cfg(test)]
+mod split_and_merge_stacks {
+    use super::*;
+
+    fn inventory_of_apples(quantities: &[u8], items: &mut ItemList) -> Inventory {
+        let mut inventory = Inventory::new(1);
+        for (index, quantity) in quantities.iter().enumerate() {
+            let item = Item::new(
+                1776 + index as u64,
+                ItemType::new(ItemClass::Food, "Apple"),
+                *quantity,
+            );
+            inventory.accept(&item, items);
+        }
+        inventory
+    }
+
+    fn total(inventory: &Inventory) -> u32 {
+        inventory.to_vec().iter().map(|i| i.quantity as u32).sum()
+    }
+
+    #[test]
+    fn splitting_makes_a_new_stack_in_the_same_inventory() {
+        let mut items = ItemList::new(None);
+        let mut inventory = inventory_of_apples(&[10], &mut items);
+
+        let mut command = SplitStackCommand::new(1776, 3, &mut inventory, &mut items, 1);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        command.execute(Some(&sender), None);
+
+        let mut quantities: Vec<u8> = inventory.to_vec().iter().map(|i| i.quantity).collect();
+        quantities.sort();
+        assert_eq!(quantities, vec![3, 7]);
+        assert!(matches!(receiver.try_recv(), Ok(InventoryUpdated(_))));
+    }
+
+    #[test]
+    fn splitting_a_stack_in_an_external_inventory_updates_that_inventory() {
+        let mut items = ItemList::new(None);
+        let mut inventory = inventory_of_apples(&[10], &mut items);
+
+        let mut command = SplitStackCommand::new(1776, 3, &mut inventory, &mut items, 2);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        command.execute(Some(&sender), None);
+
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(ExternalInventoryUpdated(_))
+        ));
+    }
+
+    #[test]
+    fn a_stack_cannot_be_split_into_nothing_or_all_of_itself() {
+        let mut items = ItemList::new(None);
+        let mut inventory = inventory_of_apples(&[10], &mut items);
+
+        for quantity in [0, 10] {
+            let command = SplitStackCommand::new(1776, quantity, &mut inventory, &mut items, 1);
+            assert_eq!(
+                command.can_perform(),
+                Err(Rejection::InvalidQuantity(1776, quantity))
+            );
+        }
+    }
+
+    #[test]
+    fn merging_fills_the_target_and_keeps_the_remainder() {
+        let mut items = ItemList::new(None);
+        let mut inventory = inventory_of_apples(&[40, 60], &mut items);
+
+        MergeStacksCommand::new(1776, 1777, &mut inventory, &mut items, 1).execute(None, None);
+
+        assert_eq!(inventory[1777].quantity, 64);
+        assert_eq!(inventory[1776].quantity, 36);
+        assert_eq!(total(&inventory), 100);
+        assert_eq!(items.get_as_item(1776).unwrap().quantity, 36);
+    }
+
+    #[test]
+    fn merging_an_entire_stack_removes_it() {
+        let mut items = ItemList::new(None);
+        let mut inventory = inventory_of_apples(&[4, 6], &mut items);
+
+        MergeStacksCommand::new(1776, 1777, &mut inventory, &mut items, 1).execute(None, None);
+
+        assert_eq!(inventory[1777].quantity, 10);
+        assert!(!inventory.holds(1776));
+        assert!(!items.holds(1776));
+    }
+
+    #[test]
+    fn different_items_do_not_merge() {
+        let mut items = ItemList::new(None);
+        let mut inventory = inventory_of_apples(&[4], &mut items);
+        inventory.accept(
+            &Item::new(1800, ItemType::new(ItemClass::Food, "Pear"), 1),
+            &mut items,
+        );
+
+        let command = MergeStacksCommand::new(1776, 1800, &mut inventory, &mut items, 1);
+
+        assert_eq!(
+            command.can_perform(),
+            Err(Rejection::CannotMerge(1776, 1800))
+        );
+    }
 }
 
 #[cfg(test)]
@@ -539,6 +1132,37 @@ mod support {
 
         item
     }
+
+    #[allow(dead_code)]
+    pub fn endorsing_test_item(
+        description: &str,
+        class: ItemClass,
+        endorsement: &str,
+        id: u64,
+        inventory: &mut Inventory,
+        items: &mut ItemList,
+    ) -> Item {
+        let mut item_type = ItemType::new(class, description);
+        item_type.add_endorsement(endorsement);
+
+        let mut item = Item::new(id, item_type, 1);
+        items[id] = ItemState::Stored(item.clone(), inventory.id());
+        inventory.accept_stack(&mut item, items);
+
+        item
+    }
+
+    #[allow(dead_code)]
+    pub fn equip(
+        item: &Item,
+        player: &mut Player,
+        inventory: &mut Inventory,
+        items: &mut ItemList,
+    ) {
+        let item_class_specifiers = ItemClassSpecifier::initialize();
+        EquipCommand::new(item, player, &item_class_specifiers, inventory, items)
+            .execute(None, None);
+    }
 }
 
 #[cfg(test)]
@@ -582,12 +1206,12 @@ mod unequip_command {
     }
 
     #[test]
-    fn announce_sends_two_messages() {
+    fn announce_sends_three_messages() {
         let mut inventory = Inventory::new(1);
         let mut items = ItemList::new(None);
 
         let item = test_item(
-            "a fuzzy hat",
+            "fuzzy hat",
             ItemClass::Headwear,
             422,
             &mut inventory,
@@ -595,6 +1219,7 @@ mod unequip_command {
         );
 
         let mut player = Player::new();
+        equip(&item, &mut player, &mut inventory, &mut items);
 
         let mut subject = UnequipCommand::new(item.id, &mut inventory, &mut player, &mut items);
 
@@ -612,5 +1237,211 @@ mod unequip_command {
             }
             _ => panic!("Unexpected response"),
         }
+
+        match receiver.try_recv() {
+            Ok(GameUpdate::Message(MessageCategory::Loot, message)) => {
+                assert_eq!(message, "You unequip a fuzzy hat.")
+            }
+            _ => panic!("Unexpected response"),
+        }
+    }
+
+    #[test]
+    fn unequipping_an_endorsing_item_revokes_its_endorsement() {
+        let mut inventory = Inventory::new(1);
+        let mut items = ItemList::new(None);
+        let mut player = Player::new();
+        let basket = endorsing_test_item(
+            "Reed Basket",
+            ItemClass::Tool,
+            ":can_pick_apples",
+            11,
+            &mut inventory,
+            &mut items,
+        );
+
+        equip(&basket, &mut player, &mut inventory, &mut items);
+        assert!(player.is_endorsed_with(":can_pick_apples"));
+
+        UnequipCommand::new(basket.id, &mut inventory, &mut player, &mut items).execute(None, None);
+
+        assert!(!player.is_endorsed_with(":can_pick_apples"));
+        assert!(player.is_endorsed_with(":newb"));
+    }
+
+    #[test]
+    fn unequipping_keeps_an_endorsement_another_item_still_grants() {
+        let mut inventory = Inventory::new(1);
+        let mut items = ItemList::new(None);
+        let mut player = Player::new();
+        let basket = endorsing_test_item(
+            "Reed Basket",
+            ItemClass::Tool,
+            ":can_pick_apples",
+            11,
+            &mut inventory,
+            &mut items,
+        );
+        let gloves = endorsing_test_item(
+            "Orchard Gloves",
+            ItemClass::Gloves,
+            ":can_pick_apples",
+            12,
+            &mut inventory,
+            &mut items,
+        );
+
+        equip(&basket, &mut player, &mut inventory, &mut items);
+        equip(&gloves, &mut player, &mut inventory, &mut items);
+
+        UnequipCommand::new(basket.id, &mut inventory, &mut player, &mut items).execute(None, None);
+
+        assert!(player.is_endorsed_with(":can_pick_apples"));
+    }
+
+    #[test]
+    fn unequipping_keeps_an_endorsement_another_source_still_grants() {
+        let mut inventory = Inventory::new(1);
+        let mut items = ItemList::new(None);
+        let mut player = Player::new();
+        let basket = endorsing_test_item(
+            "Reed Basket",
+            ItemClass::Tool,
+            ":can_pick_apples",
+            11,
+            &mut inventory,
+            &mut items,
+        );
+        player.endorse_from(EndorsementSource::Skills, ":can_pick_apples");
+
+        equip(&basket, &mut player, &mut inventory, &mut items);
+        UnequipCommand::new(basket.id, &mut inventory, &mut player, &mut items).execute(None, None);
+
+        assert!(player.is_endorsed_with(":can_pick_apples"));
+    }
+}
+
+#[cfg(test)]
+mod equip_command {
+    use super::*;
+
+    fn sword_requiring_combat(level: u8, inventory: &mut Inventory, items: &mut ItemList) -> Item {
+        let mut item_type = ItemType::new(ItemClass::BladeWeapon, "Sharp Short Sword");
+        item_type.require_skill(Skill::Combat, level);
+
+        let mut item = Item::new(77, item_type, 1);
+        items[item.id] = ItemState::Stored(item.clone(), inventory.id());
+        inventory.accept_stack(&mut item, items);
+
+        item
+    }
+
+    #[test]
+    fn it_refuses_items_whose_required_level_is_not_met() {
+        let mut inventory = Inventory::new(1);
+        let mut items = ItemList::new(None);
+        let item_class_specifiers = ItemClassSpecifier::initialize();
+        let item = sword_requiring_combat(3, &mut inventory, &mut items);
+        let mut player = Player::new();
+
+        let mut subject = EquipCommand::new(
+            &item,
+            &mut player,
+            &item_class_specifiers,
+            &mut inventory,
+            &mut items,
+        );
+        let (sender, receiver) = std::sync::mpsc::channel();
+        subject.execute(Some(&sender), None);
+
+        match receiver.try_recv() {
+            Ok(GameUpdate::CommandRejected(rejection)) => assert_eq!(
+                rejection.to_string(),
+                "Sharp Short Sword requires level 3 Combat; your Combat is level 1."
+            ),
+            other => panic!("Unexpected response: {:?}", other),
+        }
+        assert!(receiver.try_recv().is_err());
+        assert!(player.mounting_points.is_empty(&MountingPoint::OnHand));
+        assert_eq!(items.get(item.id), Some(ItemState::Stored(item, 1)));
+    }
+
+    #[test]
+    fn it_equips_items_whose_required_level_is_met() {
+        let mut inventory = Inventory::new(1);
+        let mut items = ItemList::new(None);
+        let item_class_specifiers = ItemClassSpecifier::initialize();
+        let item = sword_requiring_combat(3, &mut inventory, &mut items);
+        let mut player = Player::new();
+        player
+            .skills
+            .gain_experience(Skill::Combat, Skill::experience_for_level(3));
+
+        let mut subject = EquipCommand::new(
+            &item,
+            &mut player,
+            &item_class_specifiers,
+            &mut inventory,
+            &mut items,
+        );
+        assert_eq!(subject.can_perform(), Ok(()));
+        subject.execute(None, None);
+
+        assert!(!player.mounting_points.is_empty(&MountingPoint::OnHand));
+    }
+
+    #[test]
+    fn it_refuses_items_that_cannot_be_mounted() {
+        let mut inventory = Inventory::new(1);
+        let mut items = ItemList::new(None);
+        let item_class_specifiers = ItemClassSpecifier::initialize();
+        let item = support::test_item("Apple", ItemClass::Food, 78, &mut inventory, &mut items);
+        let mut player = Player::new();
+
+        let subject = EquipCommand::new(
+            &item,
+            &mut player,
+            &item_class_specifiers,
+            &mut inventory,
+            &mut items,
+        );
+
+        assert_eq!(subject.can_perform(), Err(Rejection::CannotEquip(78)));
+    }
+
+    #[test]
+    fn it_refuses_to_unmount_more_than_the_inventory_can_hold() {
+        use support::test_item;
+
+        let mut inventory = Inventory::with_capacity(1, 2);
+        let mut items = ItemList::new(None);
+        let item_class_specifiers = ItemClassSpecifier::initialize();
+        let mut player = Player::new();
+
+        for (id, class) in [(80, ItemClass::BladeWeapon), (81, ItemClass::Shield)] {
+            let item = test_item("held", class, id, &mut inventory, &mut items);
+            player
+                .mounting_points
+                .mount(&item, &item_class_specifiers, &mut inventory, &mut items);
+        }
+        test_item("Hatchet", ItemClass::Tool, 82, &mut inventory, &mut items);
+        let mut item_type = ItemType::new(ItemClass::BladeWeapon, "Two-Handed Sword");
+        item_type.set_fitting(Fitting::on(&[
+            MountingPoint::OnHand,
+            MountingPoint::OffHand,
+        ]));
+        let mut item = Item::new(83, item_type, 1);
+        items[item.id] = ItemState::Stored(item.clone(), inventory.id());
+        inventory.accept_stack(&mut item, &mut items);
+
+        let subject = EquipCommand::new(
+            &item,
+            &mut player,
+            &item_class_specifiers,
+            &mut inventory,
+            &mut items,
+        );
+
+        assert_eq!(subject.can_perform(), Err(Rejection::InventoryFull(1)));
     }
 }
@@ -4,16 +4,24 @@ use std::boxed::Box;
 use std::ops::DerefMut;
 
 pub mod move_command;
-pub use move_command::{ChangeFacingCommand, MoveCommand};
+pub use move_command::{
+    ChangeFacingCommand, MoveCommand, StepCommand, TeleportCommand, Teleportee,
+};
 pub mod door_commands;
-pub use door_commands::{CloseDoorCommand, OpenDoorCommand};
+pub use door_commands::{doorway_is_occupied, CloseDoorCommand, OpenDoorCommand};
 pub mod item_commands;
 pub use item_commands::{
-    DropCommand, EquipCommand, PickupCommand, TransferAllCommand, TransferItemCommand,
-    UnequipCommand,
+    DropCommand, EquipCommand, MergeStacksCommand, OpenContainerCommand, PickupCommand,
+    SplitStackCommand, TransferAllCommand, TransferItemCommand, UnequipCommand,
 };
+pub mod character_commands;
+pub use character_commands::KillCommand;
+pub mod spawn_commands;
+pub use spawn_commands::RepopulateCommand;
 pub mod facility_commands;
 pub use facility_commands::{ActivateAppleTreeCommand, OpenChestCommand};
+pub mod rejection;
+pub use rejection::Rejection;
 
 pub type GameUpdateSender = std::sync::mpsc::Sender<GameUpdate>;
 pub type CommandSender = std::sync::mpsc::Sender<Command>;
@@ -36,14 +44,30 @@ impl Command {
         map: &mut TileMap,
         obstacles: &mut BlockingMap,
         facilities: &mut FacilityList,
+        items: &ItemList,
         inventories: &mut InventoryList,
+        scheduler: &mut Scheduler,
         update_tx: Option<&GameUpdateSender>,
         command_tx: Option<&CommandSender>,
-    ) {
+    ) -> Result<(), GameError> {
+        // a step taken while one is under way follows on from it.
+        if mode != MoveCommandMode::Use && player.moving {
+            player.queued_step = Some((direction, mode));
+            return Ok(());
+        }
+
         let (dx, dy) = get_deltas_from_direction(direction);
 
-        let command = if mode == MoveCommandMode::Normal || mode == MoveCommandMode::Sneak {
-            attempt_to_enter(direction, dx, dy, player, obstacles)
+        // with bump to interact, walking into something that can be used uses it.
+        let bumps_into_something = mode == MoveCommandMode::Normal
+            && player.bump_to_interact
+            && obstacles.is_in_bounds(player.x + dx, player.y + dy)
+            && obstacles.is_blocked_at(player.x + dx, player.y + dy);
+
+        let command = if mode != MoveCommandMode::Use && !bumps_into_something {
+            attempt_to_step(
+                direction, dx, dy, mode, player, map, obstacles, items, scheduler,
+            )
         } else {
             attempt_to_use(
                 direction,
@@ -53,12 +77,34 @@ impl Command {
                 map,
                 obstacles,
                 facilities,
+                items,
                 inventories,
-            )
+                scheduler,
+            )?
         };
         if let Some(mut val) = command {
             val.deref_mut().execute(update_tx, command_tx)
         }
+        Ok(())
+    }
+
+    /// moves the player onto the tile at the end of the step under way.
+    ///   Should something have moved into the way, the player only turns to face it.
+    pub fn complete_step(
+        direction: Direction,
+        player: &mut Player,
+        obstacles: &mut BlockingMap,
+        update_tx: Option<&GameUpdateSender>,
+        command_tx: Option<&CommandSender>,
+    ) {
+        let (dx, dy) = get_deltas_from_direction(direction);
+        player.activity = None;
+        player.moving = false;
+
+        if let Some(mut command) = attempt_to_enter(direction, dx, dy, player, obstacles) {
+            command.deref_mut().execute(update_tx, command_tx)
+        }
+        GameUpdate::send(update_tx, MovementCompleted(player.id));
     }
 
     pub fn spawn_item(
@@ -69,12 +115,12 @@ impl Command {
         items: &mut ItemList,
         _update_tx: Option<&std::sync::mpsc::Sender<GameUpdate>>,
         _command_tx: Option<&CommandSender>,
-    ) {
-        let inventory = inventories
-            .get_mut(&inventory_id)
-            .expect("unable to find inventory");
+    ) -> Result<Item, GameError> {
+        let inventory = find_inventory(inventory_id, inventories)?;
+        let item = inventory.spawn_item(class, description, items);
 
-        inventory.spawn_item(class, description, items);
+        Inventory::provide_for_containers(items, inventories);
+        Ok(item)
     }
 
     pub fn pickup_item(
@@ -84,45 +130,58 @@ impl Command {
         inventories: &mut InventoryList,
         update_tx: Option<&std::sync::mpsc::Sender<GameUpdate>>,
         command_tx: Option<&CommandSender>,
-    ) {
+    ) -> Result<(), GameError> {
         let x = player.x;
         let y = player.y;
 
-        let item = items.find_nth_at(x, y, (item_index - 1) as i32).cloned();
+        let item = items
+            .find_nth_at(x, y, item_index as i32 - 1)
+            .cloned()
+            .ok_or(GameError::NothingToTake(item_index))?;
 
         match item {
-            None => println!("item not found"),
-            Some(ItemState::Bundle(item, _x, _y)) => {
-                let inventory = &mut inventories
-                    .get_mut(&player.inventory_id())
-                    .expect("unable to find inventory");
+            ItemState::Bundle(item, _x, _y) => {
+                let inventory = find_inventory(player.inventory_id(), inventories)?;
                 let mut command = PickupCommand::new(item.id, inventory, items);
                 command.execute(update_tx, command_tx);
+                Ok(())
             }
-            Some(_) => panic!("unbundled item found"),
+            other => Err(GameError::ItemNotBundled(
+                ItemState::extract_item(&other).id,
+            )),
         }
     }
+
     pub fn drop_item(
         item_index: u64,
+        quantity: u8,
         player: &mut Player,
         items: &mut ItemList,
         inventories: &mut InventoryList,
         update_tx: Option<&std::sync::mpsc::Sender<GameUpdate>>,
         command_tx: Option<&CommandSender>,
-    ) {
-        let item = &items[item_index].clone();
+    ) -> Result<(), GameError> {
+        let item = items
+            .get(item_index)
+            .ok_or(GameError::ItemNotFound(item_index))?;
         let x = player.x;
         let y = player.y;
         match item {
-            ItemState::Bundle(_, _, _) => panic!("can't drop something not held"),
+            ItemState::Bundle(_, _, _) => Err(GameError::ItemNotHeld(item_index)),
             ItemState::Stored(item, inventory_id) => {
-                let inventory = &mut inventories
-                    .get_mut(&inventory_id)
-                    .expect("unable to find inventory");
-                let mut command = DropCommand::new(item, x, y, inventory, items);
+                let inventory = find_inventory(inventory_id, inventories)?;
+                let mut command = DropCommand::new(&item, quantity, x, y, inventory, items);
                 command.execute(update_tx, command_tx);
+
+                // a container left on the ground can no longer be looked into.
+                if items.container_of(item.id).is_some() && player.external_inventory.is_some() {
+                    Command::close_external_inventory(player, update_tx);
+                }
+                Ok(())
+            }
+            ItemState::Equipped(_item_id, _inventory_id) => {
+                Err(GameError::ItemAlreadyEquipped(item_index))
             }
-            ItemState::Equipped(_item_id, _inventory_id) => panic!("can't drop an equipped item!"),
         }
     }
 
@@ -134,25 +193,26 @@ impl Command {
         inventories: &mut InventoryList,
         update_tx: Option<&std::sync::mpsc::Sender<GameUpdate>>,
         command_tx: Option<&CommandSender>,
-    ) {
-        let item = &items.clone()[item_index];
+    ) -> Result<(), GameError> {
+        let item = items
+            .get(item_index)
+            .ok_or(GameError::ItemNotFound(item_index))?;
         match item {
-            ItemState::Bundle(_, _, _) => panic!("can't equip something not held"),
+            ItemState::Bundle(_, _, _) => Err(GameError::ItemNotHeld(item_index)),
             ItemState::Stored(item, inventory_id) => {
-                let command = process_equip_item(
-                    item,
+                let mut command = process_equip_item(
+                    &item,
                     player,
                     inventory_id,
                     item_class_specifiers,
                     items,
                     inventories,
-                );
-                if let Some(mut cmd) = command {
-                    cmd.execute(update_tx, command_tx);
-                }
+                )?;
+                command.execute(update_tx, command_tx);
+                Ok(())
             }
             ItemState::Equipped(_item_id, _inventory_id) => {
-                panic!("cant re-equip an already equipped item.")
+                Err(GameError::ItemAlreadyEquipped(item_index))
             }
         }
     }
@@ -164,35 +224,81 @@ impl Command {
         inventories: &mut InventoryList,
         update_tx: Option<&GameUpdateSender>,
         command_tx: Option<&CommandSender>,
-    ) {
-        let item_state = &items.clone()[item_index];
+    ) -> Result<(), GameError> {
+        let item_state = items
+            .get(item_index)
+            .ok_or(GameError::ItemNotFound(item_index))?;
         match item_state {
-            ItemState::Bundle(_, _, _) => panic!("can't unequip something not held"),
-            ItemState::Stored(_, _) => panic!("cant unequip an already unequipped item."),
+            ItemState::Bundle(_, _, _) => Err(GameError::ItemNotHeld(item_index)),
+            ItemState::Stored(_, _) => Err(GameError::ItemNotEquipped(item_index)),
             ItemState::Equipped(item, inventory_id) => {
-                let command = process_unequip_item(item, inventory_id, player, items, inventories);
-                if let Some(mut cmd) = command {
-                    cmd.execute(update_tx, command_tx);
-                }
+                let mut command =
+                    process_unequip_item(&item, inventory_id, player, items, inventories)?;
+                command.execute(update_tx, command_tx);
+                Ok(())
             }
         }
     }
 
     pub fn transfer_item(
         item_id: u64,
+        quantity: u8,
         source_id: u64,
         destination_id: u64,
         items: &mut ItemList,
         inventories: &mut InventoryList,
         update_tx: Option<&GameUpdateSender>,
         command_tx: Option<&CommandSender>,
-    ) {
-        let item_state = &items[item_id];
-        let item = ItemState::extract_item(item_state);
-
-        let mut command =
-            TransferItemCommand::new(&item, source_id, destination_id, inventories, items);
+    ) -> Result<(), GameError> {
+        let item_state = items.get(item_id).ok_or(GameError::ItemNotFound(item_id))?;
+        let item = ItemState::extract_item(&item_state);
+        find_inventory(source_id, inventories)?;
+        find_inventory(destination_id, inventories)?;
+
+        let mut command = TransferItemCommand::new(
+            &item,
+            quantity,
+            source_id,
+            destination_id,
+            inventories,
+            items,
+        );
         command.execute(update_tx, command_tx);
+        Ok(())
+    }
+
+    pub fn split_stack(
+        item_id: u64,
+        quantity: u8,
+        player: &Player,
+        items: &mut ItemList,
+        inventories: &mut InventoryList,
+        update_tx: Option<&GameUpdateSender>,
+        command_tx: Option<&CommandSender>,
+    ) -> Result<(), GameError> {
+        let inventory_id = stored_in(item_id, items)?;
+        let inventory = find_inventory(inventory_id, inventories)?;
+
+        SplitStackCommand::new(item_id, quantity, inventory, items, player.inventory_id())
+            .execute(update_tx, command_tx);
+        Ok(())
+    }
+
+    pub fn merge_stacks(
+        item_id: u64,
+        into_id: u64,
+        player: &Player,
+        items: &mut ItemList,
+        inventories: &mut InventoryList,
+        update_tx: Option<&GameUpdateSender>,
+        command_tx: Option<&CommandSender>,
+    ) -> Result<(), GameError> {
+        let inventory_id = stored_in(item_id, items)?;
+        let inventory = find_inventory(inventory_id, inventories)?;
+
+        MergeStacksCommand::new(item_id, into_id, inventory, items, player.inventory_id())
+            .execute(update_tx, command_tx);
+        Ok(())
     }
 
     pub fn transfer_all_items(
@@ -201,36 +307,174 @@ impl Command {
         items: &mut ItemList,
         inventories: &mut InventoryList,
         update_tx: Option<&GameUpdateSender>,
-        _command_tx: Option<&CommandSender>,
-    ) {
-        let inventories = inventories;
+        command_tx: Option<&CommandSender>,
+    ) -> Result<(), GameError> {
+        find_inventory(source_id, inventories)?;
+        find_inventory(destination_id, inventories)?;
 
         let mut command = TransferAllCommand::new(source_id, destination_id, inventories, items);
-        command.execute(update_tx, None);
+        command.execute(update_tx, command_tx);
+        Ok(())
+    }
+
+    pub fn teleport_character(
+        id: u64,
+        x: i32,
+        y: i32,
+        player: &mut Player,
+        characters: &mut CharacterList,
+        obstacles: &mut BlockingMap,
+        update_tx: Option<&GameUpdateSender>,
+        command_tx: Option<&CommandSender>,
+    ) -> Result<(), GameError> {
+        let character = if id == player.id {
+            Teleportee::Player(player)
+        } else {
+            Teleportee::Character(
+                characters
+                    .get_mut(id)
+                    .ok_or(GameError::CharacterNotFound(id))?,
+            )
+        };
+
+        TeleportCommand::new(character, x, y, obstacles).execute(update_tx, command_tx);
+        Ok(())
     }
 
-    pub fn close_external_inventory(update_tx: Option<&std::sync::mpsc::Sender<GameUpdate>>) {
+    pub fn kill_character(
+        id: u64,
+        player: &mut Player,
+        characters: &mut CharacterList,
+        obstacles: &mut BlockingMap,
+        items: &mut ItemList,
+        inventories: &mut InventoryList,
+        update_tx: Option<&GameUpdateSender>,
+        command_tx: Option<&CommandSender>,
+    ) -> Result<(), GameError> {
+        if characters.get(id).is_none() {
+            return Err(GameError::CharacterNotFound(id));
+        }
+
+        KillCommand::new(id, player, characters, obstacles, items).execute(update_tx, command_tx);
+        Inventory::provide_for_containers(items, inventories);
+        Ok(())
+    }
+
+    /// closes a door whose time to close has come, waiting again if its doorway is occupied.
+    pub fn close_door(
+        door_id: u64,
+        map: &mut TileMap,
+        obstacles: &mut BlockingMap,
+        items: &ItemList,
+        scheduler: &mut Scheduler,
+        update_tx: Option<&GameUpdateSender>,
+        command_tx: Option<&CommandSender>,
+    ) -> Result<(), GameError> {
+        let (x, y) = match map.doors.get(door_id) {
+            Some(door) => (door.x, door.y),
+            None => return Err(GameError::DoorNotFound(door_id)),
+        };
+        let is_open = map.at(x, y) == tile_map::Tile::OpenDoor;
+        let door = map.doors.get_mut(door_id).unwrap();
+
+        if let Some(pending_close) = door.pending_close.take() {
+            scheduler.cancel(pending_close);
+        }
+        if !is_open {
+            return Ok(());
+        }
+        if let (Some(delay), true) = (
+            door.closes_after,
+            doorway_is_occupied(x, y, obstacles, items),
+        ) {
+            door.pending_close =
+                Some(scheduler.schedule(delay, vec![GameEvent::CloseDoor(door_id)]));
+            return Ok(());
+        }
+
+        CloseDoorCommand::new(x, y, items, obstacles, map, scheduler)
+            .execute(update_tx, command_tx);
+        Ok(())
+    }
+
+    pub fn repopulate(
+        index: u64,
+        player: &Player,
+        map: &TileMap,
+        obstacles: &mut BlockingMap,
+        characters: &mut CharacterList,
+        items: &mut ItemList,
+        inventories: &mut InventoryList,
+        update_tx: Option<&GameUpdateSender>,
+        command_tx: Option<&CommandSender>,
+    ) -> Result<(), GameError> {
+        let spawn_point = map
+            .spawn_points
+            .get(index as usize)
+            .ok_or(GameError::SpawnPointNotFound(index))?;
+
+        RepopulateCommand::new(spawn_point, player, map, obstacles, characters, items)
+            .execute(update_tx, command_tx);
+        Inventory::provide_for_containers(items, inventories);
+        Ok(())
+    }
+
+    pub fn open_container(
+        item_id: u64,
+        player: &mut Player,
+        items: &ItemList,
+        inventories: &InventoryList,
+        update_tx: Option<&GameUpdateSender>,
+        command_tx: Option<&CommandSender>,
+    ) -> Result<(), GameError> {
+        if !items.holds(item_id) {
+            return Err(GameError::ItemNotFound(item_id));
+        }
+
+        OpenContainerCommand::new(item_id, player, items, inventories)
+            .execute(update_tx, command_tx);
+        Ok(())
+    }
+
+    pub fn close_external_inventory(
+        player: &mut Player,
+        update_tx: Option<&std::sync::mpsc::Sender<GameUpdate>>,
+    ) {
+        player.external_inventory = None;
         GameUpdate::send(update_tx, GameUpdate::ExternalInventoryClosed);
     }
 }
 
+fn find_inventory(
+    inventory_id: u64,
+    inventories: &mut InventoryList,
+) -> Result<&mut Inventory, GameError> {
+    inventories
+        .get_mut(&inventory_id)
+        .ok_or(GameError::InventoryNotFound(inventory_id))
+}
+
+/// returns the id of the inventory storing the item.
+fn stored_in(item_id: u64, items: &ItemList) -> Result<u64, GameError> {
+    match items.get(item_id) {
+        None => Err(GameError::ItemNotFound(item_id)),
+        Some(ItemState::Stored(_, inventory_id)) => Ok(inventory_id),
+        Some(ItemState::Equipped(_, _)) => Err(GameError::ItemAlreadyEquipped(item_id)),
+        Some(ItemState::Bundle(_, _, _)) => Err(GameError::ItemNotHeld(item_id)),
+    }
+}
+
 fn process_equip_item<'a>(
     item: &'a Item,
     player: &'a mut Player,
-    inventory_id: &'a u64,
+    inventory_id: u64,
     item_class_specifiers: &'a ItemClassSpecifierList,
     items: &'a mut ItemList,
     inventories: &'a mut InventoryList,
-) -> Option<Box<dyn CommandHandler + 'a>> {
-    let item_class_specifiers = &item_class_specifiers;
-
-    let inventory = inventories
-        .get_mut(&inventory_id)
-        .expect("unable to find inventory");
+) -> Result<Box<dyn CommandHandler + 'a>, GameError> {
+    let inventory = find_inventory(inventory_id, inventories)?;
 
-    let items = items;
-
-    Some(std::boxed::Box::new(EquipCommand::new(
+    Ok(Box::new(EquipCommand::new(
         item,
         player,
         item_class_specifiers,
@@ -241,19 +485,19 @@ fn process_equip_item<'a>(
 
 fn process_unequip_item<'a>(
     item: &'a Item,
-    inventory_id: &'a u64,
+    inventory_id: u64,
     player: &'a mut Player,
     items: &'a mut ItemList,
     inventories: &'a mut InventoryList,
-) -> Option<Box<dyn CommandHandler + 'a>> {
-    let inventory = inventories.get_mut(inventory_id).unwrap();
+) -> Result<Box<dyn CommandHandler + 'a>, GameError> {
+    let inventory = find_inventory(inventory_id, inventories)?;
 
-    Some(std::boxed::Box::new(UnequipCommand::new(
+    Ok(Box::new(UnequipCommand::new(
         item.id, inventory, player, items,
     )))
 }
 
-fn get_deltas_from_direction(direction: Direction) -> (i32, i32) {
+pub fn get_deltas_from_direction(direction: Direction) -> (i32, i32) {
     match direction {
         Direction::Up => (0, -1),
         Direction::Down => (0, 1),
@@ -291,6 +535,33 @@ fn attempt_to_enter<'a>(
     )))
 }
 
+/// starts a step to the next tile, taking as long as the player's speed and the terrain make it.
+///   Stepping into something that is in the way turns to face it instead.
+fn attempt_to_step<'a>(
+    facing: Direction,
+    dx: i32,
+    dy: i32,
+    mode: MoveCommandMode,
+    player: &'a mut Player,
+    map: &TileMap,
+    obstacles: &'a mut BlockingMap,
+    items: &ItemList,
+    scheduler: &'a mut Scheduler,
+) -> Option<Box<dyn CommandHandler + 'a>> {
+    let new_x = player.x + dx;
+    let new_y = player.y + dy;
+
+    if !obstacles.is_in_bounds(new_x, new_y) || obstacles.is_blocked_at(new_x, new_y) {
+        return attempt_to_enter(facing, dx, dy, player, obstacles);
+    }
+
+    let duration = player.step_duration(mode, map.at(new_x, new_y), items);
+
+    Some(Box::new(StepCommand::new(
+        player, facing, mode, new_x, new_y, duration, scheduler,
+    )))
+}
+
 fn attempt_to_use<'a>(
     facing: Direction,
     dx: i32,
@@ -299,12 +570,14 @@ fn attempt_to_use<'a>(
     map: &'a mut TileMap,
     obstacles: &'a mut BlockingMap,
     facilities: &'a mut FacilityList,
+    items: &'a ItemList,
     inventories: &'a mut InventoryList,
-) -> Option<Box<dyn CommandHandler + 'a>> {
+    scheduler: &'a mut Scheduler,
+) -> Result<Option<Box<dyn CommandHandler + 'a>>, GameError> {
     let target_x = player.x + dx;
     let target_y = player.y + dy;
 
-    if can_use_at(target_x, target_y, map, player, facilities) {
+    if can_use_at(target_x, target_y, map, player, facilities, inventories)? {
         use_at(
             facing,
             target_x,
@@ -313,27 +586,32 @@ fn attempt_to_use<'a>(
             map,
             obstacles,
             facilities,
+            items,
             inventories,
+            scheduler,
         )
+    } else if facing != player.facing {
+        // there is nothing to use, so the player only turns; moving takes a step.
+        Ok(Some(Box::new(ChangeFacingCommand::new(player, facing))))
     } else {
-        attempt_to_enter(facing, dx, dy, player, obstacles)
+        Ok(None)
     }
 }
 
-fn can_use_at(x: i32, y: i32, map: &TileMap, player: &Player, facilities: &FacilityList) -> bool {
+fn can_use_at(
+    x: i32,
+    y: i32,
+    map: &TileMap,
+    player: &Player,
+    facilities: &FacilityList,
+    inventories: &InventoryList,
+) -> Result<bool, GameError> {
     match map.at(x, y) {
-        tile_map::Tile::ClosedDoor | tile_map::Tile::OpenDoor => true,
-        tile_map::Tile::Facility(facility_id) => {
-            let facility = facilities.get(facility_id).expect("facility not found");
-            match facility.class {
-                FacilityClass::ClosedChest => !facility.is_in_use(),
-                FacilityClass::AppleTree => {
-                    !facility.is_in_use() && player.is_endorsed_with(":can_pick_apples")
-                }
-                _ => false,
-            }
-        }
-        _ => false,
+        tile_map::Tile::Facility(facility_id) => Ok(facilities
+            .get(facility_id)
+            .ok_or(GameError::FacilityNotFound(facility_id))?
+            .can_be_used_by(player, inventories.get(&player.inventory_id()))),
+        tile => Ok(tile.is_usable()),
     }
 }
 
@@ -345,13 +623,28 @@ fn use_at<'a>(
     map: &'a mut TileMap,
     obstacles: &'a mut BlockingMap,
     facilities: &'a mut FacilityList,
+    items: &'a ItemList,
     inventories: &'a mut InventoryList,
-) -> Option<Box<dyn CommandHandler + 'a>> {
-    match map.at(x, y) {
-        tile_map::Tile::ClosedDoor => Some(Box::new(OpenDoorCommand::new(x, y, obstacles, map))),
-        tile_map::Tile::OpenDoor => Some(Box::new(CloseDoorCommand::new(x, y, obstacles, map))),
+    scheduler: &'a mut Scheduler,
+) -> Result<Option<Box<dyn CommandHandler + 'a>>, GameError> {
+    Ok(match map.at(x, y) {
+        tile_map::Tile::ClosedDoor => Some(Box::new(OpenDoorCommand::new(
+            x,
+            y,
+            player,
+            inventories.get(&player.inventory_id()),
+            &items.item_types,
+            obstacles,
+            map,
+            scheduler,
+        ))),
+        tile_map::Tile::OpenDoor => Some(Box::new(CloseDoorCommand::new(
+            x, y, items, obstacles, map, scheduler,
+        ))),
         tile_map::Tile::Facility(facility_id) => {
-            let facility = facilities.get(facility_id).expect("missing facility");
+            let facility = facilities
+                .get(facility_id)
+                .ok_or(GameError::FacilityNotFound(facility_id))?;
 
             match facility.class {
                 FacilityClass::ClosedChest => Some(Box::new(OpenChestCommand::new(
@@ -362,7 +655,17 @@ fn use_at<'a>(
                     facilities,
                     inventories,
                 ))),
-                FacilityClass::AppleTree => Some(Box::new(ActivateAppleTreeCommand::new(player))),
+                FacilityClass::AppleTree => {
+                    let requirement = facility.requirement.clone();
+                    let inventory = inventories.get(&player.inventory_id());
+
+                    Some(Box::new(ActivateAppleTreeCommand::new(
+                        player,
+                        requirement,
+                        inventory,
+                        scheduler,
+                    )))
+                }
                 _ => {
                     println!("facility not matched!");
                     None
@@ -370,15 +673,17 @@ fn use_at<'a>(
             }
         }
         _ => None,
-    }
+    })
 }
 
 pub trait CommandHandler {
-    fn can_perform(&self) -> bool {
-        true
+    /// returns why the command cannot be performed, if it cannot.
+    fn can_perform(&self) -> Result<(), Rejection> {
+        Ok(())
     }
 
     /// execute and announce the results of the command.
+    ///   A command that cannot be performed is not executed; its rejection is announced instead.
     /// # Arguments
     /// * update_tx - an optional channel to announce upon.  Can be None for testing purposes.
     fn execute(
@@ -386,6 +691,11 @@ pub trait CommandHandler {
         update_tx: Option<&std::sync::mpsc::Sender<GameUpdate>>,
         command_tx: Option<&std::sync::mpsc::Sender<Command>>,
     ) {
+        if let Err(rejection) = self.can_perform() {
+            GameUpdate::send(update_tx, GameUpdate::CommandRejected(rejection));
+            return;
+        }
+
         self.perform_execute(update_tx, command_tx);
 
         if let Some(update_tx) = update_tx {
@@ -28,6 +28,18 @@ impl<'a> MoveCommand<'a> {
     }
 }
 impl<'a> CommandHandler for MoveCommand<'a> {
+    fn can_perform(&self) -> Result<(), Rejection> {
+        if !self.obstacles.is_in_bounds(self.x, self.y) {
+            return Err(Rejection::OutOfBounds(self.x, self.y));
+        }
+        if (self.character.x, self.character.y) != (self.x, self.y)
+            && self.obstacles.is_blocked_at(self.x, self.y)
+        {
+            return Err(Rejection::Blocked(self.x, self.y));
+        }
+        Ok(())
+    }
+
     fn perform_execute(
         &mut self,
         _update_tx: Option<&GameUpdateSender>,
@@ -61,6 +73,60 @@ impl<'a> CommandHandler for MoveCommand<'a> {
     }
 }
 
+/// starts the player on a step to the next tile, which they reach once it is over.
+pub struct StepCommand<'a> {
+    player: &'a mut Player,
+    facing: Direction,
+    mode: MoveCommandMode,
+    x: i32,
+    y: i32,
+    duration: u64,
+    scheduler: &'a mut Scheduler,
+}
+
+impl<'a> StepCommand<'a> {
+    pub fn new(
+        player: &'a mut Player,
+        facing: Direction,
+        mode: MoveCommandMode,
+        x: i32,
+        y: i32,
+        duration: u64,
+        scheduler: &'a mut Scheduler,
+    ) -> StepCommand<'a> {
+        StepCommand {
+            player,
+            facing,
+            mode,
+            x,
+            y,
+            duration,
+            scheduler,
+        }
+    }
+}
+
+impl<'a> CommandHandler for StepCommand<'a> {
+    fn perform_execute(
+        &mut self,
+        _update_tx: Option<&GameUpdateSender>,
+        _command_tx: Option<&std::sync::mpsc::Sender<Command>>,
+    ) {
+        self.player.activity = Some(
+            self.scheduler
+                .schedule(self.duration, vec![GameEvent::Step(self.facing, self.mode)]),
+        );
+        self.player.moving = true;
+    }
+
+    fn announce(&self, update_tx: &std::sync::mpsc::Sender<GameUpdate>) {
+        GameUpdate::send(
+            Some(update_tx),
+            MovementStarted(self.player.id, self.x, self.y, self.duration as u32),
+        );
+    }
+}
+
 pub struct ChangeFacingCommand<'a> {
     pub player: &'a mut Player,
     pub facing: Direction,
@@ -87,3 +153,108 @@ impl<'a> CommandHandler for ChangeFacingCommand<'a> {
         )
     }
 }
+
+/// a character that can be teleported; the player or one of the other characters.
+pub enum Teleportee<'a> {
+    Player(&'a mut Player),
+    Character(&'a mut Character),
+}
+
+impl<'a> Teleportee<'a> {
+    fn id(&self) -> u64 {
+        match self {
+            Teleportee::Player(player) => player.id,
+            Teleportee::Character(character) => character.id,
+        }
+    }
+
+    fn location(&self) -> (i32, i32) {
+        match self {
+            Teleportee::Player(player) => (player.x, player.y),
+            Teleportee::Character(character) => (character.x, character.y),
+        }
+    }
+
+    fn locate(&mut self, x: i32, y: i32) {
+        match self {
+            Teleportee::Player(player) => {
+                player.x = x;
+                player.y = y;
+            }
+            Teleportee::Character(character) => {
+                character.x = x;
+                character.y = y;
+            }
+        }
+    }
+}
+
+pub struct TeleportCommand<'a> {
+    character: Teleportee<'a>,
+    x: i32,
+    y: i32,
+    inventory_closed: bool,
+    obstacles: &'a mut BlockingMap,
+}
+
+impl<'a> TeleportCommand<'a> {
+    pub fn new(
+        character: Teleportee<'a>,
+        x: i32,
+        y: i32,
+        obstacles: &'a mut BlockingMap,
+    ) -> TeleportCommand<'a> {
+        TeleportCommand {
+            character,
+            x,
+            y,
+            inventory_closed: false,
+            obstacles,
+        }
+    }
+}
+
+impl<'a> CommandHandler for TeleportCommand<'a> {
+    /// the destination must be on the map and unblocked, unless the character is already there.
+    fn can_perform(&self) -> Result<(), Rejection> {
+        if !self.obstacles.is_in_bounds(self.x, self.y) {
+            return Err(Rejection::OutOfBounds(self.x, self.y));
+        }
+        if self.character.location() != (self.x, self.y)
+            && self.obstacles.is_blocked_at(self.x, self.y)
+        {
+            return Err(Rejection::Blocked(self.x, self.y));
+        }
+        Ok(())
+    }
+
+    fn perform_execute(
+        &mut self,
+        _update_tx: Option<&GameUpdateSender>,
+        _command_tx: Option<&std::sync::mpsc::Sender<Command>>,
+    ) {
+        let (old_x, old_y) = self.character.location();
+        self.obstacles.unblock_at(old_x, old_y);
+        self.obstacles.block_at(self.x, self.y);
+
+        self.character.locate(self.x, self.y);
+
+        if let Teleportee::Player(player) = &mut self.character {
+            if player.external_inventory.is_some() {
+                player.external_inventory = None;
+                self.inventory_closed = true;
+            }
+        }
+    }
+
+    fn announce(&self, update_tx: &std::sync::mpsc::Sender<GameUpdate>) {
+        if self.inventory_closed {
+            GameUpdate::send(Some(update_tx), ExternalInventoryClosed);
+        }
+
+        GameUpdate::send(
+            Some(update_tx),
+            CharacterTeleported(self.character.id(), self.x, self.y),
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use super::*;
+use std::fmt;
+
+/// why a command was not performed.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Rejection {
+    OutOfBounds(i32, i32),
+    Blocked(i32, i32),
+    RequirementNotMet(String), // (what could not be done)
+    SkillTooLow {
+        description: String,
+        skill: Skill,
+        required: u8,
+        current: u8,
+    },
+    InventoryFull(u64),       // (inventory_id)
+    ItemNotHeld(u64),         // (item_id)
+    ItemNotBundled(u64),      // (item_id)
+    ItemNotEquipped(u64),     // (item_id)
+    CannotEquip(u64),         // (item_id)
+    InvalidQuantity(u64, u8), // (item_id, quantity)
+    CannotMerge(u64, u64),    // (item_id, into_id)
+    NotAContainer(u64),       // (item_id)
+    ContainerIntoItself(u64), // (item_id)
+    NothingToOpen(i32, i32),
+    NothingToClose(i32, i32),
+    DoorLocked(i32, i32),
+    OutOfReach(u64), // (character_id)
+}
+
+impl fmt::Display for Rejection {
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::command::Rejection;
+    /// let subject = Rejection::RequirementNotMet("pick apples".into());
+    /// assert_eq!(subject.to_string(), "You do not meet the requirements to pick apples.");
+    /// ```
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Rejection::OutOfBounds(_, _) => {
+                write!(formatter, "That is beyond the edge of the world.")
+            }
+            Rejection::Blocked(_, _) => write!(formatter, "Something is in the way."),
+            Rejection::RequirementNotMet(action) => {
+                write!(formatter, "You do not meet the requirements to {}.", action)
+            }
+            Rejection::SkillTooLow {
+                description,
+                skill,
+                required,
+                current,
+            } => write!(
+                formatter,
+                "{} requires level {} {:?}; your {:?} is level {}.",
+                description, required, skill, skill, current
+            ),
+            Rejection::InventoryFull(_) => write!(formatter, "There is no room for that."),
+            Rejection::ItemNotHeld(_) => write!(formatter, "That is not being held."),
+            Rejection::ItemNotBundled(_) => write!(formatter, "That is not lying on the ground."),
+            Rejection::ItemNotEquipped(_) => write!(formatter, "That is not equipped."),
+            Rejection::CannotEquip(_) => write!(formatter, "That cannot be equipped."),
+            Rejection::InvalidQuantity(_, quantity) => {
+                write!(formatter, "You cannot take {} from that stack.", quantity)
+            }
+            Rejection::CannotMerge(_, _) => write!(formatter, "Those do not stack together."),
+            Rejection::NotAContainer(_) => write!(formatter, "That cannot be opened."),
+            Rejection::ContainerIntoItself(_) => {
+                write!(formatter, "That cannot be put inside itself.")
+            }
+            Rejection::NothingToOpen(_, _) => write!(formatter, "There is nothing there to open."),
+            Rejection::NothingToClose(_, _) => {
+                write!(formatter, "There is nothing there to close.")
+            }
+            Rejection::DoorLocked(_, _) => write!(formatter, "The door is locked."),
+            Rejection::OutOfReach(_) => write!(formatter, "That is out of reach."),
+        }
+    }
+}
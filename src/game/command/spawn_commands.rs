@@ -0,0 +1,171 @@
+use super::*;
+
+/// brings one more creature or item into a spawn point's region, if the region is short of them
+///   and has somewhere out of the player's sight to put it.
+pub struct RepopulateCommand<'a> {
+    spawn_point: &'a SpawnPoint,
+    player: &'a Player,
+    map: &'a TileMap,
+    obstacles: &'a mut BlockingMap,
+    characters: &'a mut CharacterList,
+    items: &'a mut ItemList,
+}
+
+impl<'a> RepopulateCommand<'a> {
+    pub fn new(
+        spawn_point: &'a SpawnPoint,
+        player: &'a Player,
+        map: &'a TileMap,
+        obstacles: &'a mut BlockingMap,
+        characters: &'a mut CharacterList,
+        items: &'a mut ItemList,
+    ) -> RepopulateCommand<'a> {
+        RepopulateCommand {
+            spawn_point,
+            player,
+            map,
+            obstacles,
+            characters,
+            items,
+        }
+    }
+}
+
+impl<'a> CommandHandler for RepopulateCommand<'a> {
+    fn perform_execute(
+        &mut self,
+        update_tx: Option<&GameUpdateSender>,
+        _command_tx: Option<&CommandSender>,
+    ) {
+        let spawn_point = self.spawn_point;
+        if spawn_point.population_in(self.characters, self.items) >= spawn_point.population as usize
+        {
+            return;
+        }
+
+        let locations = spawn_point.vacant_locations(self.player, self.map, self.obstacles);
+        if locations.is_empty() {
+            return;
+        }
+        let (x, y) = locations[with_random(|rng| rng.below(locations.len() as u64)) as usize];
+
+        // newcomers are introduced the way those present when the level loaded were.
+        match &spawn_point.spawn {
+            Spawn::Creature(character_type) => {
+                let character = Character::new(NEXT_ID(), x, y, Direction::Up, *character_type);
+                Level::introduce_character(&character, self.obstacles, update_tx);
+                self.characters.add(character);
+            }
+            Spawn::Item(item_type) => {
+                let item = Item::spawn_from_type(item_type, 1, &self.items.item_types);
+                self.items.bundle(&item, x, y);
+                Level::introduce_item(item.id, &item, x, y, update_tx);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod repopulate_command {
+    use super::*;
+
+    fn level() -> (Player, TileMap, BlockingMap, CharacterList, ItemList) {
+        let mut map = TileMap::new();
+        map.set_dimensions(40, 10, tile_map::Tile::DirtFloor);
+        let mut obstacles = BlockingMap::new();
+        obstacles.refresh(&map);
+
+        let mut player = Player::new();
+        player.x = 1;
+        player.y = 1;
+
+        let mut item_types = ItemTypeList::new();
+        item_types.insert("apple".into(), ItemType::new(ItemClass::Food, "Apple"));
+
+        (
+            player,
+            map,
+            obstacles,
+            CharacterList::new(),
+            ItemList::new(Some(item_types)),
+        )
+    }
+
+    fn spawn_point(spawn: Spawn, population: u8) -> SpawnPoint {
+        let mut result = SpawnPoint::new(spawn, 30, 5);
+        result.population = population;
+        result.radius = 2;
+        result
+    }
+
+    #[test]
+    fn a_creature_is_spawned_until_the_population_is_reached() {
+        let (player, map, mut obstacles, mut characters, mut items) = level();
+        let spawn_point = spawn_point(Spawn::Creature(CharacterType::Rat), 2);
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        for _ in 0..3 {
+            RepopulateCommand::new(
+                &spawn_point,
+                &player,
+                &map,
+                &mut obstacles,
+                &mut characters,
+                &mut items,
+            )
+            .execute(Some(&sender), None);
+        }
+
+        assert_eq!(characters.len(), 2);
+        for character in characters.iter() {
+            assert!(spawn_point.covers(character.x, character.y));
+            assert!(obstacles.is_blocked_at(character.x, character.y));
+        }
+        let updates: Vec<GameUpdate> = receiver.try_iter().collect();
+        assert_eq!(updates.len(), 2);
+        assert!(updates.iter().all(|u| matches!(
+            u,
+            CharacterEntered {
+                character_type: CharacterType::Rat,
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn an_item_is_bundled_within_the_region() {
+        let (player, map, mut obstacles, mut characters, mut items) = level();
+        let spawn_point = spawn_point(Spawn::Item("apple".into()), 1);
+
+        RepopulateCommand::new(
+            &spawn_point,
+            &player,
+            &map,
+            &mut obstacles,
+            &mut characters,
+            &mut items,
+        )
+        .execute(None, None);
+
+        assert_eq!(spawn_point.population_in(&characters, &items), 1);
+    }
+
+    #[test]
+    fn nothing_is_spawned_where_the_player_can_see() {
+        let (mut player, map, mut obstacles, mut characters, mut items) = level();
+        player.x = 25;
+        let spawn_point = spawn_point(Spawn::Creature(CharacterType::Spider), 1);
+
+        RepopulateCommand::new(
+            &spawn_point,
+            &player,
+            &map,
+            &mut obstacles,
+            &mut characters,
+            &mut items,
+        )
+        .execute(None, None);
+
+        assert_eq!(characters.len(), 0);
+    }
+}
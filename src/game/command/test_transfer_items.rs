@@ -2,3 +2,88 @@ pub use super::*;
 
 #[test]
 fn transferring_item_from_one_inventory_to_another() {}
+
+#[test]
+fn transferring_into_a_full_inventory_is_rejected() {
+    let mut inventories = InventoryList::new();
+    let mut items = ItemList::new(None);
+
+    let item = Item::new(1776, ItemType::new(ItemClass::Tool, "Hatchet"), 1);
+    Inventory::new_into_inventory_list(1, &mut inventories).accept(&item, &mut items);
+
+    let chest = Inventory::new_into_inventory_list(5, &mut inventories);
+    for id in 0..27 {
+        chest.force_accept(&Item::new(id, ItemType::new(ItemClass::Tool, "Shovel"), 1));
+    }
+
+    let mut command = TransferItemCommand::new(&item, 1, 1, 5, &mut inventories, &mut items);
+    assert_eq!(command.can_perform(), Err(Rejection::InventoryFull(5)));
+
+    command.execute(None, None);
+    assert!(inventories[&1].holds(1776));
+}
+
+#[test]
+fn a_whole_stack_is_merged_into_a_full_inventory_with_room_in_its_stacks() {
+    let mut inventories = InventoryList::new();
+    let mut items = ItemList::new(None);
+    let apple = ItemType::new(ItemClass::Food, "Apple");
+
+    let item = Item::new(1776, apple.clone(), 10);
+    Inventory::new_into_inventory_list(1, &mut inventories).accept(&item, &mut items);
+
+    let chest = Inventory::new_into_inventory_list(5, &mut inventories);
+    chest.accept(&Item::new(1800, apple.clone(), 50), &mut items);
+    for id in 0..26 {
+        chest.force_accept(&Item::new(id, ItemType::new(ItemClass::Tool, "Shovel"), 1));
+    }
+    assert!(chest.is_full());
+
+    let mut command = TransferItemCommand::new(&item, 10, 1, 5, &mut inventories, &mut items);
+    assert_eq!(command.can_perform(), Ok(()));
+    command.execute(None, None);
+
+    assert!(!inventories[&1].holds(1776));
+    assert_eq!(inventories[&5][1800].quantity, 60);
+    assert_eq!(items.get(1776), None);
+}
+
+#[test]
+fn transferring_an_item_the_source_does_not_hold_is_rejected() {
+    let mut inventories = InventoryList::new();
+    let mut items = ItemList::new(None);
+    Inventory::new_into_inventory_list(1, &mut inventories);
+    Inventory::new_into_inventory_list(5, &mut inventories);
+
+    let item = Item::new(1776, ItemType::new(ItemClass::Tool, "Hatchet"), 1);
+    let command = TransferItemCommand::new(&item, 1, 1, 5, &mut inventories, &mut items);
+
+    assert_eq!(command.can_perform(), Err(Rejection::ItemNotHeld(1776)));
+}
+
+#[test]
+fn transferring_part_of_a_stack_merges_it_into_the_destination() {
+    let mut inventories = InventoryList::new();
+    let mut items = ItemList::new(None);
+    let apple = ItemType::new(ItemClass::Food, "Apple");
+
+    let item = Item::new(1776, apple.clone(), 10);
+    Inventory::new_into_inventory_list(1, &mut inventories).accept(&item, &mut items);
+    Inventory::new_into_inventory_list(5, &mut inventories)
+        .accept(&Item::new(1800, apple.clone(), 60), &mut items);
+
+    let mut command = TransferItemCommand::new(&item, 7, 1, 5, &mut inventories, &mut items);
+    command.execute(None, None);
+
+    assert_eq!(inventories[&1][1776].quantity, 3);
+    let chest = inventories[&5].to_vec();
+    let mut quantities: Vec<u8> = chest.iter().map(|i| i.quantity).collect();
+    quantities.sort();
+    assert_eq!(quantities, vec![3, 64]);
+
+    let total: u32 = items
+        .iter()
+        .map(|(_id, state)| ItemState::extract_item(state).quantity as u32)
+        .sum();
+    assert_eq!(total, 70);
+}
@@ -0,0 +1,192 @@
+use super::*;
+use regex::Regex;
+
+/// a door of the map, found wherever the map file has a door tile.
+///   Its open or closed state is its tile; the rest is declared in the map file's doors section:
+/// ```text
+/// 31,7 { key: brass_key; closes_after: 5000 }
+/// 12,3 { requires: skill(combat, 5) }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Door {
+    pub id: u64,
+    pub x: i32,
+    pub y: i32,
+    pub key: Option<String>, // (item_type) the door is locked unless this is carried
+    pub closes_after: Option<u64>, // in milliseconds
+    pub requirement: Requirement, // what a player needs in order to open the door
+    pub pending_close: Option<u64>, // id of the scheduled event that closes the door
+}
+
+impl Door {
+    pub fn new(id: u64, x: i32, y: i32) -> Self {
+        Self {
+            id,
+            x,
+            y,
+            key: None,
+            closes_after: None,
+            requirement: Requirement::Always,
+            pending_close: None,
+        }
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.key.is_some()
+    }
+
+    /// returns true if the door has no lock or the inventory holds its key.
+    pub fn can_be_opened_with(
+        &self,
+        inventory: Option<&Inventory>,
+        item_types: &ItemTypeList,
+    ) -> bool {
+        let key = match &self.key {
+            Some(key) => key,
+            None => return true,
+        };
+        let key_type = match item_types.get(key) {
+            Some(key_type) => key_type,
+            None => return false,
+        };
+
+        inventory.is_some_and(|inventory| {
+            inventory
+                .to_vec()
+                .iter()
+                .any(|item| item.item_type == *key_type)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DoorList {
+    doors: Vec<Door>,
+}
+
+impl DoorList {
+    pub fn new() -> Self {
+        Self { doors: vec![] }
+    }
+
+    pub fn len(&self) -> usize {
+        self.doors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.doors.is_empty()
+    }
+
+    pub fn add(&mut self, door: Door) {
+        self.doors.push(door);
+    }
+
+    pub fn get(&self, id: u64) -> Option<&Door> {
+        self.doors.iter().find(|door| door.id == id)
+    }
+
+    pub fn get_mut(&mut self, id: u64) -> Option<&mut Door> {
+        self.doors.iter_mut().find(|door| door.id == id)
+    }
+
+    pub fn at(&self, x: i32, y: i32) -> Option<&Door> {
+        self.doors.iter().find(|door| door.x == x && door.y == y)
+    }
+
+    pub fn at_mut(&mut self, x: i32, y: i32) -> Option<&mut Door> {
+        self.doors
+            .iter_mut()
+            .find(|door| door.x == x && door.y == y)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Door> {
+        self.doors.iter()
+    }
+
+    /// creates a door, numbered from 1 in reading order, for every door tile of the map,
+    ///   then gives them the attributes declared for them.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::door::DoorList;
+    /// # use muframework::game::requirement::Requirement;
+    /// # use muframework::game::tile_map::{Tile,TileMap};
+    /// let mut map = TileMap::new();
+    /// map.set_dimensions(10, 10, Tile::DirtFloor);
+    /// map.set_tile_at(4, 2, Tile::ClosedDoor);
+    /// map.set_tile_at(1, 5, Tile::OpenDoor);
+    ///
+    /// let subject = DoorList::read_in_doors(
+    ///     &map,
+    ///     &["1,5 { closes_after: 3000; requires: :guard }".into()],
+    /// );
+    ///
+    /// assert_eq!(subject.len(), 2);
+    /// assert_eq!(subject.at(4, 2).unwrap().id, 1);
+    /// assert_eq!(subject.get(2).unwrap().closes_after, Some(3000));
+    /// assert_eq!(subject.get(2).unwrap().requirement, Requirement::Endorsement(":guard".into()));
+    /// assert!(!subject.get(2).unwrap().is_locked());
+    /// ```
+    pub fn read_in_doors(map: &TileMap, doors: &[String]) -> DoorList {
+        let mut result = DoorList::new();
+
+        for y in 0..map.map_height as i32 {
+            for x in 0..map.map_width as i32 {
+                if let tile_map::Tile::ClosedDoor | tile_map::Tile::OpenDoor = map.at(x, y) {
+                    result.add(Door::new(result.len() as u64 + 1, x, y));
+                }
+            }
+        }
+
+        let re = Regex::new(r#"^(\d+)\s*,\s*(\d+)\s*\{([^}]*)\}\s*(?://.*)?$"#).unwrap();
+
+        for line in doors
+            .iter()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with("//"))
+        {
+            let captures = re
+                .captures(line)
+                .unwrap_or_else(|| panic!("unable to parse door: {}", line));
+            let x = capture_coordinate(&captures, 1);
+            let y = capture_coordinate(&captures, 2);
+            let door = result
+                .at_mut(x, y)
+                .unwrap_or_else(|| panic!("no door at {},{}", x, y));
+
+            Self::read_in_attributes_for(door, capture_string(&captures, 3));
+        }
+
+        result
+    }
+
+    fn read_in_attributes_for(door: &mut Door, attributes: &str) {
+        for attribute in attributes
+            .split(';')
+            .map(|a| a.trim())
+            .filter(|a| !a.is_empty())
+        {
+            let (name, value) = attribute
+                .split_once(':')
+                .unwrap_or_else(|| panic!("unable to parse door attribute: {}", attribute));
+            let value = value.trim();
+
+            match name.trim() {
+                "key" => door.key = Some(value.to_string()),
+                "closes_after" => {
+                    door.closes_after = Some(
+                        value
+                            .parse::<u64>()
+                            .ok()
+                            .filter(|delay| *delay > 0)
+                            .unwrap_or_else(|| panic!("invalid door attribute: {}", attribute)),
+                    )
+                }
+                "requires" => {
+                    door.requirement =
+                        Requirement::parse(value).unwrap_or_else(|error| panic!("{}", error))
+                }
+                _ => panic!("unrecognized door attribute: {}", attribute),
+            }
+        }
+    }
+}
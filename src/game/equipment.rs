@@ -1,7 +1,6 @@
 use super::*;
-use std::collections::HashSet;
 
-#[derive(Debug, Hash, Eq, PartialEq, Copy, Clone)]
+#[derive(Debug, Hash, Eq, PartialEq, Copy, Clone, Ord, PartialOrd)]
 pub enum MountingPoint {
     Head,
     Face,
@@ -36,6 +35,73 @@ static ALL_MOUNTING_POINTS: [MountingPoint; 14] = [
     MountingPoint::OffHand,
 ];
 
+impl MountingPoint {
+    /// returns the mounting point with the given name, ignoring case.
+    pub fn parse(name: &str) -> Option<MountingPoint> {
+        ALL_MOUNTING_POINTS
+            .iter()
+            .find(|mp| format!("{:?}", mp).eq_ignore_ascii_case(name.trim()))
+            .copied()
+    }
+}
+
+/// the mounting points an item can be mounted on.
+///   Each alternative is a set of mounting points the item occupies at once.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Fitting {
+    alternatives: Vec<Vec<MountingPoint>>,
+}
+
+impl Fitting {
+    /// fits items that occupy all of the mounting points together.
+    pub fn on(mounting_points: &[MountingPoint]) -> Self {
+        Self {
+            alternatives: vec![mounting_points.to_vec()],
+        }
+    }
+
+    /// fits items that occupy any one of the mounting points.
+    pub fn any_of(mounting_points: &[MountingPoint]) -> Self {
+        Self {
+            alternatives: mounting_points.iter().map(|mp| vec![*mp]).collect(),
+        }
+    }
+
+    /// parses a fitting as declared in a map file.  Mounting points occupied together are
+    ///   joined by '+' and alternatives are separated by '|'.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::equipment::*;
+    /// use MountingPoint::*;
+    /// assert_eq!(Fitting::parse("OnHand + OffHand"), Ok(Fitting::on(&[OnHand, OffHand])));
+    /// assert_eq!(Fitting::parse("leftwrist | rightwrist"), Ok(Fitting::any_of(&[LeftWrist, RightWrist])));
+    /// assert!(Fitting::parse("OnHand + Tail").is_err());
+    /// ```
+    pub fn parse(declaration: &str) -> Result<Fitting, String> {
+        let mut alternatives = vec![];
+
+        for alternative in declaration.split('|') {
+            let mut mounting_points = vec![];
+            for name in alternative.split('+') {
+                let mounting_point = MountingPoint::parse(name)
+                    .ok_or_else(|| format!("unknown mounting point: {}", name.trim()))?;
+                mounting_points.push(mounting_point);
+            }
+            alternatives.push(mounting_points);
+        }
+        Ok(Fitting { alternatives })
+    }
+
+    pub fn alternatives(&self) -> &Vec<Vec<MountingPoint>> {
+        &self.alternatives
+    }
+
+    /// returns true if nothing can be mounted with this fitting.
+    pub fn is_empty(&self) -> bool {
+        self.alternatives.is_empty()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct MountingPointMap {
     mounts: HashMap<MountingPoint, Option<u64>>,
@@ -80,51 +146,84 @@ impl MountingPointMap {
             .collect()
     }
 
+    /// replaces the player's equipment endorsements with those granted by what is mounted.
     pub fn endorse(&self, player: &mut Player, items: &ItemList) {
         let all_equipment = self.to_vec_of_items(items);
 
-        for equipment in all_equipment {
-            equipment.endorse(player);
-        }
+        player.set_endorsements_from(
+            EndorsementSource::Equipment,
+            all_equipment.iter().flat_map(|e| e.endorsements().clone()),
+        );
     }
 
-    // TODO: allow for multiple mounting points
     fn perform_mount(&mut self, item: &Item, mounting_points: &Vec<&MountingPoint>) {
-        if mounting_points.is_empty() {
-            return;
+        for mounting_point in mounting_points {
+            self.mounts.insert(**mounting_point, Some(item.id));
         }
+    }
+
+    /// returns the mounting points the item would occupy: the first alternative that is
+    ///   entirely free, or else the first alternative.
+    pub fn mounting_points_for(
+        &self,
+        item: &Item,
+        item_class_specifiers: &ItemClassSpecifierList,
+    ) -> Option<Vec<MountingPoint>> {
+        let fitting = ItemClassSpecifier::fitting_for(item, item_class_specifiers);
 
-        let new_item_id = item.id;
-        self.mounts.insert(*mounting_points[0], Some(new_item_id));
+        fitting
+            .alternatives()
+            .iter()
+            .find(|alternative| alternative.iter().all(|mp| self.is_empty(mp)))
+            .or_else(|| fitting.alternatives().first())
+            .cloned()
+    }
+
+    /// returns the ids of the items that equipping the item would unmount.
+    pub fn conflicts_for(
+        &self,
+        item: &Item,
+        item_class_specifiers: &ItemClassSpecifierList,
+    ) -> Vec<u64> {
+        let mut result: Vec<u64> = self
+            .mounting_points_for(item, item_class_specifiers)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|mp| self.mounts[mp])
+            .filter(|id| *id != item.id)
+            .collect();
+
+        result.sort();
+        result.dedup();
+        result
     }
 
-    // TODO: accept multiple mounting points
+    /// mounts a stored item, first returning every item in the way to the inventory.
     pub fn mount(
         &mut self,
         item: &Item,
-        // mounting_points: &Vec<&MountingPoint>,
         item_class_specifiers: &ItemClassSpecifierList,
         inventory: &mut Inventory,
         items: &mut ItemList,
     ) {
-        let mounting_points = ItemClassSpecifier::mounting_points_for(&item, item_class_specifiers);
-
-        if mounting_points.is_empty() {
-            return;
-        }
+        let mounting_points = match self.mounting_points_for(item, item_class_specifiers) {
+            Some(mounting_points) => mounting_points,
+            None => return,
+        };
+        let new_item = match items.get(item.id) {
+            Some(ItemState::Stored(new_item, _inventory_id)) => new_item,
+            _ => return,
+        };
+        let conflicts = self.conflicts_for(item, item_class_specifiers);
 
-        let previous = self
-            .mounts
-            .get(mounting_points[0])
-            .expect("previous not found");
+        // release the new item first so that its place can take one of the conflicts.
+        self.mount_new_item(&new_item, inventory, items);
 
-        if let Some(previous_id) = previous {
-            Self::unmount_previous_item(*previous_id, inventory, items)
+        for previous_id in conflicts {
+            self.force_unmount_item_by_id(previous_id);
+            Self::unmount_previous_item(previous_id, inventory, items);
         }
-
-        if let ItemState::Stored(new_item, _inventory_id) = items[item.id].clone() {
-            self.mount_new_item(&new_item, &mounting_points, inventory, items)
-        };
+        self.perform_mount(&new_item, &mounting_points.iter().collect());
     }
 
     fn unmount_previous_item(previous_id: u64, inventory: &mut Inventory, items: &mut ItemList) {
@@ -136,15 +235,8 @@ impl MountingPointMap {
         }
     }
 
-    fn mount_new_item(
-        &mut self,
-        new_item: &Item,
-        mounting_points: &Vec<&MountingPoint>,
-        inventory: &mut Inventory,
-        items: &mut ItemList,
-    ) {
+    fn mount_new_item(&mut self, new_item: &Item, inventory: &mut Inventory, items: &mut ItemList) {
         let new_item_id = new_item.id;
-        self.perform_mount(&new_item, mounting_points);
         items[new_item_id] = ItemState::Equipped(new_item.clone(), inventory.id());
         inventory.release_item(&new_item_id);
     }
@@ -177,9 +269,9 @@ impl MountingPointMap {
         items: &mut ItemList,
     ) {
         for mounting_point in mounting_points {
-            let mounted_item = self.mounts.insert(**mounting_point, None);
-            if let Some(Some(item_id)) = mounted_item {
-                println!("unmounted: {:?}", item_id);
+            // an item occupying several mounting points leaves all of them.
+            if let Some(item_id) = self.mounts[*mounting_point] {
+                self.force_unmount_item_by_id(item_id);
                 let mut item = items.get_as_item(item_id).unwrap();
                 inventory.accept_stack(&mut item, items);
             }
@@ -189,86 +281,33 @@ impl MountingPointMap {
 
 #[derive(Debug, Clone)]
 pub struct ItemClassSpecifier {
-    pub mounting_points: HashSet<MountingPoint>,
+    pub fitting: Fitting,
 }
 
 pub type ItemClassSpecifierList = HashMap<ItemClass, ItemClassSpecifier>;
 
 impl ItemClassSpecifier {
+    /// reads each item class's mounting points from the item class data file.
     pub fn initialize() -> ItemClassSpecifierList {
-        let mut item_class_specifiers = ItemClassSpecifierList::new();
-
-        item_class_specifiers.insert(
-            ItemClass::BladeWeapon,
-            ItemClassSpecifier {
-                mounting_points: [MountingPoint::OnHand].iter().cloned().collect(),
-            },
-        );
-        item_class_specifiers.insert(
-            ItemClass::Dagger,
-            ItemClassSpecifier {
-                mounting_points: [MountingPoint::OnHand].iter().cloned().collect(),
-            },
-        );
-        item_class_specifiers.insert(
-            ItemClass::Shield,
-            ItemClassSpecifier {
-                mounting_points: [MountingPoint::OffHand].iter().cloned().collect(),
-            },
-        );
-        item_class_specifiers.insert(
-            ItemClass::SoftArmor,
-            ItemClassSpecifier {
-                mounting_points: [MountingPoint::Chest].iter().cloned().collect(),
-            },
-        );
-        item_class_specifiers.insert(
-            ItemClass::Pants,
-            ItemClassSpecifier {
-                mounting_points: [MountingPoint::Legs].iter().cloned().collect(),
-            },
-        );
-        item_class_specifiers.insert(
-            ItemClass::Gloves,
-            ItemClassSpecifier {
-                mounting_points: [MountingPoint::Hands].iter().cloned().collect(),
-            },
-        );
-        item_class_specifiers.insert(
-            ItemClass::Shoes,
-            ItemClassSpecifier {
-                mounting_points: [MountingPoint::Feet].iter().cloned().collect(),
-            },
-        );
-        item_class_specifiers.insert(
-            ItemClass::Headwear,
-            ItemClassSpecifier {
-                mounting_points: [MountingPoint::Head].iter().cloned().collect(),
-            },
-        );
-        item_class_specifiers.insert(
-            ItemClass::Tool,
-            ItemClassSpecifier {
-                mounting_points: [MountingPoint::OnHand].iter().cloned().collect(),
-            },
-        );
-        item_class_specifiers.insert(
-            ItemClass::Potion,
-            ItemClassSpecifier {
-                mounting_points: [].iter().cloned().collect(),
-            },
-        );
-        item_class_specifiers
-    }
-    pub fn to_vec<'a>(&'a self) -> Vec<&'a MountingPoint> {
-        self.mounting_points.iter().collect()
+        ItemClass::all()
+            .into_iter()
+            .map(|item_class| {
+                let fitting = item_class.definition().fitting.clone();
+                (item_class, ItemClassSpecifier { fitting })
+            })
+            .collect()
     }
 
-    pub fn mounting_points_for<'a>(
-        item: &Item,
-        item_class_specifiers: &'a ItemClassSpecifierList,
-    ) -> Vec<&'a MountingPoint> {
-        item_class_specifiers[&item.class()].to_vec()
+    /// returns how the item is mounted: as declared by its item type or else by its class.
+    ///   Items of classes that cannot be equipped get an empty fitting.
+    pub fn fitting_for(item: &Item, item_class_specifiers: &ItemClassSpecifierList) -> Fitting {
+        if let Some(fitting) = item.item_type.fitting() {
+            return fitting.clone();
+        }
+        item_class_specifiers
+            .get(&item.class())
+            .map(|specifier| specifier.fitting.clone())
+            .unwrap_or_default()
     }
 }
 
@@ -476,6 +515,79 @@ mod mounting_point_map {
     }
 }
 
+#[cfg(test)]
+mod mounting_point_map_fittings {
+    use super::*;
+    use MountingPoint::*;
+
+    fn stored_item(
+        item_type: ItemType,
+        id: u64,
+        inventory: &mut Inventory,
+        items: &mut ItemList,
+    ) -> Item {
+        let mut item = Item::new(id, item_type, 1);
+        items[id] = ItemState::Stored(item.clone(), inventory.id());
+        inventory.accept_stack(&mut item, items);
+        item
+    }
+
+    #[test]
+    fn a_two_handed_weapon_returns_both_hands_to_the_inventory() {
+        let mut subject = MountingPointMap::new();
+        let mut inventory = Inventory::new(1);
+        let mut items = ItemList::new(None);
+        let item_class_specifiers = ItemClassSpecifier::initialize();
+
+        let sword = ItemType::new(ItemClass::BladeWeapon, "Short Sword");
+        let shield = ItemType::new(ItemClass::Shield, "Buckler");
+        let mut two_handed = ItemType::new(ItemClass::BladeWeapon, "Two-Handed Sword");
+        two_handed.set_fitting(Fitting::on(&[OnHand, OffHand]));
+
+        for (id, item_type) in [(1, sword), (2, shield)] {
+            let item = stored_item(item_type, id, &mut inventory, &mut items);
+            subject.mount(&item, &item_class_specifiers, &mut inventory, &mut items);
+        }
+        let item = stored_item(two_handed, 3, &mut inventory, &mut items);
+        assert_eq!(
+            subject.conflicts_for(&item, &item_class_specifiers),
+            vec![1, 2]
+        );
+
+        subject.mount(&item, &item_class_specifiers, &mut inventory, &mut items);
+
+        assert_eq!(subject.to_vec(), vec![3]);
+        assert_eq!(subject.mounts[&OnHand], Some(3));
+        assert_eq!(subject.mounts[&OffHand], Some(3));
+        assert!(inventory.holds(1) && inventory.holds(2) && !inventory.holds(3));
+        assert!(matches!(items.get(1), Some(ItemState::Stored(_, 1))));
+        assert!(matches!(items.get(3), Some(ItemState::Equipped(_, 1))));
+
+        subject.unmount(&vec![&OffHand], &mut inventory, &mut items);
+
+        assert!(subject.is_empty(&OnHand));
+        assert!(inventory.holds(3));
+    }
+
+    #[test]
+    fn rings_go_in_any_free_slot() {
+        let mut subject = MountingPointMap::new();
+        let mut inventory = Inventory::new(1);
+        let mut items = ItemList::new(None);
+        let item_class_specifiers = ItemClassSpecifier::initialize();
+
+        for id in 1..=3 {
+            let ring = ItemType::new(ItemClass::Ring, "Copper Ring");
+            let item = stored_item(ring, id, &mut inventory, &mut items);
+            subject.mount(&item, &item_class_specifiers, &mut inventory, &mut items);
+        }
+
+        assert_eq!(subject.mounts[&LeftWrist], Some(3));
+        assert_eq!(subject.mounts[&RightWrist], Some(2));
+        assert!(inventory.holds(1));
+    }
+}
+
 #[cfg(test)]
 mod mounting_point_map_unmount {
     use super::*;
@@ -0,0 +1,58 @@
+use std::fmt;
+
+/// a command that does not fit the state of the game, such as one sent by a stale client.
+///   Unlike a Rejection, a GameError is not a rule of the game being enforced.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum GameError {
+    ItemNotFound(u64),        // (item_id)
+    NothingToTake(u64),       // (item_index)
+    ItemNotBundled(u64),      // (item_id)
+    ItemNotHeld(u64),         // (item_id)
+    ItemAlreadyEquipped(u64), // (item_id)
+    ItemNotEquipped(u64),     // (item_id)
+    InventoryNotFound(u64),   // (inventory_id)
+    CharacterNotFound(u64),   // (character_id)
+    SpawnPointNotFound(u64),  // (spawn_point_index)
+    DoorNotFound(u64),        // (door_id)
+    FacilityNotFound(u64),    // (facility_id)
+}
+
+impl fmt::Display for GameError {
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::GameError;
+    /// let subject = GameError::ItemAlreadyEquipped(11);
+    /// assert_eq!(subject.to_string(), "Item 11 is already equipped.");
+    /// ```
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameError::ItemNotFound(id) => write!(formatter, "Item {} does not exist.", id),
+            GameError::NothingToTake(index) => {
+                write!(formatter, "There is no item {} here to take.", index)
+            }
+            GameError::ItemNotBundled(id) => {
+                write!(formatter, "Item {} is not lying on the ground.", id)
+            }
+            GameError::ItemNotHeld(id) => write!(formatter, "Item {} is not being held.", id),
+            GameError::ItemAlreadyEquipped(id) => {
+                write!(formatter, "Item {} is already equipped.", id)
+            }
+            GameError::ItemNotEquipped(id) => write!(formatter, "Item {} is not equipped.", id),
+            GameError::InventoryNotFound(id) => {
+                write!(formatter, "Inventory {} does not exist.", id)
+            }
+            GameError::CharacterNotFound(id) => {
+                write!(formatter, "Character {} does not exist.", id)
+            }
+            GameError::SpawnPointNotFound(index) => {
+                write!(formatter, "Spawn point {} does not exist.", index)
+            }
+            GameError::DoorNotFound(id) => write!(formatter, "Door {} does not exist.", id),
+            GameError::FacilityNotFound(id) => {
+                write!(formatter, "Facility {} does not exist.", id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
@@ -31,6 +31,8 @@ pub struct Facility {
     pub class: FacilityClass,
     pub description: String,
     pub inventory: Option<u64>,
+    pub requirement: Requirement, // what a player needs in order to use the facility
+    pub loot: Option<String>,     // the loot table that stocks the facility's inventory
 }
 
 impl<'a> Facility {
@@ -61,6 +63,8 @@ impl<'a> Facility {
             class,
             description,
             inventory: inventory_id,
+            requirement: Requirement::Always,
+            loot: None,
         }
     }
     pub fn new_with_inventory<T, U>(
@@ -92,6 +96,8 @@ impl<'a> Facility {
             class,
             description,
             inventory: Some(inventory_id),
+            requirement: Requirement::Always,
+            loot: None,
         }
     }
 
@@ -109,10 +115,34 @@ impl<'a> Facility {
 
         let class = FacilityClass::from_symbol(symbol);
 
-        (
-            Facility::new(NEXT_ID(), x, y, class, description.into(), inventories),
-            inventory_alias,
-        )
+        let mut facility = Facility::new(NEXT_ID(), x, y, class, description.into(), inventories);
+        if let Some(attributes) = captures.get(6) {
+            Self::read_in_facility_attributes_for(&mut facility, attributes.as_str());
+        }
+
+        (facility, inventory_alias)
+    }
+
+    /// reads attributes given as { name: value; name: value }.
+    fn read_in_facility_attributes_for(facility: &mut Facility, attributes: &str) {
+        let re = regex::Regex::new(r#"^(requires|loot):\s*(.+)$"#).unwrap();
+
+        for attribute in attributes
+            .split(';')
+            .map(|a| a.trim())
+            .filter(|a| !a.is_empty())
+        {
+            let captures = re.captures(attribute).expect("unable to parse attribute");
+            let attribute_value = capture_string(&captures, 2);
+
+            match capture_string(&captures, 1) {
+                "requires" => {
+                    facility.requirement = Requirement::parse(attribute_value)
+                        .unwrap_or_else(|error| panic!("{}", error))
+                }
+                _ => facility.loot = Some(attribute_value.to_string()),
+            }
+        }
     }
 
     fn read_facility_from_string(
@@ -137,8 +167,10 @@ impl<'a> Facility {
         let mut aliases = AliasList::new(1);
         let mut result = FacilityList::new();
 
-        let re = regex::Regex::new("(?m)^(.+)\\s(\\d+)\\s*,\\s*(\\d+)\\s\"([^\"]*)\"\\s*(\\w+)?")
-            .unwrap();
+        let re = regex::Regex::new(
+            "(?m)^(.+)\\s(\\d+)\\s*,\\s*(\\d+)\\s\"([^\"]*)\"\\s*(\\w+)?\\s*(?:\\{\\s*(.*?)\\s*\\})?",
+        )
+        .unwrap();
 
         for string in facilities {
             Self::read_facility_from_string(&re, string, &mut aliases, &mut result, inventories);
@@ -147,9 +179,51 @@ impl<'a> Facility {
         (result, aliases)
     }
 
+    /// fills the inventory of every facility that names a loot table with a roll of the table.
+    pub fn stock_with_loot(
+        facilities: &FacilityList,
+        items: &mut ItemList,
+        inventories: &mut InventoryList,
+    ) {
+        let mut stocked: Vec<&Facility> = facilities
+            .iter()
+            .map(|(_, facility)| facility)
+            .filter(|facility| facility.loot.is_some() && facility.inventory.is_some())
+            .collect();
+        // rolled in a fixed order so that the same seed stocks the same level.
+        stocked.sort_by_key(|facility| facility.id);
+
+        for facility in stocked {
+            let loot = facility.loot.as_ref().unwrap();
+            let inventory = match inventories.get_mut(&facility.inventory.unwrap()) {
+                Some(inventory) => inventory,
+                None => continue,
+            };
+            let spawned = with_random(|rng| items.loot_tables.spawn(loot, &items.item_types, rng));
+
+            for mut item in spawned {
+                inventory.accept_stack(&mut item, items);
+            }
+        }
+    }
+
     pub fn is_in_use(&self) -> bool {
         false
     }
+
+    /// returns true if the facility does something when used.
+    pub fn is_usable(&self) -> bool {
+        matches!(
+            self.class,
+            FacilityClass::ClosedChest | FacilityClass::AppleTree
+        )
+    }
+
+    /// returns true if the player may use the facility now.
+    ///   inventory is the player's own inventory.
+    pub fn can_be_used_by(&self, player: &Player, inventory: Option<&Inventory>) -> bool {
+        self.is_usable() && !self.is_in_use() && self.requirement.is_met_by(player, inventory)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -255,6 +329,8 @@ impl IndexMut<u64> for FacilityList {
                     class: FacilityClass::ClosedChest,
                     description: "".into(),
                     inventory: Some(u64::MAX),
+                    requirement: Requirement::Always,
+                    loot: None,
                 },
             );
         }
@@ -384,3 +460,47 @@ mod chests {
         }
     }
 }
+
+#[cfg(test)]
+mod requirements {
+    use super::*;
+
+    #[test]
+    fn facilities_without_a_declared_requirement_can_be_used_by_anyone() {
+        let mut inventories = InventoryList::new();
+        let facility_src = vec![r#"≡ 7,7 "A wooden chest" chest1"#.into()];
+        let (facilities, _aliases) = Facility::read_in_facilities(&facility_src, &mut inventories);
+        let (_id, subject) = facilities.iter().next().unwrap();
+
+        assert_eq!(subject.requirement, Requirement::Always);
+        assert!(subject.can_be_used_by(&Player::new(), None));
+    }
+
+    #[test]
+    fn facilities_can_declare_a_requirement_alongside_an_alias() {
+        let mut inventories = InventoryList::new();
+        let facility_src = vec![
+            r#"▲r 9,9 "An old Apple Tree" orchard { requires: any(:can_pick_apples, skill(harvesting, 10)) }"#
+                .into(),
+        ];
+        let (facilities, aliases) = Facility::read_in_facilities(&facility_src, &mut inventories);
+        let (id, subject) = facilities.iter().next().unwrap();
+
+        assert_eq!(aliases.get(&"orchard"), Some(id));
+
+        let mut player = Player::new();
+        assert!(!subject.can_be_used_by(&player, None));
+
+        player.endorse_with(":can_pick_apples");
+        assert!(subject.can_be_used_by(&player, None));
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown requirement 'bogus'")]
+    fn malformed_requirements_are_refused() {
+        let mut inventories = InventoryList::new();
+        let facility_src = vec![r#"▲r 9,9 "An old Apple Tree" { requires: bogus(1) }"#.into()];
+
+        Facility::read_in_facilities(&facility_src, &mut inventories);
+    }
+}
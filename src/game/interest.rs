@@ -0,0 +1,548 @@
+use super::*;
+use std::collections::{HashMap, HashSet};
+
+/// somewhere updates can be sent on to.
+pub trait UpdateSink {
+    fn dispatch(&mut self, update: GameUpdate);
+}
+
+impl UpdateSink for GameUpdateSender {
+    fn dispatch(&mut self, update: GameUpdate) {
+        GameUpdate::send(Some(self), update);
+    }
+}
+
+/// something on the map that a client is told about as it comes into its area of interest,
+///   and told to forget as it leaves.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum Entity {
+    Character(u64),
+    Item(u64),
+    Facility(u64),
+}
+
+impl Entity {
+    /// returns the entity the update is about, if it is about one.
+    pub fn of(update: &GameUpdate) -> Option<Entity> {
+        match update {
+            CharacterEntered { id, .. }
+            | CharacterMoved(id, _, _)
+            | CharacterTeleported(id, _, _)
+            | CharacterExited(id)
+            | CharacterFacingChanged(id, _)
+            | MovementStarted(id, _, _, _)
+            | MovementCompleted(id)
+            | MovementAborted(id) => Some(Entity::Character(*id)),
+            ItemAdded { id, .. } | ItemRemoved(id) => Some(Entity::Item(*id)),
+            FacilityAdded { id, .. } | FacilityUpdated { id, .. } | FacilityRemoved(id) => {
+                Some(Entity::Facility(*id))
+            }
+            _ => None,
+        }
+    }
+
+    /// returns the update telling a client to forget the entity.
+    fn exit(&self) -> GameUpdate {
+        match self {
+            Entity::Character(id) => CharacterExited(*id),
+            Entity::Item(id) => ItemRemoved(*id),
+            Entity::Facility(id) => FacilityRemoved(*id),
+        }
+    }
+}
+
+/// a client's interest in the part of the map around a character, usually its player.
+struct Subscription {
+    update_tx: GameUpdateSender,
+    focus: u64,  // (character_id) at the centre of the area
+    radius: i32, // how far the area reaches from its centre, in any direction
+    known: HashSet<Entity>,
+    chunks: HashSet<(i32, i32)>, // of the map, that the client has been sent
+}
+
+/// sends each client only the updates about the part of the map it is interested in.
+///   Every update passes through, so that what is where is always known, and a client can be
+///   told about an entity as it comes into its area, or be given a snapshot when it joins.
+///   The map itself is sent a chunk at a time, as the client's area comes near each chunk.
+///   Updates that are not about the map or an entity on it go to every client.
+#[derive(Default)]
+pub struct InterestManager {
+    subscriptions: Vec<Subscription>,
+    background: Option<TileMap>,
+    entities: HashMap<Entity, GameUpdate>, // the update introducing each entity, kept current
+}
+
+impl InterestManager {
+    /// how far a client's area of interest reaches, which covers the map window.
+    pub const DEFAULT_RADIUS: i32 = 20;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// adds a client interested in the area within radius of the character, and sends it a
+    ///   snapshot of the map and of what is in the area now.
+    pub fn subscribe(&mut self, update_tx: GameUpdateSender, focus: u64, radius: i32) {
+        let mut subscription = Subscription {
+            update_tx,
+            focus,
+            radius,
+            known: HashSet::new(),
+            chunks: HashSet::new(),
+        };
+
+        if let Some(background) = &self.background {
+            GameUpdate::send(
+                Some(&subscription.update_tx),
+                MapDimensions(background.map_width, background.map_height),
+            );
+        }
+        self.refresh(&mut subscription);
+        self.subscriptions.push(subscription);
+    }
+
+    /// returns where the entity is, if it is known to be on the map.
+    pub fn location_of(&self, entity: &Entity) -> Option<(i32, i32)> {
+        match self.entities.get(entity)? {
+            CharacterEntered { x, y, .. } | ItemAdded { x, y, .. } | FacilityAdded { x, y, .. } => {
+                Some((*x, *y))
+            }
+            _ => None,
+        }
+    }
+
+    /// returns true if the entity is one the client has been told about.
+    fn is_known_by(&self, subscription: &Subscription, entity: &Entity) -> bool {
+        subscription.known.contains(entity)
+    }
+
+    fn is_of_interest_to(&self, subscription: &Subscription, entity: &Entity) -> bool {
+        if *entity == Entity::Character(subscription.focus) {
+            return true;
+        }
+
+        match (
+            self.location_of(&Entity::Character(subscription.focus)),
+            self.location_of(entity),
+        ) {
+            (Some((fx, fy)), Some((x, y))) => {
+                (x - fx).abs() <= subscription.radius && (y - fy).abs() <= subscription.radius
+            }
+            _ => false,
+        }
+    }
+
+    /// sends the client the chunks of the map that its area has come near.
+    fn stream_chunks(&self, subscription: &mut Subscription, updates: &mut Vec<GameUpdate>) {
+        let (background, (focus_x, focus_y)) = match (
+            &self.background,
+            self.location_of(&Entity::Character(subscription.focus)),
+        ) {
+            (Some(background), Some(location)) => (background, location),
+            _ => return,
+        };
+
+        let reach = subscription.radius + MapChunk::SIZE;
+        let (left, top) = MapChunk::containing(focus_x - reach, focus_y - reach);
+        let (right, bottom) = MapChunk::containing(focus_x + reach, focus_y + reach);
+
+        for chunk_y in top..=bottom {
+            for chunk_x in left..=right {
+                if subscription.chunks.contains(&(chunk_x, chunk_y)) {
+                    continue;
+                }
+                if let Some(chunk) = background.chunk(chunk_x, chunk_y) {
+                    subscription.chunks.insert((chunk_x, chunk_y));
+                    updates.push(ChunkLoaded(chunk));
+                }
+            }
+        }
+    }
+
+    /// tells the client about whatever has come into its area, and to forget whatever has left it.
+    fn refresh(&self, subscription: &mut Subscription) {
+        let mut updates = vec![];
+        self.stream_chunks(subscription, &mut updates);
+
+        for entity in subscription.known.clone() {
+            if !self.is_of_interest_to(subscription, &entity) {
+                subscription.known.remove(&entity);
+                updates.push(entity.exit());
+            }
+        }
+
+        let mut newcomers: Vec<(&Entity, &GameUpdate)> = self
+            .entities
+            .iter()
+            .filter(|(entity, _)| {
+                !subscription.known.contains(entity) && self.is_of_interest_to(subscription, entity)
+            })
+            .collect();
+        // in a consistent order, with the focus first.
+        newcomers.sort_by_key(|(entity, _)| match entity {
+            Entity::Character(id) if *id == subscription.focus => (0, 0),
+            Entity::Character(id) => (1, *id),
+            Entity::Item(id) => (2, *id),
+            Entity::Facility(id) => (3, *id),
+        });
+        for (entity, introduction) in newcomers {
+            subscription.known.insert(*entity);
+            updates.push(introduction.clone());
+        }
+
+        for update in updates {
+            GameUpdate::send(Some(&subscription.update_tx), update);
+        }
+    }
+
+    /// keeps what is known about the map up to date with the update.
+    fn track(&mut self, update: &GameUpdate) {
+        match update {
+            SetBackground(map) => self.background = Some(map.clone()),
+            TileChangedAt(x, y, tile, _) => {
+                if let Some(background) = &mut self.background {
+                    background.set_tile_at(*x, *y, *tile);
+                }
+            }
+            CharacterEntered { .. } | ItemAdded { .. } | FacilityAdded { .. } => {
+                self.entities
+                    .insert(Entity::of(update).unwrap(), update.clone());
+            }
+            CharacterMoved(id, new_x, new_y) | CharacterTeleported(id, new_x, new_y) => {
+                if let Some(CharacterEntered { x, y, .. }) =
+                    self.entities.get_mut(&Entity::Character(*id))
+                {
+                    (*x, *y) = (*new_x, *new_y);
+                }
+            }
+            FacilityUpdated {
+                id,
+                description,
+                class,
+            } => {
+                if let Some(FacilityAdded {
+                    description: d,
+                    class: c,
+                    ..
+                }) = self.entities.get_mut(&Entity::Facility(*id))
+                {
+                    (*d, *c) = (description.clone(), class.clone());
+                }
+            }
+            CharacterExited(_) | ItemRemoved(_) | FacilityRemoved(_) => {
+                self.entities.remove(&Entity::of(update).unwrap());
+            }
+            _ => {}
+        }
+    }
+
+    /// sends the update on to the clients it concerns.
+    fn route(&self, update: &GameUpdate, subscription: &mut Subscription) {
+        match update {
+            SetBackground(map) => {
+                subscription.chunks.clear();
+                GameUpdate::send(
+                    Some(&subscription.update_tx),
+                    MapDimensions(map.map_width, map.map_height),
+                );
+                self.refresh(subscription);
+                return;
+            }
+            // a client without the chunk will be sent it, change and all, as it comes near.
+            TileChangedAt(x, y, _, _) => {
+                if subscription.chunks.contains(&MapChunk::containing(*x, *y)) {
+                    GameUpdate::send(Some(&subscription.update_tx), update.clone());
+                }
+                return;
+            }
+            _ => {}
+        }
+
+        let entity = match Entity::of(update) {
+            Some(entity) => entity,
+            None => {
+                GameUpdate::send(Some(&subscription.update_tx), update.clone());
+                return;
+            }
+        };
+
+        match update {
+            CharacterEntered { id, .. }
+            | CharacterMoved(id, _, _)
+            | CharacterTeleported(id, _, _)
+                if *id == subscription.focus =>
+            {
+                subscription.known.insert(entity);
+                GameUpdate::send(Some(&subscription.update_tx), update.clone());
+                self.refresh(subscription);
+            }
+            CharacterEntered { .. }
+            | ItemAdded { .. }
+            | FacilityAdded { .. }
+            | CharacterMoved(_, _, _)
+            | CharacterTeleported(_, _, _) => {
+                let known = self.is_known_by(subscription, &entity);
+                let of_interest = self.is_of_interest_to(subscription, &entity);
+
+                let forwarded = match (known, of_interest) {
+                    (true, true) => Some(update.clone()),
+                    (true, false) => {
+                        subscription.known.remove(&entity);
+                        Some(entity.exit())
+                    }
+                    (false, true) => {
+                        subscription.known.insert(entity);
+                        self.entities.get(&entity).cloned()
+                    }
+                    (false, false) => None,
+                };
+                if let Some(forwarded) = forwarded {
+                    GameUpdate::send(Some(&subscription.update_tx), forwarded);
+                }
+            }
+            CharacterExited(_) | ItemRemoved(_) | FacilityRemoved(_) => {
+                if subscription.known.remove(&entity) {
+                    GameUpdate::send(Some(&subscription.update_tx), update.clone());
+                }
+            }
+            _ => {
+                if self.is_known_by(subscription, &entity) {
+                    GameUpdate::send(Some(&subscription.update_tx), update.clone());
+                }
+            }
+        }
+    }
+}
+
+impl UpdateSink for InterestManager {
+    fn dispatch(&mut self, update: GameUpdate) {
+        self.track(&update);
+
+        let mut subscriptions = std::mem::take(&mut self.subscriptions);
+        for subscription in subscriptions.iter_mut() {
+            self.route(&update, subscription);
+        }
+        self.subscriptions = subscriptions;
+    }
+}
+
+#[cfg(test)]
+mod interest {
+    use super::*;
+    use std::sync::mpsc::{channel, Receiver};
+
+    fn entered(id: u64, x: i32, y: i32) -> GameUpdate {
+        CharacterEntered {
+            id,
+            x,
+            y,
+            character_type: if id == 1 {
+                CharacterType::Player
+            } else {
+                CharacterType::Rat
+            },
+        }
+    }
+
+    fn item_at(id: u64, x: i32, y: i32) -> GameUpdate {
+        ItemAdded {
+            id,
+            x,
+            y,
+            description: "Rat Tail".into(),
+            class: ItemClass::Food,
+        }
+    }
+
+    fn subscribed(subject: &mut InterestManager) -> Receiver<GameUpdate> {
+        let (sender, receiver) = channel();
+        subject.subscribe(sender, 1, 5);
+        receiver
+    }
+
+    fn received(receiver: &Receiver<GameUpdate>) -> Vec<String> {
+        receiver.try_iter().map(|u| format!("{:?}", u)).collect()
+    }
+
+    #[test]
+    fn only_what_is_near_the_focus_is_sent() {
+        let mut subject = InterestManager::new();
+        let receiver = subscribed(&mut subject);
+
+        subject.dispatch(entered(1, 10, 10));
+        subject.dispatch(entered(2, 15, 15));
+        subject.dispatch(entered(3, 16, 10));
+        subject.dispatch(item_at(4, 30, 30));
+        subject.dispatch(GameUpdate::Message(MessageCategory::Chat, "Hello".into()));
+
+        assert_eq!(
+            received(&receiver),
+            vec![
+                format!("{:?}", entered(1, 10, 10)),
+                format!("{:?}", entered(2, 15, 15)),
+                "Message(Chat, \"Hello\")".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn entities_enter_and_exit_as_they_cross_the_area() {
+        let mut subject = InterestManager::new();
+        let receiver = subscribed(&mut subject);
+        subject.dispatch(entered(1, 10, 10));
+        subject.dispatch(entered(2, 16, 10));
+        received(&receiver);
+
+        subject.dispatch(CharacterMoved(2, 15, 10));
+        subject.dispatch(CharacterFacingChanged(2, Direction::Left));
+        subject.dispatch(CharacterMoved(2, 16, 10));
+        subject.dispatch(CharacterFacingChanged(2, Direction::Right));
+
+        assert_eq!(
+            received(&receiver),
+            vec![
+                format!("{:?}", entered(2, 15, 10)),
+                "CharacterFacingChanged(2, Left)".to_string(),
+                "CharacterExited(2)".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn the_area_follows_the_focus() {
+        let mut subject = InterestManager::new();
+        let receiver = subscribed(&mut subject);
+        subject.dispatch(entered(1, 10, 10));
+        subject.dispatch(item_at(2, 4, 10));
+        subject.dispatch(item_at(3, 16, 10));
+        received(&receiver);
+
+        subject.dispatch(CharacterMoved(1, 11, 10));
+
+        assert_eq!(
+            received(&receiver),
+            vec![
+                "CharacterMoved(1, 11, 10)".to_string(),
+                format!("{:?}", item_at(3, 16, 10)),
+            ]
+        );
+
+        subject.dispatch(CharacterTeleported(1, 40, 40));
+        assert_eq!(
+            received(&receiver),
+            vec![
+                "CharacterTeleported(1, 40, 40)".to_string(),
+                "ItemRemoved(3)".to_string(),
+            ]
+        );
+    }
+
+    fn map(width: i32, height: i32) -> TileMap {
+        let mut result = TileMap::new();
+        result.set_dimensions(width, height, tile_map::Tile::DirtFloor);
+        result
+    }
+
+    fn chunks_loaded(updates: &[GameUpdate]) -> Vec<(i32, i32)> {
+        updates
+            .iter()
+            .filter_map(|update| match update {
+                ChunkLoaded(chunk) => Some((chunk.x, chunk.y)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn chunks_of_the_map_are_sent_as_the_focus_approaches_them() {
+        let mut subject = InterestManager::new();
+        let receiver = subscribed(&mut subject);
+
+        subject.dispatch(SetBackground(map(100, 20)));
+        subject.dispatch(entered(1, 10, 10));
+        let updates: Vec<GameUpdate> = receiver.try_iter().collect();
+
+        assert!(matches!(updates[0], MapDimensions(100, 20)));
+        assert_eq!(
+            chunks_loaded(&updates),
+            vec![(0, 0), (1, 0), (0, 1), (1, 1)]
+        );
+
+        subject.dispatch(CharacterMoved(1, 11, 10));
+        let updates: Vec<GameUpdate> = receiver.try_iter().collect();
+        assert_eq!(chunks_loaded(&updates), vec![(2, 0), (2, 1)]);
+
+        // chunks once sent are not sent again.
+        subject.dispatch(CharacterMoved(1, 10, 10));
+        subject.dispatch(CharacterMoved(1, 11, 10));
+        assert!(chunks_loaded(&receiver.try_iter().collect::<Vec<_>>()).is_empty());
+    }
+
+    #[test]
+    fn tile_changes_are_sent_only_to_clients_holding_their_chunk() {
+        let mut subject = InterestManager::new();
+        let receiver = subscribed(&mut subject);
+        subject.dispatch(SetBackground(map(100, 20)));
+        subject.dispatch(entered(1, 10, 10));
+        received(&receiver);
+
+        subject.dispatch(TileChangedAt(3, 3, tile_map::Tile::StoneWall, 1));
+        subject.dispatch(TileChangedAt(90, 3, tile_map::Tile::StoneWall, 1));
+        assert_eq!(
+            received(&receiver),
+            vec!["TileChangedAt(3, 3, stone_wall, 1)".to_string()]
+        );
+
+        subject.dispatch(CharacterTeleported(1, 90, 10));
+        let updates: Vec<GameUpdate> = receiver.try_iter().collect();
+        let chunk = updates
+            .iter()
+            .find_map(|update| match update {
+                ChunkLoaded(chunk) if (chunk.x, chunk.y) == (5, 0) => Some(chunk),
+                _ => None,
+            })
+            .unwrap();
+
+        assert_eq!(chunk.version, 1);
+        assert!(chunk
+            .tiles_with_locations()
+            .any(|(x, y, tile)| (x, y, tile) == (90, 3, tile_map::Tile::StoneWall)));
+    }
+
+    #[test]
+    fn a_late_joiner_gets_a_snapshot_of_its_area() {
+        let mut subject = InterestManager::new();
+        subject.dispatch(SetBackground(map(50, 50)));
+        subject.dispatch(TileChangedAt(3, 3, tile_map::Tile::StoneWall, 1));
+        subject.dispatch(entered(2, 11, 10));
+        subject.dispatch(entered(1, 10, 10));
+        subject.dispatch(CharacterMoved(2, 12, 10));
+        subject.dispatch(item_at(3, 30, 30));
+
+        let receiver = subscribed(&mut subject);
+        let updates: Vec<GameUpdate> = receiver.try_iter().collect();
+
+        assert!(matches!(updates[0], MapDimensions(50, 50)));
+        assert_eq!(
+            chunks_loaded(&updates),
+            vec![(0, 0), (1, 0), (0, 1), (1, 1)]
+        );
+        match &updates[1] {
+            ChunkLoaded(chunk) => {
+                assert_eq!(chunk.version, 1);
+                assert_eq!(chunk.tiles[3 * 16 + 3], tile_map::Tile::StoneWall);
+            }
+            update => panic!("unexpected update: {:?}", update),
+        }
+        assert_eq!(
+            updates[5..]
+                .iter()
+                .map(|u| format!("{:?}", u))
+                .collect::<Vec<String>>(),
+            vec![
+                format!("{:?}", entered(1, 10, 10)),
+                format!("{:?}", entered(2, 12, 10)),
+            ]
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use super::*;
+use std::collections::BTreeMap;
 use std::ops::Index;
 
 #[derive(Debug)]
@@ -54,16 +55,34 @@ pub type InventoryList = HashMap<u64, Inventory>;
 #[derive(Debug, Clone)]
 pub struct Inventory {
     id: u64,
-    pub items: HashMap<u64, Item>,
+    capacity: usize,
+    pub items: BTreeMap<u64, Item>, // ordered so that stacking is deterministic
 }
 
 impl Inventory {
+    /// the number of stacks held by inventories of characters and facilities.
+    pub const CAPACITY: usize = 27;
+
     /// returns an inventory with the given id.  Does not store it in any
     ///    inventory list.
     pub fn new(id: u64) -> Inventory {
+        Self::with_capacity(id, Self::CAPACITY)
+    }
+
+    /// returns an inventory holding at most capacity stacks.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::inventory::*;
+    /// # use muframework::game::items::*;
+    /// let mut subject = Inventory::with_capacity(1, 1);
+    /// subject.force_accept(&Item::new(601, ItemType::new(ItemClass::Tool, "Hatchet"), 1));
+    /// assert!(subject.is_full());
+    /// ```
+    pub fn with_capacity(id: u64, capacity: usize) -> Inventory {
         Inventory {
             id,
-            items: HashMap::new(),
+            capacity,
+            items: BTreeMap::new(),
         }
     }
 
@@ -71,6 +90,11 @@ impl Inventory {
         self.id
     }
 
+    /// returns the number of stacks the inventory can hold.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
     /// returns a reference to a new inventory with the given id that has been stored in
     ///   the given inventory list.
     /// # Examples:
@@ -90,10 +114,75 @@ impl Inventory {
         inventories.get_mut(&id).unwrap()
     }
 
+    /// gives every container in items that lacks one an inventory of its own.
+    ///   Containers are visited in id order so that inventory ids are deterministic.
+    pub fn provide_for_containers(items: &mut ItemList, inventories: &mut InventoryList) {
+        let mut containers: Vec<Item> = items
+            .iter()
+            .map(|(_id, item_state)| ItemState::extract_item(item_state))
+            .filter(|item| item.item_type.is_container() && items.container_of(item.id).is_none())
+            .collect();
+        containers.sort_by_key(|item| item.id);
+
+        for container in containers {
+            let inventory =
+                Inventory::with_capacity(NEXT_ID(), container.item_type.capacity() as usize);
+            items.set_container(container.id, inventory.id);
+            inventories.insert(inventory.id, inventory);
+        }
+    }
+
+    /// returns true if the inventory other_id is the inventory inventory_id or is nested
+    ///   somewhere within the containers it holds.
+    pub fn encloses(
+        inventory_id: u64,
+        other_id: u64,
+        items: &ItemList,
+        inventories: &InventoryList,
+    ) -> bool {
+        if inventory_id == other_id {
+            return true;
+        }
+
+        inventories.get(&inventory_id).is_some_and(|inventory| {
+            inventory.items.keys().any(|item_id| {
+                items.container_of(*item_id).is_some_and(|container_id| {
+                    Self::encloses(container_id, other_id, items, inventories)
+                })
+            })
+        })
+    }
+
     /// returns true if the inventory is full, otherwise false.
     #[inline]
     pub fn is_full(&self) -> bool {
-        self.items.len() > 26
+        self.items.len() >= self.capacity
+    }
+
+    /// returns true if there is room for the item, either in a stack of its type or on its own.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::inventory::*;
+    /// # use muframework::game::items::*;
+    /// let mut subject = Inventory::new(1);
+    /// for id in 0..27 {
+    ///     subject.force_accept(&Item::new(id, ItemType::new(ItemClass::Food, "Apple"), 1));
+    /// }
+    /// assert!(subject.can_accept(&Item::new(99, ItemType::new(ItemClass::Food, "Apple"), 1)));
+    /// assert!(!subject.can_accept(&Item::new(99, ItemType::new(ItemClass::Food, "Pear"), 1)));
+    /// ```
+    pub fn can_accept(&self, item: &Item) -> bool {
+        if !self.is_full() {
+            return true;
+        }
+
+        let room: u32 = self
+            .items
+            .values()
+            .filter(|i| item.is_stackable() && item.is_same_type_as(i))
+            .map(|i| ItemClass::stack_limits(i.class()).saturating_sub(i.quantity) as u32)
+            .sum();
+        room >= item.quantity as u32
     }
 
     /// returns true if the inventory is empty.  false otherwise.
@@ -194,6 +283,9 @@ impl Inventory {
 
         if item.quantity > 0 {
             self.accept(&item, items);
+        } else {
+            // the whole stack was merged into others, so it no longer exists.
+            items.remove(item.id);
         }
     }
 
@@ -236,15 +328,17 @@ impl Inventory {
     }
 
     /// creates an item and places it in the inventory generating
-    ///   an appropriate id.
+    ///   an appropriate id.  returns the item as it was created.
     pub fn spawn_item<S: ToString>(
         &mut self,
         class: ItemClass,
         description: S,
         items: &mut ItemList,
-    ) {
+    ) -> Item {
         let mut item = Item::spawn(class, description);
+        let spawned = item.clone();
         self.accept_stack(&mut item, items);
+        spawned
     }
     /// release item and bundle it at x,y
     ///
@@ -265,10 +359,115 @@ impl Inventory {
     }
 
     /// returns true if inventory holds an item_id.
-    pub fn holds(&mut self, item_id: u64) -> bool {
+    pub fn holds(&self, item_id: u64) -> bool {
         self.items.contains_key(&item_id)
     }
 
+    /// removes quantity items from a held stack and returns them as a new item.
+    ///   The new item is not placed anywhere; returns None unless the stack is held and
+    ///   larger than quantity.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::inventory::*;
+    /// # use muframework::game::items::*;
+    /// let mut subject = Inventory::new(1);
+    /// let mut items = ItemList::new(None);
+    /// let apples = Item::new(601, ItemType::new(ItemClass::Food, "Apple"), 10);
+    /// subject.accept(&apples, &mut items);
+    ///
+    /// let taken = subject.take_from_stack(601, 3, &mut items).unwrap();
+    ///
+    /// assert_eq!(taken.quantity, 3);
+    /// assert_ne!(taken.id, 601);
+    /// assert_eq!(subject[601].quantity, 7);
+    /// assert_eq!(items.get_as_item(601).unwrap().quantity, 7);
+    /// assert!(subject.take_from_stack(601, 7, &mut items).is_none());
+    /// ```
+    pub fn take_from_stack(
+        &mut self,
+        item_id: u64,
+        quantity: u8,
+        items: &mut ItemList,
+    ) -> Option<Item> {
+        let stack = self.items.get_mut(&item_id)?;
+        if quantity == 0 || quantity >= stack.quantity {
+            return None;
+        }
+
+        stack.quantity -= quantity;
+        items.update_item(stack);
+
+        Some(Item::new(NEXT_ITEM_ID(), stack.item_type.clone(), quantity))
+    }
+
+    /// splits quantity items off a held stack into a stack of their own in this inventory.
+    ///   returns the id of the new stack.
+    pub fn split_stack(&mut self, item_id: u64, quantity: u8, items: &mut ItemList) -> Option<u64> {
+        if self.is_full() {
+            return None;
+        }
+        let item = self.take_from_stack(item_id, quantity, items)?;
+
+        items.store(&item, self.id);
+        self.force_accept(&item);
+        Some(item.id)
+    }
+
+    /// returns true if the held stack from_id could be merged into the held stack into_id.
+    pub fn can_merge(&self, from_id: u64, into_id: u64) -> bool {
+        match (self.items.get(&from_id), self.items.get(&into_id)) {
+            (Some(from), Some(into)) => {
+                from_id != into_id && from.is_stackable() && from.is_same_type_as(into)
+            }
+            _ => false,
+        }
+    }
+
+    /// moves as much of the stack from_id as fits into the stack into_id.
+    ///   A stack that is emptied no longer exists.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::inventory::*;
+    /// # use muframework::game::items::*;
+    /// let mut subject = Inventory::new(1);
+    /// let mut items = ItemList::new(None);
+    /// let apple_type = ItemType::new(ItemClass::Food, "Apple");
+    /// subject.accept(&Item::new(601, apple_type.clone(), 60), &mut items);
+    /// subject.accept(&Item::new(602, apple_type.clone(), 10), &mut items);
+    /// subject.accept(&Item::new(603, apple_type.clone(), 2), &mut items);
+    ///
+    /// subject.merge_stacks(602, 601, &mut items);
+    /// assert_eq!((subject[601].quantity, subject[602].quantity), (64, 6));
+    ///
+    /// subject.merge_stacks(603, 602, &mut items);
+    /// assert_eq!(subject[602].quantity, 8);
+    /// assert!(!subject.holds(603));
+    /// assert!(!items.holds(603));
+    /// ```
+    pub fn merge_stacks(&mut self, from_id: u64, into_id: u64, items: &mut ItemList) {
+        use std::cmp::min;
+
+        if !self.can_merge(from_id, into_id) {
+            return;
+        }
+        let mut from = self.items[&from_id].clone();
+        let into = self.items.get_mut(&into_id).expect("merge target held");
+
+        let limit = ItemClass::stack_limits(into.class());
+        let quantity_to_move = min(limit.saturating_sub(into.quantity), from.quantity);
+        into.quantity += quantity_to_move;
+        from.quantity -= quantity_to_move;
+        items.update_item(into);
+
+        if from.quantity == 0 {
+            self.release_item(&from_id);
+            items.remove(from_id);
+        } else {
+            items.update_item(&from);
+            self.items.insert(from_id, from);
+        }
+    }
+
     /// picks up an item from a bundle and puts it into the inventory.
     pub fn pick_up_item(&mut self, item_id: u64, items: &mut ItemList) {
         let possible_item = items.get(item_id);
@@ -349,23 +548,34 @@ mod test_inventory {
 
         &subject[1777];
     }
+    #[test]
+    fn a_stack_over_its_limit_leaves_no_room() {
+        let mut subject = Inventory::with_capacity(1, 1);
+        let limit = ItemClass::stack_limits(ItemClass::Food);
+        subject.force_accept(&Item::new(
+            1,
+            ItemType::new(ItemClass::Food, "Apple"),
+            limit + 1,
+        ));
+
+        assert!(!subject.can_accept(&Item::new(2, ItemType::new(ItemClass::Food, "Apple"), 1)));
+    }
 }
 
 #[cfg(test)]
 mod inventory_spawn_item {
     use super::*;
-    use ItemClass::*;
 
     #[test]
     fn it_stacks_correctly() {
         let mut items = ItemList::new(None);
         let mut subject = Inventory::new(1);
-        let item = Item::spawn(Potion, "Mtn Dew");
+        let item = Item::spawn(ItemClass::Potion, "Mtn Dew");
 
         items.store(&item, subject.id());
         subject.accept(&item, &mut items);
 
-        subject.spawn_item(Potion, "Mtn Dew", &mut items);
+        subject.spawn_item(ItemClass::Potion, "Mtn Dew", &mut items);
 
         assert_eq!(subject[item.id].quantity, 2);
     }
@@ -374,18 +584,17 @@ mod inventory_spawn_item {
 #[cfg(test)]
 mod accept_stack {
     use super::*;
-    use ItemClass::*;
 
     #[test]
     fn if_item_is_non_stackable_it_adds_it_to_inventory_as_new_stack() {
         let mut items = ItemList::new(None);
         let mut subject = Inventory::new(1);
 
-        let mut item = Item::spawn(Headwear, "Mtn Dew Cap");
+        let mut item = Item::spawn(ItemClass::Headwear, "Mtn Dew Cap");
 
         subject.accept_stack(&mut item, &mut items);
 
-        let mut new_item = Item::spawn(Headwear, "Mtn Dew Cap");
+        let mut new_item = Item::spawn(ItemClass::Headwear, "Mtn Dew Cap");
 
         subject.accept_stack(&mut new_item, &mut items);
 
@@ -399,10 +608,10 @@ mod accept_stack {
     fn adds_1_to_the_stack_when_given_an_item_of_1() {
         let mut items = ItemList::new(None);
         let mut subject = Inventory::new(1);
-        let item = Item::spawn(Potion, "Mtn Dew");
+        let item = Item::spawn(ItemClass::Potion, "Mtn Dew");
         let item_id = item.id;
 
-        let mut new_item = Item::spawn_stack(Potion, "Mtn Dew", 7);
+        let mut new_item = Item::spawn_stack(ItemClass::Potion, "Mtn Dew", 7);
 
         items.store(&item, subject.id());
         items.store(&new_item, subject.id());
@@ -419,10 +628,10 @@ mod accept_stack {
     fn only_first_in_multiple_stacks_changed_if_it_can_fit() {
         let mut items = ItemList::new(None);
         let mut subject = Inventory::new(1);
-        let item1 = Item::spawn(Potion, "Mtn Dew");
-        let item2 = Item::spawn_stack(Potion, "Mtn Dew", 2);
+        let item1 = Item::spawn(ItemClass::Potion, "Mtn Dew");
+        let item2 = Item::spawn_stack(ItemClass::Potion, "Mtn Dew", 2);
 
-        let mut new_item = Item::spawn_stack(Potion, "Mtn Dew", 7);
+        let mut new_item = Item::spawn_stack(ItemClass::Potion, "Mtn Dew", 7);
 
         subject.accept(&item1, &mut items);
         subject.accept(&item2, &mut items);
@@ -467,10 +676,10 @@ mod accept_stack {
     fn restricts_stacking_to_same_type_of_item() {
         let mut items = ItemList::new(None);
         let mut subject = Inventory::new(1);
-        let item1 = Item::spawn_stack(Potion, "Mtn Dew", 2);
+        let item1 = Item::spawn_stack(ItemClass::Potion, "Mtn Dew", 2);
         subject.accept(&item1, &mut items);
 
-        let mut new_item = Item::spawn_stack(Food, "Apple", 7);
+        let mut new_item = Item::spawn_stack(ItemClass::Food, "Apple", 7);
         subject.accept_stack(&mut new_item, &mut items);
 
         assert_eq!(item1.quantity, 2);
@@ -482,10 +691,10 @@ mod accept_stack {
     fn adds_new_stack_if_existing_stack_is_completely_full() {
         let mut items = ItemList::new(None);
         let mut subject = Inventory::new(1);
-        let item1 = Item::spawn_stack(Potion, "Mtn Dew", 18);
+        let item1 = Item::spawn_stack(ItemClass::Potion, "Mtn Dew", 18);
         subject.accept(&item1, &mut items);
 
-        let mut new_item = Item::spawn_stack(Food, "Apple", 7);
+        let mut new_item = Item::spawn_stack(ItemClass::Food, "Apple", 7);
         subject.accept_stack(&mut new_item, &mut items);
 
         assert_eq!(item1.quantity, 18);
@@ -497,10 +706,10 @@ mod accept_stack {
     fn adds_new_stack_with_remainder_if_existing_stack_is_almost_full() {
         let mut items = ItemList::new(None);
         let mut subject = Inventory::new(1);
-        let item1 = Item::spawn_stack(Potion, "Mtn Dew", 15);
+        let item1 = Item::spawn_stack(ItemClass::Potion, "Mtn Dew", 15);
         subject.accept(&item1, &mut items);
 
-        let mut new_item = Item::spawn_stack(Potion, "Mtn Dew", 7);
+        let mut new_item = Item::spawn_stack(ItemClass::Potion, "Mtn Dew", 7);
 
         subject.accept_stack(&mut new_item, &mut items);
 
@@ -513,10 +722,10 @@ mod accept_stack {
     fn if_finishes_with_zero_stack_is_removed_from_inventory_and_items() {
         let mut items = ItemList::new(None);
         let mut subject = Inventory::new(1);
-        let item1 = Item::spawn_stack(Potion, "Mtn Dew", 9);
+        let item1 = Item::spawn_stack(ItemClass::Potion, "Mtn Dew", 9);
         subject.accept(&item1, &mut items);
 
-        let mut new_item = Item::spawn_stack(Potion, "Mtn Dew", 7);
+        let mut new_item = Item::spawn_stack(ItemClass::Potion, "Mtn Dew", 7);
 
         subject.accept_stack(&mut new_item, &mut items);
 
@@ -0,0 +1,231 @@
+use super::*;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::fmt;
+
+/// the kind of an item, named in the item class data file.
+///   The classes the code refers to by name are provided as constants.
+#[derive(Hash, Eq, PartialEq, Copy, Clone, Ord, PartialOrd)]
+pub struct ItemClass(&'static str);
+
+#[allow(non_upper_case_globals)]
+impl ItemClass {
+    pub const BladeWeapon: ItemClass = ItemClass("bladeweapon");
+    pub const Dagger: ItemClass = ItemClass("dagger");
+    pub const Shield: ItemClass = ItemClass("shield");
+    pub const SoftArmor: ItemClass = ItemClass("softarmor");
+    pub const Pants: ItemClass = ItemClass("pants");
+    pub const Gloves: ItemClass = ItemClass("gloves");
+    pub const Shoes: ItemClass = ItemClass("shoes");
+    pub const Headwear: ItemClass = ItemClass("headwear");
+    pub const Tool: ItemClass = ItemClass("tool");
+    pub const Potion: ItemClass = ItemClass("potion");
+    pub const Food: ItemClass = ItemClass("food");
+    pub const Container: ItemClass = ItemClass("container");
+    pub const Ring: ItemClass = ItemClass("ring");
+}
+
+impl ItemClass {
+    /// returns every class defined in the data file, in the order defined.
+    pub fn all() -> Vec<ItemClass> {
+        ITEM_CLASSES
+            .definitions
+            .iter()
+            .map(|definition| ItemClass(&definition.name))
+            .collect()
+    }
+
+    /// returns the class with the given name, ignoring case, or None if it is not defined.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::items::ItemClass;
+    /// assert_eq!(ItemClass::parse("BladeWeapon"), Some(ItemClass::BladeWeapon));
+    /// assert_eq!(ItemClass::parse("amulet").map(|c| c.name()), Some("amulet"));
+    /// assert_eq!(ItemClass::parse("spoon"), None);
+    /// ```
+    pub fn parse<S: ToString>(name: S) -> Option<ItemClass> {
+        let name = name.to_string().to_lowercase();
+
+        ITEM_CLASSES
+            .find(|definition| definition.name == name)
+            .map(|definition| ItemClass(&definition.name))
+    }
+
+    pub fn from_name<S: ToString>(name: S) -> ItemClass {
+        Self::parse(name).expect("unknown item class")
+    }
+
+    /// returns the class whose items are marked with the symbol in map files.
+    pub fn from_symbol<S: ToString>(symbol: S) -> ItemClass {
+        let symbol = symbol.to_string().chars().next();
+
+        ITEM_CLASSES
+            .find(|definition| definition.symbol.is_some() && definition.symbol == symbol)
+            .map(|definition| ItemClass(&definition.name))
+            .expect("unknown item class")
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.0
+    }
+
+    pub fn definition(&self) -> &'static ItemClassDefinition {
+        ITEM_CLASSES
+            .find(|definition| definition.name == self.0)
+            .unwrap_or(&UNDEFINED_CLASS)
+    }
+
+    pub fn stack_limits(class: ItemClass) -> u8 {
+        class.definition().stack_limit
+    }
+}
+
+impl fmt::Debug for ItemClass {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}", self.0)
+    }
+}
+
+/// what the item class data file says about a class.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ItemClassDefinition {
+    pub name: String,
+    pub symbol: Option<char>,
+    pub glyph: char,
+    pub color: String, // as #RRGGBB
+    pub stack_limit: u8,
+    pub fitting: Fitting,
+}
+
+impl ItemClassDefinition {
+    pub fn new<S: ToString>(name: S) -> Self {
+        Self {
+            name: name.to_string(),
+            symbol: None,
+            glyph: '?',
+            color: "#FFFFFF".into(),
+            stack_limit: 1,
+            fitting: Fitting::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ItemClassList {
+    definitions: Vec<ItemClassDefinition>,
+}
+
+impl ItemClassList {
+    /// the item class data file, built into the game so that it runs from any directory.
+    pub const DATA: &'static str = include_str!("../../data/item_classes.txt");
+
+    /// returns the item classes of the item class data file.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::item_class::*;
+    /// let subject = ItemClassList::built_in();
+    /// assert!(subject.get("dagger").is_some());
+    /// ```
+    pub fn built_in() -> ItemClassList {
+        Self::read_in_item_classes(Self::DATA).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// reads item class definitions in the form used by the item class data file.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::item_class::*;
+    /// # use muframework::game::equipment::*;
+    /// let subject = ItemClassList::read_in_item_classes(
+    ///     "ring {\n  symbol: =\n  mounting_points: LeftWrist | RightWrist\n}\nrock",
+    /// )
+    /// .unwrap();
+    ///
+    /// let ring = subject.get("ring").unwrap();
+    /// assert_eq!(ring.symbol, Some('='));
+    /// assert_eq!(ring.glyph, '=');
+    /// assert_eq!(
+    ///     ring.fitting,
+    ///     Fitting::any_of(&[MountingPoint::LeftWrist, MountingPoint::RightWrist])
+    /// );
+    /// assert_eq!(subject.get("rock").unwrap().stack_limit, 1);
+    /// assert!(ItemClassList::read_in_item_classes("rock {\n  weight: 12\n}").is_err());
+    /// ```
+    pub fn read_in_item_classes(contents: &str) -> Result<ItemClassList, String> {
+        let mut result = ItemClassList::default();
+
+        let re = Regex::new(r#"(?m)^(\w+)[ \t]*(?:\{([^}]*)\})?[ \t]*(?://.*)?$"#).unwrap();
+
+        for captures in re.captures_iter(contents) {
+            let name = capture_string(&captures, 1).to_lowercase();
+            let attributes = capture_optional_string(&captures, 2);
+
+            let mut definition = ItemClassDefinition::new(name);
+            Self::read_in_class_attributes_for(&mut definition, attributes)?;
+            result.definitions.push(definition);
+        }
+
+        Ok(result)
+    }
+
+    fn read_in_class_attributes_for(
+        definition: &mut ItemClassDefinition,
+        attributes: &str,
+    ) -> Result<(), String> {
+        let re = Regex::new(r#"^(\w+):\s+(.+?)(?:\s+//.*)?$"#).unwrap();
+        let mut glyph = None;
+
+        for attribute in attributes
+            .lines()
+            .map(|a| a.trim())
+            .filter(|a| !a.is_empty())
+        {
+            let captures = re
+                .captures(attribute)
+                .ok_or_else(|| format!("unable to parse attribute: {}", attribute))?;
+
+            let attribute_name = capture_string(&captures, 1);
+            let attribute_value = capture_string(&captures, 2);
+            let character = || {
+                attribute_value
+                    .chars()
+                    .next()
+                    .filter(|_| attribute_value.chars().count() == 1)
+                    .ok_or_else(|| format!("expected a single character: {}", attribute))
+            };
+
+            match attribute_name {
+                "symbol" => definition.symbol = Some(character()?),
+                "glyph" => glyph = Some(character()?),
+                "color" => definition.color = attribute_value.to_string(),
+                "stack_limit" => {
+                    definition.stack_limit = attribute_value
+                        .parse::<u8>()
+                        .map_err(|_| format!("unable to parse stack limit: {}", attribute))?
+                }
+                "mounting_points" => definition.fitting = Fitting::parse(attribute_value)?,
+                _ => return Err(format!("unrecognized attribute: {}", attribute_name)),
+            }
+        }
+
+        definition.glyph = glyph.or(definition.symbol).unwrap_or('?');
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ItemClassDefinition> {
+        self.find(|definition| definition.name == name)
+    }
+
+    fn find<P: Fn(&ItemClassDefinition) -> bool>(
+        &self,
+        predicate: P,
+    ) -> Option<&ItemClassDefinition> {
+        self.definitions
+            .iter()
+            .find(|definition| predicate(definition))
+    }
+}
+
+lazy_static! {
+    static ref ITEM_CLASSES: ItemClassList = ItemClassList::built_in();
+    static ref UNDEFINED_CLASS: ItemClassDefinition = ItemClassDefinition::new("undefined");
+}
@@ -2,62 +2,6 @@ pub use super::*;
 use regex::Regex;
 use std::collections::HashMap;
 use std::ops::{Index, IndexMut};
-use ItemClass::*;
-
-#[derive(Debug, Hash, Eq, PartialEq, Copy, Clone, Ord, PartialOrd)]
-pub enum ItemClass {
-    BladeWeapon,
-    Dagger,
-    Shield,
-    SoftArmor,
-    Pants,
-    Gloves,
-    Shoes,
-    Headwear,
-    Tool,
-    Potion,
-    Food,
-}
-
-impl ItemClass {
-    pub fn from_symbol<S: ToString>(symbol: S) -> ItemClass {
-        match &symbol.to_string()[..] {
-            "↓" => Dagger,
-            "^" => Headwear,
-            "!" => BladeWeapon,
-            "¡" => Potion,
-            "♠" => Tool,
-            _ => panic!("unknown item class"),
-        }
-    }
-
-    pub fn from_name<S: ToString>(name: S) -> ItemClass {
-        match &name.to_string()[..] {
-            "bladeweapon" => BladeWeapon,
-            "dagger" => Dagger,
-            "shield" => Shield,
-            "softarmor" => SoftArmor,
-            "pants" => Pants,
-            "gloves" => Gloves,
-            "shoes" => Shoes,
-            "headwear" => Headwear,
-            "tool" => Tool,
-            "potion" => Potion,
-            "food" => Food,
-            _ => panic!("unknown item class"),
-        }
-    }
-
-    pub fn stack_limits(class: ItemClass) -> u8 {
-        use ItemClass::*;
-
-        match class {
-            Food => 64,
-            Potion => 16,
-            _ => 1,
-        }
-    }
-}
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum ItemState {
@@ -93,6 +37,10 @@ pub struct ItemType {
     class: ItemClass,
     description: String,
     endorsements: Vec<String>,
+    requirement: Requirement, // what a player needs in order to equip items of this type
+    capacity: u8,             // the number of stacks items of this type can hold, if containers
+    fitting: Option<Fitting>, // where items of this type are mounted, if not where their class is
+    speed: i8,                // added to the speed of a player who has items of this type equipped
 }
 
 impl ItemType {
@@ -101,13 +49,91 @@ impl ItemType {
             class,
             description: description.to_string(),
             endorsements: vec![],
+            requirement: Requirement::Always,
+            capacity: 0,
+            fitting: None,
+            speed: 0,
         }
     }
 
+    pub fn class(&self) -> ItemClass {
+        self.class
+    }
+
     pub fn add_endorsement<S: ToString>(&mut self, endorsement: S) {
         self.endorsements.push(endorsement.to_string());
     }
 
+    /// requires a level in a skill to equip items of this type, as well as whatever else
+    ///   their requirement asks for.
+    pub fn require_skill(&mut self, skill: Skill, level: u8) {
+        self.requirement =
+            std::mem::take(&mut self.requirement).and(Requirement::SkillLevel(skill, level));
+    }
+
+    /// returns the skill and level needed to equip items of this type, if their requirement
+    ///   insists upon one.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::items::*;
+    /// # use muframework::game::skills::Skill;
+    /// let mut subject = ItemType::new(ItemClass::BladeWeapon, "Sharp Short Sword");
+    /// assert_eq!(subject.skill_requirement(), None);
+    /// subject.require_skill(Skill::Combat, 5);
+    /// assert_eq!(subject.skill_requirement(), Some((Skill::Combat, 5)));
+    /// ```
+    pub fn skill_requirement(&self) -> Option<(Skill, u8)> {
+        self.requirement.skill_levels().into_iter().next()
+    }
+
+    pub fn requirement(&self) -> &Requirement {
+        &self.requirement
+    }
+
+    pub fn set_requirement(&mut self, requirement: Requirement) {
+        self.requirement = requirement;
+    }
+
+    /// returns the number of stacks an item of this type can hold.
+    pub fn capacity(&self) -> u8 {
+        self.capacity
+    }
+
+    /// makes items of this type containers holding up to capacity stacks.
+    pub fn set_capacity(&mut self, capacity: u8) {
+        self.capacity = capacity;
+    }
+
+    /// returns true if items of this type have inventories of their own.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::items::*;
+    /// let mut subject = ItemType::new(ItemClass::Container, "Backpack");
+    /// assert!(!subject.is_container());
+    /// subject.set_capacity(12);
+    /// assert!(subject.is_container());
+    /// ```
+    pub fn is_container(&self) -> bool {
+        self.capacity > 0
+    }
+
+    /// returns where items of this type are mounted, if it differs from their class.
+    pub fn fitting(&self) -> Option<&Fitting> {
+        self.fitting.as_ref()
+    }
+
+    pub fn set_fitting(&mut self, fitting: Fitting) {
+        self.fitting = Some(fitting);
+    }
+
+    pub fn speed(&self) -> i8 {
+        self.speed
+    }
+
+    pub fn set_speed(&mut self, speed: i8) {
+        self.speed = speed;
+    }
+
     pub fn read_in_item_types(items: &mut Vec<String>) -> ItemTypeList {
         let mut result = ItemTypeList::new();
         let long_string = items.join("\n");
@@ -132,21 +158,62 @@ impl ItemType {
         result
     }
 
+    /// reads the attributes of an item type.  required_skill and required_level are a
+    ///   shorthand for a skill level the type's requirement insists upon as well.
     fn read_in_type_attributes_for(new_type: &mut ItemType, attributes: &str) {
         if attributes.is_empty() {
             return;
         }
+        let mut required_skill = None;
+        let mut required_level = 1;
 
-        let re = Regex::new(r#"^(endorsement):\s+(:\w+|\d+)(?:\s*//.*)?$"#).unwrap();
-
-        let captures = re.captures(attributes).expect("unable to parse attribute");
-
-        let attribute_name = capture_string(&captures, 1);
-        let attribute_value = capture_string(&captures, 2);
+        let re = Regex::new(
+            r#"^(endorsement|required_skill|required_level|requires|capacity|mounting_points|speed):\s+(.+?)(?:\s*//.*)?$"#,
+        )
+        .unwrap();
+
+        for attribute in attributes
+            .lines()
+            .map(|a| a.trim())
+            .filter(|a| !a.is_empty())
+        {
+            let captures = re.captures(attribute).expect("unable to parse attribute");
+
+            let attribute_name = capture_string(&captures, 1);
+            let attribute_value = capture_string(&captures, 2);
+
+            match attribute_name {
+                "endorsement" => new_type.add_endorsement(attribute_value),
+                "required_skill" => {
+                    required_skill = Some(Skill::from_name(attribute_value.to_lowercase()))
+                }
+                "required_level" => {
+                    required_level = attribute_value
+                        .parse::<u8>()
+                        .expect("unable to parse required level")
+                }
+                "requires" => new_type.set_requirement(
+                    Requirement::parse(attribute_value).unwrap_or_else(|error| panic!("{}", error)),
+                ),
+                "capacity" => new_type.set_capacity(
+                    attribute_value
+                        .parse::<u8>()
+                        .expect("unable to parse capacity"),
+                ),
+                "speed" => new_type.set_speed(
+                    attribute_value
+                        .parse::<i8>()
+                        .expect("unable to parse speed"),
+                ),
+                "mounting_points" => new_type.set_fitting(
+                    Fitting::parse(attribute_value).unwrap_or_else(|error| panic!("{}", error)),
+                ),
+                _ => panic!("unrecognized attribute: {}", attribute_name),
+            }
+        }
 
-        match attribute_name {
-            "endorsement" => new_type.add_endorsement(attribute_value),
-            _ => panic!("unrecognized attribute: {}", attribute_name),
+        if let Some(skill) = required_skill {
+            new_type.require_skill(skill, required_level);
         }
     }
 }
@@ -203,10 +270,9 @@ impl Item {
         format!("{} {}", prefix, inflected_description).clone()
     }
 
-    pub fn endorse(&self,player: &mut Player) {
-        for endorsement in &self.item_type.endorsements {
-            player.endorse_with(endorsement);
-        }
+    /// returns the endorsements granted while the item is equipped.
+    pub fn endorsements(&self) -> &Vec<String> {
+        &self.item_type.endorsements
     }
 
     /// creates a new item assigning it its Id as appropriate.
@@ -334,7 +400,9 @@ impl Item {
 #[derive(Debug, Clone)]
 pub struct ItemList {
     items: HashMap<u64, ItemState>,
+    containers: HashMap<u64, u64>, // (item_id, inventory_id) of each container
     pub item_types: ItemTypeList,
+    pub loot_tables: LootTableList,
 }
 
 impl ItemList {
@@ -347,7 +415,9 @@ impl ItemList {
 
         ItemList {
             items: HashMap::new(),
+            containers: HashMap::new(),
             item_types,
+            loot_tables: LootTableList::new(),
         }
     }
 
@@ -397,14 +467,16 @@ impl ItemList {
         self.items.contains_key(&item_id)
     }
 
+    /// returns the nth item bundled at x,y.  Items are ordered by id.
     pub fn find_nth_at(&self, x: i32, y: i32, n: i32) -> Option<&ItemState> {
-        let item_state = self
+        let mut item_states: Vec<(&u64, &ItemState)> = self
             .items
             .iter()
             .filter(|(_i, item_state)| item_state.is_bundled_at(x, y))
-            .nth(n as usize);
+            .collect();
+        item_states.sort_by_key(|(id, _)| **id);
 
-        match item_state {
+        match item_states.get(n as usize) {
             None => None,
             Some((_, item_state)) => Some(item_state),
         }
@@ -475,6 +547,22 @@ impl ItemList {
         }
     }
 
+    /// forgets the item entirely, as when its stack has been merged into another.
+    pub fn remove(&mut self, item_id: u64) {
+        self.items.remove(&item_id);
+        self.containers.remove(&item_id);
+    }
+
+    /// returns the id of the container's own inventory, or None if the item is not a container.
+    pub fn container_of(&self, item_id: u64) -> Option<u64> {
+        self.containers.get(&item_id).copied()
+    }
+
+    /// records that inventory_id is the inventory within the container item_id.
+    pub fn set_container(&mut self, item_id: u64, inventory_id: u64) {
+        self.containers.insert(item_id, inventory_id);
+    }
+
     pub fn iter(&self) -> std::collections::hash_map::Iter<'_, u64, ItemState> {
         self.items.iter()
     }
@@ -533,7 +621,7 @@ mod item_list_index_mut {
         let item = Item {
             id: 1776,
             quantity: 1,
-            item_type: ItemType::new(Headwear, "blah"),
+            item_type: ItemType::new(ItemClass::Headwear, "blah"),
         };
         subject[1776] = ItemState::Equipped(item.clone(), 1);
 
@@ -547,7 +635,7 @@ mod update_item {
 
     #[test]
     fn it_updates_a_stored_item() {
-        let item = Item::spawn(Potion, "Coca-Cola");
+        let item = Item::spawn(ItemClass::Potion, "Coca-Cola");
         let item_id = item.id;
         let new_item = Item::new(item.id, ItemType::new(item.class(), item.description()), 8);
         let mut subject = ItemList::new(None);
@@ -561,7 +649,7 @@ mod update_item {
     }
     #[test]
     fn it_updates_a_bundle_item() {
-        let item = Item::spawn(Potion, "Coca-Cola");
+        let item = Item::spawn(ItemClass::Potion, "Coca-Cola");
         let item_id = item.id;
         let new_item = Item::new(item.id, ItemType::new(item.class(), item.description()), 8);
 
@@ -28,39 +28,57 @@ impl Level {
         update_tx: Option<&GameUpdateSender>,
     ) {
         for character in characters.iter() {
-            GameUpdate::send(
-                update_tx,
-                CharacterEntered {
-                    id: character.id,
-                    x: character.x,
-                    y: character.y,
-                    character_type: character.character_type,
-                },
-            );
-            obstacles.block_at(character.x, character.y);
+            Self::introduce_character(&character, obstacles, update_tx);
         }
     }
 
+    pub fn introduce_character(
+        character: &Character,
+        obstacles: &mut BlockingMap,
+        update_tx: Option<&GameUpdateSender>,
+    ) {
+        GameUpdate::send(
+            update_tx,
+            CharacterEntered {
+                id: character.id,
+                x: character.x,
+                y: character.y,
+                character_type: character.character_type,
+            },
+        );
+        obstacles.block_at(character.x, character.y);
+    }
+
     pub fn introduce_items(items: &ItemList, update_tx: Option<&GameUpdateSender>) {
         for (index, item) in items.iter() {
             match item {
                 ItemState::Bundle(item, x, y) => {
-                    GameUpdate::send(
-                        update_tx,
-                        ItemAdded {
-                            id: *index,
-                            x: *x,
-                            y: *y,
-                            class: item.class(),
-                            description: item.description(),
-                        },
-                    );
+                    Self::introduce_item(*index, item, *x, *y, update_tx);
                 }
                 _ => {} // only introduce bundles
             }
         }
     }
 
+    pub fn introduce_item(
+        index: u64,
+        item: &Item,
+        x: i32,
+        y: i32,
+        update_tx: Option<&GameUpdateSender>,
+    ) {
+        GameUpdate::send(
+            update_tx,
+            ItemAdded {
+                id: index,
+                x,
+                y,
+                class: item.class(),
+                description: item.description(),
+            },
+        );
+    }
+
     pub fn introduce_facilities(
         facilities: &FacilityList,
         map: &mut TileMap,
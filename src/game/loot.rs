@@ -0,0 +1,363 @@
+use super::*;
+use regex::Regex;
+use std::collections::HashMap;
+
+/// one of the outcomes a loot table can roll.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum LootEntry {
+    Item { item_type: String, min: u8, max: u8 },
+    Table(String), // another loot table, rolled in turn
+    Nothing,
+}
+
+/// a weighted list of what a chest holds or a creature drops, as declared in a map file:
+/// ```text
+/// chest_loot {
+///     rolls: 2                 // the number of times the table is rolled
+///     dropped_by: Rat, Spider  // the creatures that drop the loot
+///     3 pink_potion 1-2        // weight, item type and quantity range
+///     1 @trinkets              // another loot table
+///     2 nothing
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct LootTable {
+    pub rolls: u8,
+    pub entries: Vec<(u32, LootEntry)>, // (weight, entry)
+    pub dropped_by: Vec<CharacterType>,
+}
+
+impl Default for LootTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LootTable {
+    pub fn new() -> Self {
+        Self {
+            rolls: 1,
+            entries: vec![],
+            dropped_by: vec![],
+        }
+    }
+
+    pub fn add_entry(&mut self, weight: u32, entry: LootEntry) {
+        self.entries.push((weight, entry));
+    }
+
+    fn total_weight(&self) -> u64 {
+        self.entries.iter().map(|(weight, _)| *weight as u64).sum()
+    }
+
+    /// picks an entry with a chance in proportion to its weight.
+    fn pick(&self, rng: &mut Rng) -> Option<&LootEntry> {
+        let mut roll = rng.below(self.total_weight());
+
+        for (weight, entry) in self.entries.iter() {
+            if roll < *weight as u64 {
+                return Some(entry);
+            }
+            roll -= *weight as u64;
+        }
+        None
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LootTableList {
+    tables: HashMap<String, LootTable>,
+}
+
+impl LootTableList {
+    /// tables nested deeper than this are not rolled, so that a table naming itself ends.
+    pub const MAX_DEPTH: u8 = 8;
+
+    pub fn new() -> Self {
+        Self {
+            tables: HashMap::new(),
+        }
+    }
+
+    pub fn insert<S: ToString>(&mut self, name: S, table: LootTable) {
+        self.tables.insert(name.to_string(), table);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&LootTable> {
+        self.tables.get(name)
+    }
+
+    /// returns the name of the table dropped by creatures of the character type, if any.
+    pub fn table_for(&self, character_type: CharacterType) -> Option<&str> {
+        let mut names: Vec<&String> = self
+            .tables
+            .iter()
+            .filter(|(_, table)| table.dropped_by.contains(&character_type))
+            .map(|(name, _)| name)
+            .collect();
+
+        names.sort();
+        names.first().map(|name| name.as_str())
+    }
+
+    /// rolls the table, returning the item type names and quantities won.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::loot::*;
+    /// # use muframework::game::random::Rng;
+    /// let mut subject = LootTableList::new();
+    /// let mut gems = LootTable::new();
+    /// gems.add_entry(1, LootEntry::Item { item_type: "ruby".into(), min: 1, max: 1 });
+    /// let mut chest = LootTable::new();
+    /// chest.rolls = 2;
+    /// chest.add_entry(1, LootEntry::Table("gems".into()));
+    /// subject.insert("gems", gems);
+    /// subject.insert("chest", chest);
+    ///
+    /// let result = subject.roll("chest", &mut Rng::seeded(1));
+    /// assert_eq!(result, vec![("ruby".to_string(), 1), ("ruby".to_string(), 1)]);
+    /// assert!(subject.roll("no_such_table", &mut Rng::seeded(1)).is_empty());
+    /// ```
+    pub fn roll(&self, name: &str, rng: &mut Rng) -> Vec<(String, u8)> {
+        let mut result = vec![];
+        self.roll_into(name, rng, 0, &mut result);
+        result
+    }
+
+    fn roll_into(&self, name: &str, rng: &mut Rng, depth: u8, result: &mut Vec<(String, u8)>) {
+        let table = match self.tables.get(name) {
+            Some(table) if depth < Self::MAX_DEPTH => table,
+            _ => return,
+        };
+
+        for _ in 0..table.rolls {
+            match table.pick(rng) {
+                Some(LootEntry::Item {
+                    item_type,
+                    min,
+                    max,
+                }) => {
+                    let quantity = rng.between(*min as u32, *max as u32) as u8;
+                    if quantity > 0 {
+                        result.push((item_type.clone(), quantity));
+                    }
+                }
+                Some(LootEntry::Table(nested)) => self.roll_into(nested, rng, depth + 1, result),
+                Some(LootEntry::Nothing) | None => {}
+            }
+        }
+    }
+
+    /// rolls the table and spawns the items won, in stacks no larger than their class allows.
+    pub fn spawn(&self, name: &str, item_types: &ItemTypeList, rng: &mut Rng) -> Vec<Item> {
+        let mut result = vec![];
+
+        for (item_type_name, quantity) in self.roll(name, rng) {
+            let item_type = item_types
+                .get(&item_type_name)
+                .unwrap_or_else(|| panic!("unknown item type in loot: {}", item_type_name));
+            let limit = ItemClass::stack_limits(item_type.class());
+
+            let mut remaining = quantity;
+            while remaining > 0 {
+                let stack = remaining.min(limit);
+                result.push(Item::spawn_from_type(&item_type_name, stack, item_types));
+                remaining -= stack;
+            }
+        }
+        result
+    }
+
+    pub fn read_in_loot_tables(loot_tables: &[String]) -> LootTableList {
+        let mut result = LootTableList::new();
+        let long_string = loot_tables.join("\n");
+
+        let re = Regex::new(r#"(?m)^(\w+)\s*\{([^}]*)\}"#).unwrap();
+
+        for captures in re.captures_iter(&long_string[..]) {
+            let name = capture_string(&captures, 1);
+            let body = capture_string(&captures, 2);
+
+            result.insert(name, Self::read_in_loot_table(body));
+        }
+
+        result
+    }
+
+    fn read_in_loot_table(body: &str) -> LootTable {
+        let mut result = LootTable::new();
+
+        let attribute_re = Regex::new(r#"^(rolls|dropped_by):\s+(.+)$"#).unwrap();
+        let entry_re = Regex::new(r#"^(\d+)\s+(@?\w+)(?:\s+(\d+)(?:\s*-\s*(\d+))?)?$"#).unwrap();
+
+        for line in body
+            .lines()
+            .map(|l| l.split("//").next().unwrap().trim())
+            .filter(|l| !l.is_empty())
+        {
+            if let Some(captures) = attribute_re.captures(line) {
+                let value = capture_string(&captures, 2);
+
+                match capture_string(&captures, 1) {
+                    "rolls" => result.rolls = value.parse::<u8>().expect("unable to parse rolls"),
+                    _ => {
+                        result.dropped_by = value
+                            .split(',')
+                            .map(|name| CharacterType::from_name(name.trim()))
+                            .collect()
+                    }
+                }
+                continue;
+            }
+
+            let captures = entry_re
+                .captures(line)
+                .unwrap_or_else(|| panic!("unable to parse loot entry: {}", line));
+            let weight = capture_string(&captures, 1)
+                .parse::<u32>()
+                .expect("unable to parse weight");
+            let name = capture_string(&captures, 2);
+            let min = captures.get(3).map_or(1, |m| {
+                m.as_str().parse::<u8>().expect("unable to parse quantity")
+            });
+            let max = captures.get(4).map_or(min, |m| {
+                m.as_str().parse::<u8>().expect("unable to parse quantity")
+            });
+
+            let entry = match name {
+                "nothing" => LootEntry::Nothing,
+                _ if name.starts_with('@') => LootEntry::Table(name[1..].to_string()),
+                _ => LootEntry::Item {
+                    item_type: name.to_string(),
+                    min,
+                    max,
+                },
+            };
+            result.add_entry(weight, entry);
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod loot_table_list {
+    use super::*;
+
+    fn loot_tables() -> LootTableList {
+        LootTableList::read_in_loot_tables(&vec![
+            "chest_loot {".into(),
+            "    rolls: 3".into(),
+            "    3 pink_potion 2-20 // a lot of potions".into(),
+            "    1 @trinkets".into(),
+            "}".into(),
+            "trinkets {".into(),
+            "    dropped_by: Rat, Spider".into(),
+            "    1 baseball_cap".into(),
+            "    1 nothing".into(),
+            "}".into(),
+            "recursive {".into(),
+            "    1 @recursive".into(),
+            "}".into(),
+        ])
+    }
+
+    fn item_types() -> ItemTypeList {
+        let mut result = ItemTypeList::new();
+        result.insert(
+            "pink_potion".into(),
+            ItemType::new(ItemClass::Potion, "Pink Potion"),
+        );
+        result.insert(
+            "baseball_cap".into(),
+            ItemType::new(ItemClass::Headwear, "Baseball Cap"),
+        );
+        result
+    }
+
+    #[test]
+    fn tables_are_read_in_with_their_entries() {
+        let subject = loot_tables();
+        let chest_loot = subject.get("chest_loot").unwrap();
+
+        assert_eq!(chest_loot.rolls, 3);
+        assert_eq!(
+            chest_loot.entries,
+            vec![
+                (
+                    3,
+                    LootEntry::Item {
+                        item_type: "pink_potion".into(),
+                        min: 2,
+                        max: 20
+                    }
+                ),
+                (1, LootEntry::Table("trinkets".into())),
+            ]
+        );
+        assert_eq!(
+            subject.get("trinkets").unwrap().entries[1].1,
+            LootEntry::Nothing
+        );
+        assert_eq!(subject.table_for(CharacterType::Spider), Some("trinkets"));
+        assert_eq!(subject.table_for(CharacterType::Player), None);
+    }
+
+    #[test]
+    fn the_same_seed_rolls_the_same_loot() {
+        let subject = loot_tables();
+
+        for seed in 0..20 {
+            assert_eq!(
+                subject.roll("chest_loot", &mut Rng::seeded(seed)),
+                subject.roll("chest_loot", &mut Rng::seeded(seed))
+            );
+        }
+    }
+
+    #[test]
+    fn rolls_stay_within_the_quantity_ranges() {
+        let subject = loot_tables();
+        let mut rng = Rng::seeded(42);
+
+        for _ in 0..50 {
+            let result = subject.roll("chest_loot", &mut rng);
+
+            assert!(result.len() <= 3);
+            for (item_type, quantity) in result {
+                match &item_type[..] {
+                    "pink_potion" => assert!((2..=20).contains(&quantity)),
+                    "baseball_cap" => assert_eq!(quantity, 1),
+                    other => panic!("unexpected loot: {}", other),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn a_table_naming_itself_ends() {
+        let subject = loot_tables();
+
+        assert!(subject.roll("recursive", &mut Rng::seeded(1)).is_empty());
+    }
+
+    #[test]
+    fn spawned_loot_respects_stack_limits() {
+        let mut subject = LootTableList::new();
+        let mut potions = LootTable::new();
+        potions.add_entry(
+            1,
+            LootEntry::Item {
+                item_type: "pink_potion".into(),
+                min: 20,
+                max: 20,
+            },
+        );
+        subject.insert("potions", potions);
+
+        let result = subject.spawn("potions", &item_types(), &mut Rng::seeded(1));
+
+        let quantities: Vec<u8> = result.iter().map(|item| item.quantity).collect();
+        assert_eq!(quantities, vec![16, 4]);
+    }
+}
@@ -0,0 +1,48 @@
+use super::*;
+
+/// a square region of the map, as sent to clients as their players approach it.
+///   Its version counts the changes made to its tiles, so that a client can tell whether a
+///   tile change it is sent is newer than the chunk it holds.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MapChunk {
+    pub x: i32, // in chunks from the left of the map
+    pub y: i32, // in chunks from the top of the map
+    pub version: u64,
+    pub width: usize, // chunks at the right and bottom edges of the map may be cut short
+    pub height: usize,
+    pub tiles: Vec<tile_map::Tile>,
+}
+
+impl MapChunk {
+    /// how many tiles wide and high a chunk is.
+    pub const SIZE: i32 = 16;
+
+    /// returns the coordinates of the chunk holding the map location.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::map_chunk::MapChunk;
+    /// assert_eq!(MapChunk::containing(15, 16), (0, 1));
+    /// assert_eq!(MapChunk::containing(40, 2), (2, 0));
+    /// ```
+    pub fn containing(x: i32, y: i32) -> (i32, i32) {
+        (x.div_euclid(Self::SIZE), y.div_euclid(Self::SIZE))
+    }
+
+    /// returns the map location of the chunk's top left tile.
+    pub fn origin(&self) -> (i32, i32) {
+        (self.x * Self::SIZE, self.y * Self::SIZE)
+    }
+
+    /// returns each tile of the chunk along with its map location.
+    pub fn tiles_with_locations(&self) -> impl Iterator<Item = (i32, i32, tile_map::Tile)> + '_ {
+        let (origin_x, origin_y) = self.origin();
+
+        self.tiles.iter().enumerate().map(move |(index, tile)| {
+            (
+                origin_x + (index % self.width) as i32,
+                origin_y + (index / self.width) as i32,
+                *tile,
+            )
+        })
+    }
+}
@@ -1,6 +1,7 @@
 use super::*;
 use std::convert::*;
-use timer::Guard;
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::{Duration, Instant};
 
 use character::CharacterList;
 // use geometry::Point;
@@ -11,13 +12,16 @@ pub mod command;
 pub use command::{CommandSender, GameUpdateSender};
 
 pub mod character;
-pub use character::{Character, CharacterType, Player};
+pub use character::{Character, CharacterType, EndorsementSource, Player};
 
 pub mod level;
 pub use level::Level;
 
+pub mod item_class;
+pub use item_class::{ItemClass, ItemClassDefinition, ItemClassList};
+
 pub mod items;
-pub use items::{Item, ItemClass, ItemList, ItemState, ItemType, ItemTypeList};
+pub use items::{Item, ItemList, ItemState, ItemType, ItemTypeList};
 
 pub mod inventory;
 pub use inventory::{AliasList, Inventory, InventoryList};
@@ -26,15 +30,62 @@ pub mod facility;
 pub use facility::{Facility, FacilityList};
 
 pub mod equipment;
-pub use equipment::{ItemClassSpecifier, ItemClassSpecifierList, MountingPoint, MountingPointMap};
+pub use equipment::{
+    Fitting, ItemClassSpecifier, ItemClassSpecifierList, MountingPoint, MountingPointMap,
+};
+
+pub mod tile_type;
+pub use tile_type::{TileDefinition, TileType, TileTypeList};
 
 pub mod tile_map;
 pub use tile_map::TileMap;
 
+pub mod map_chunk;
+pub use map_chunk::MapChunk;
+
 pub mod parsing;
 pub use parsing::*;
 
-use std::sync::atomic::{AtomicU64, Ordering};
+pub mod skills;
+pub use skills::{Skill, SkillSet};
+
+pub mod requirement;
+pub use requirement::Requirement;
+
+pub mod random;
+pub use random::Rng;
+
+pub mod loot;
+pub use loot::{LootEntry, LootTable, LootTableList};
+
+pub mod door;
+pub use door::{Door, DoorList};
+
+pub mod perception;
+pub use perception::Perception;
+
+pub mod spawn_point;
+pub use spawn_point::{Spawn, SpawnPoint};
+
+pub mod error;
+pub use error::GameError;
+
+pub mod clock;
+pub use clock::GameClock;
+
+pub mod scheduler;
+pub use scheduler::{GameEvent, ScheduledEvent, Scheduler};
+
+pub mod tick;
+pub use tick::{TickMetrics, TickRate};
+
+pub mod interest;
+pub use interest::{Entity, InterestManager, UpdateSink};
+
+pub mod recorder;
+pub use recorder::{forward_updates, Recorder, Recording, Replay};
+
+use std::cell::{Cell, RefCell};
 
 pub struct GameData {
     pub player: Player,
@@ -51,31 +102,86 @@ pub struct GameData {
 
 // starts at two to reserve one for the player.
 //  this is temporary
-static GLOBAL_NEXT_ID: AtomicU64 = AtomicU64::new(2);
-static GLOBAL_NEXT_ITEM_ID: AtomicU64 = AtomicU64::new(1);
+//  ids are allocated per game thread so that a game can be replayed exactly.
+thread_local! {
+    static NEXT_ID_COUNTER: Cell<u64> = const { Cell::new(2) };
+    static NEXT_ITEM_ID_COUNTER: Cell<u64> = const { Cell::new(1) };
+}
 
 #[allow(non_snake_case)]
 fn NEXT_ID() -> u64 {
-    GLOBAL_NEXT_ID.fetch_add(1, Ordering::SeqCst)
+    NEXT_ID_COUNTER.with(|next| next.replace(next.get() + 1))
 }
 
 #[allow(non_snake_case)]
 fn NEXT_ITEM_ID() -> u64 {
-    GLOBAL_NEXT_ITEM_ID.fetch_add(1, Ordering::SeqCst)
+    NEXT_ITEM_ID_COUNTER.with(|next| next.replace(next.get() + 1))
 }
 
-pub struct GameState {}
+/// restarts id allocation for the current thread as if no game had been started.
+///   The thread's random number generator is returned to its default seed as well.
+pub fn reset_ids() {
+    NEXT_ID_COUNTER.with(|next| next.set(2));
+    NEXT_ITEM_ID_COUNTER.with(|next| next.set(1));
+    seed_random(Rng::DEFAULT_SEED);
+}
+
+// like ids, random rolls are made per game thread so that a game can be replayed exactly.
+thread_local! {
+    static RANDOM: RefCell<Rng> = RefCell::new(Rng::default());
+}
+
+/// reseeds the current thread's random number generator.
+pub fn seed_random(seed: u64) {
+    RANDOM.with(|rng| *rng.borrow_mut() = Rng::seeded(seed));
+}
+
+/// runs f with the current thread's random number generator.
+pub fn with_random<T, F: FnOnce(&mut Rng) -> T>(f: F) -> T {
+    RANDOM.with(|rng| f(&mut rng.borrow_mut()))
+}
+
+pub struct GameState {
+    pub scheduler: Scheduler,
+    pub tick_rate: TickRate,
+    pub tick_metrics: TickMetrics,
+}
 
 impl GameState {
+    pub const LEVEL_PATH: &'static str = "maps/level1.map";
+
+    /// returns a game state driven by a virtual clock.
     pub fn new() -> Self {
-        Self {}
+        Self::new_with_clock(GameClock::new_virtual())
+    }
+
+    pub fn new_with_clock(clock: GameClock) -> Self {
+        Self {
+            scheduler: Scheduler::new(clock),
+            tick_rate: TickRate::default(),
+            tick_metrics: TickMetrics::new(),
+        }
     }
 
-    pub fn game_loop(
+    /// runs the game until the command channel closes, returning how its ticks went.
+    ///   Each tick, commands are handled as they arrive until the tick is up, then the world is
+    ///   advanced, whether or not any commands came.  How long the ticks take is logged once
+    ///   each report interval.
+    /// # Arguments
+    /// * tick_rate - how often the world is advanced.
+    /// * recorder - when given, every command received and every update sent is recorded.
+    pub fn game_loop<W: std::io::Write>(
         update_tx: GameUpdateSender,
         command_rx: std::sync::mpsc::Receiver<Command>,
         command_tx: CommandSender,
-    ) {
+        tick_rate: TickRate,
+        recorder: Option<Recorder<W>>,
+    ) -> TickMetrics {
+        let mut recorder = recorder;
+
+        // updates are routed through the loop so that they can be recorded before being sent on.
+        let (game_update_tx, game_update_rx) = std::sync::mpsc::channel();
+
         let (
             player,
             map,
@@ -85,14 +191,191 @@ impl GameState {
             items,
             facilities,
             inventories,
-        ) = &mut Self::initialize_game("maps/level1.map", Some(&update_tx));
+        ) = &mut Self::initialize_game(Self::LEVEL_PATH, Some(&game_update_tx));
 
-        let game_state = &mut GameState::new();
+        // the one client so far follows the player.
+        let mut interest = InterestManager::new();
+        interest.subscribe(update_tx, player.id, InterestManager::DEFAULT_RADIUS);
+        forward_updates(&game_update_rx, &mut interest, &mut recorder);
+
+        let game_state = &mut GameState::new_with_clock(GameClock::new_real_time());
+        game_state.tick_rate = tick_rate;
+        game_state.schedule_spawn_points(map);
+
+        let mut next_tick = Instant::now() + tick_rate.tick_length();
         loop {
-            let command = command_rx.recv();
+            // time spent handling commands and advancing the world, but not waiting.
+            let mut work = Duration::ZERO;
+
+            loop {
+                // a steady stream of commands must not hold the world back.
+                if Instant::now() >= next_tick {
+                    break;
+                }
+
+                let command =
+                    command_rx.recv_timeout(next_tick.saturating_duration_since(Instant::now()));
+                let started = Instant::now();
+
+                match command {
+                    Ok(command) => {
+                        game_state.scheduler.synchronize();
+                        if let Some(recorder) = &mut recorder {
+                            recorder.record_command(game_state.scheduler.now(), &command);
+                        }
+                        game_state.game_loop_iteration(
+                            player,
+                            map,
+                            obstacles,
+                            characters,
+                            item_class_specifiers,
+                            items,
+                            facilities,
+                            inventories,
+                            &command,
+                            Some(&game_update_tx),
+                            Some(&command_tx),
+                        );
+                        forward_updates(&game_update_rx, &mut interest, &mut recorder);
+                        work += started.elapsed();
+                    }
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => {
+                        // if receiver is broken, we just bail, ending the game.
+                        //   eventually, we need to save the game, probably whenever
+                        //   leaving this loop.
+                        return game_state.tick_metrics.clone();
+                    }
+                }
+            }
+
+            let started = Instant::now();
+            let events_run = game_state.tick(
+                player,
+                map,
+                obstacles,
+                characters,
+                item_class_specifiers,
+                items,
+                facilities,
+                inventories,
+                Some(&game_update_tx),
+                Some(&command_tx),
+            );
+            if let (Some(recorder), true) = (&mut recorder, events_run > 0) {
+                recorder.record_advance(game_state.scheduler.now());
+            }
+            forward_updates(&game_update_rx, &mut interest, &mut recorder);
+            work += started.elapsed();
+
+            game_state.tick_metrics.record(work, &tick_rate);
+            if game_state.tick_metrics.is_report_due(&tick_rate) {
+                println!("game loop: {}", game_state.tick_metrics);
+            }
+
+            // a tick that overran is not made up for; the next one starts a full tick later.
+            next_tick = (next_tick + tick_rate.tick_length()).max(Instant::now());
+        }
+    }
+
+    /// advances the world by a tick: catches the clock up and runs whatever has come due,
+    ///   which is how facility timers, spawners, doors and steps all move on.
+    ///   returns the number of events run.
+    pub fn tick(
+        &mut self,
+        player: &mut Player,
+        map: &mut TileMap,
+        obstacles: &mut BlockingMap,
+        characters: &mut CharacterList,
+        item_class_specifiers: &mut ItemClassSpecifierList,
+        items: &mut ItemList,
+        facilities: &mut FacilityList,
+        inventories: &mut InventoryList,
+        update_tx: Option<&GameUpdateSender>,
+        command_tx: Option<&CommandSender>,
+    ) -> usize {
+        self.scheduler.synchronize();
+        self.process_scheduled_events(
+            player,
+            map,
+            obstacles,
+            characters,
+            item_class_specifiers,
+            items,
+            facilities,
+            inventories,
+            update_tx,
+            command_tx,
+        )
+    }
+
+    /// keeps the regions of the map's spawn points populated, each at its own pace.
+    pub fn schedule_spawn_points(&mut self, map: &TileMap) {
+        for (index, spawn_point) in map.spawn_points.iter().enumerate() {
+            self.scheduler
+                .schedule_repeating(spawn_point.delay, vec![GameEvent::Repopulate(index as u64)]);
+        }
+    }
+
+    /// advances a virtual clock and runs every event that comes due.
+    /// pub for testing purposes only
+    pub fn advance_clock(
+        &mut self,
+        millis: u64,
+        player: &mut Player,
+        map: &mut TileMap,
+        obstacles: &mut BlockingMap,
+        characters: &mut CharacterList,
+        item_class_specifiers: &mut ItemClassSpecifierList,
+        items: &mut ItemList,
+        facilities: &mut FacilityList,
+        inventories: &mut InventoryList,
+        update_tx: Option<&GameUpdateSender>,
+        command_tx: Option<&CommandSender>,
+    ) {
+        self.scheduler.advance(millis);
+        self.process_scheduled_events(
+            player,
+            map,
+            obstacles,
+            characters,
+            item_class_specifiers,
+            items,
+            facilities,
+            inventories,
+            update_tx,
+            command_tx,
+        );
+    }
 
-            if let Ok(command) = command {
-                game_state.game_loop_iteration(
+    /// runs the game events of every due event.
+    ///   returns the number of events run.
+    pub fn process_scheduled_events(
+        &mut self,
+        player: &mut Player,
+        map: &mut TileMap,
+        obstacles: &mut BlockingMap,
+        characters: &mut CharacterList,
+        item_class_specifiers: &mut ItemClassSpecifierList,
+        items: &mut ItemList,
+        facilities: &mut FacilityList,
+        inventories: &mut InventoryList,
+        update_tx: Option<&GameUpdateSender>,
+        command_tx: Option<&CommandSender>,
+    ) -> usize {
+        let events = self.scheduler.take_due_events();
+        let count = events.len();
+
+        for event in events {
+            // a step is seen to by its own event, which may start the next.
+            let completes_activity = player.activity == Some(event.id) && !player.moving;
+
+            if completes_activity {
+                GameUpdate::send(update_tx, GameUpdate::ActivityExpired());
+            }
+
+            for game_event in event.events.iter() {
+                self.run_game_event(
                     player,
                     map,
                     obstacles,
@@ -101,17 +384,128 @@ impl GameState {
                     items,
                     facilities,
                     inventories,
-                    &command,
-                    Some(&update_tx),
-                    Some(&command_tx),
+                    game_event,
+                    update_tx,
+                    command_tx,
                 );
-            } else {
-                // if receiver is broken, we just bail, ending the game.
-                //   eventually, we need to save the game, probably whenever
-                //   leaving this loop.
-                return;
+            }
+
+            if completes_activity {
+                match event.interval {
+                    Some(interval) => {
+                        GameUpdate::send(update_tx, GameUpdate::ActivityStarted(interval as u32))
+                    }
+                    None => player.activity = None,
+                }
             }
         }
+        count
+    }
+
+    /// runs one game event of a scheduled event, feeding any command back through
+    ///   game_loop_iteration.
+    /// pub for testing purposes only
+    pub fn run_game_event(
+        &mut self,
+        player: &mut Player,
+        map: &mut TileMap,
+        obstacles: &mut BlockingMap,
+        characters: &mut CharacterList,
+        item_class_specifiers: &mut ItemClassSpecifierList,
+        items: &mut ItemList,
+        facilities: &mut FacilityList,
+        inventories: &mut InventoryList,
+        game_event: &GameEvent,
+        update_tx: Option<&GameUpdateSender>,
+        command_tx: Option<&CommandSender>,
+    ) {
+        let result = match game_event {
+            GameEvent::Command(command) => {
+                self.game_loop_iteration(
+                    player,
+                    map,
+                    obstacles,
+                    characters,
+                    item_class_specifiers,
+                    items,
+                    facilities,
+                    inventories,
+                    command,
+                    update_tx,
+                    command_tx,
+                );
+                Ok(())
+            }
+            GameEvent::GainExperience(skill, amount) => {
+                Self::gain_experience(*skill, *amount, player, update_tx);
+                Ok(())
+            }
+            // only the step under way can be completed.
+            GameEvent::Step(_, _) if !player.moving => Ok(()),
+            GameEvent::Step(direction, mode) => {
+                let position = (player.x, player.y);
+                Command::complete_step(*direction, player, obstacles, update_tx, command_tx);
+
+                if (player.x, player.y) != position {
+                    Perception::alert_characters(*mode, player, map, characters, update_tx);
+                }
+
+                if let Some(sequence) = player.step_sequence.take() {
+                    GameUpdate::send(update_tx, CommandProcessed(sequence));
+                }
+
+                if let Some((direction, mode)) = player.queued_step.take() {
+                    let command = match player.queued_sequence.take() {
+                        Some(sequence) => {
+                            Command::Sequenced(sequence, Box::new(Command::Move(direction, mode)))
+                        }
+                        None => Command::Move(direction, mode),
+                    };
+                    self.game_loop_iteration(
+                        player,
+                        map,
+                        obstacles,
+                        characters,
+                        item_class_specifiers,
+                        items,
+                        facilities,
+                        inventories,
+                        &command,
+                        update_tx,
+                        command_tx,
+                    );
+                }
+                Ok(())
+            }
+            GameEvent::CloseDoor(door_id) => Command::close_door(
+                *door_id,
+                map,
+                obstacles,
+                items,
+                &mut self.scheduler,
+                update_tx,
+                command_tx,
+            ),
+            GameEvent::Repopulate(index) => Command::repopulate(
+                *index,
+                player,
+                map,
+                obstacles,
+                characters,
+                items,
+                inventories,
+                update_tx,
+                command_tx,
+            ),
+        };
+
+        // an event that no longer fits the game is reported rather than allowed to end it.
+        if let Err(error) = result {
+            GameUpdate::send(
+                update_tx,
+                GameUpdate::Message(MessageCategory::Error, error.to_string()),
+            );
+        }
     }
 
     /// public for testing purposes
@@ -135,8 +529,14 @@ impl GameState {
             item_vec,
             facility_vec,
             stored_item_vec,
+            loot_table_vec,
+            spawn_point_vec,
+            door_vec,
         ) = TileMap::load_from_file(level_path.to_string());
 
+        map.spawn_points = SpawnPoint::read_in_spawn_points(&spawn_point_vec);
+        map.doors = DoorList::read_in_doors(&map, &door_vec);
+
         let mut obstacles = BlockingMap::new();
         obstacles.refresh(&map);
 
@@ -147,8 +547,7 @@ impl GameState {
         let item_types = ItemType::read_in_item_types(&mut item_type_vec);
 
         let mut items = Item::read_in_items(&item_vec, item_types);
-
-        // find home for activity guard and activity timer
+        items.loot_tables = LootTableList::read_in_loot_tables(&loot_table_vec);
 
         let mut player = Player::new();
 
@@ -163,6 +562,8 @@ impl GameState {
         Inventory::new_into_inventory_list(player.id, inventories);
 
         Item::read_in_stored_items(&stored_item_vec, aliases, &mut items, inventories);
+        Facility::stock_with_loot(&facilities, &mut items, inventories);
+        Inventory::provide_for_containers(&mut items, inventories);
 
         let item_class_specifiers = ItemClassSpecifier::initialize();
 
@@ -193,7 +594,7 @@ impl GameState {
         player: &mut Player,
         map: &mut TileMap,
         obstacles: &mut BlockingMap,
-        _characters: &mut CharacterList,
+        characters: &mut CharacterList,
         item_class_specifiers: &mut ItemClassSpecifierList,
         items: &mut ItemList,
         facilities: &mut FacilityList,
@@ -204,11 +605,12 @@ impl GameState {
     ) {
         self.abort_activity_if_necessary(player, command, update_tx);
 
-        match command {
+        let result = match command {
             Command::QuitGame => {
                 GameUpdate::send(update_tx, Exit);
-                return;
+                Ok(())
             }
+            // the player only arrives once the step is over; creatures are alerted then.
             Command::Move(direction, mode) => Command::move_player(
                 *direction,
                 *mode,
@@ -216,28 +618,74 @@ impl GameState {
                 map,
                 obstacles,
                 facilities,
+                items,
                 inventories,
+                &mut self.scheduler,
                 update_tx,
                 command_tx,
             ),
-            Command::Teleport(id, _new_x, _new_y) => {
-                if *id != 1 {
-                    todo!()
-                }
-                todo!()
-                // Command::teleport_character(*id, *new_x, *new_y, self, &update_tx);
-            }
-            Command::SpawnItem(inventory_id, class, description) => {
-                Command::spawn_item(
-                    *inventory_id,
-                    *class,
-                    description,
-                    inventories,
+            Command::Sequenced(sequence, command) => {
+                let queued = match **command {
+                    Command::Move(_, mode) => player.moving && mode != MoveCommandMode::Use,
+                    _ => false,
+                };
+                let activity = player.activity;
+
+                self.game_loop_iteration(
+                    player,
+                    map,
+                    obstacles,
+                    characters,
+                    item_class_specifiers,
                     items,
+                    facilities,
+                    inventories,
+                    command,
                     update_tx,
                     command_tx,
                 );
+
+                // a step is over only once it has been taken; anything else is over already.
+                if queued {
+                    if let Some(replaced) = player.queued_sequence.replace(*sequence) {
+                        GameUpdate::send(update_tx, CommandProcessed(replaced));
+                    }
+                } else if player.moving && player.activity != activity {
+                    player.step_sequence = Some(*sequence);
+                } else {
+                    GameUpdate::send(update_tx, CommandProcessed(*sequence));
+                }
+                Ok(())
             }
+            Command::Teleport(id, new_x, new_y) => Command::teleport_character(
+                *id, *new_x, *new_y, player, characters, obstacles, update_tx, command_tx,
+            ),
+            Command::KillCharacter(id) => Command::kill_character(
+                *id,
+                player,
+                characters,
+                obstacles,
+                items,
+                inventories,
+                update_tx,
+                command_tx,
+            ),
+            Command::SpawnItem(inventory_id, class, description) => Command::spawn_item(
+                *inventory_id,
+                *class,
+                description,
+                inventories,
+                items,
+                update_tx,
+                command_tx,
+            )
+            .map(|item| {
+                // what an activity yields the player is news to them.
+                if *inventory_id == player.inventory_id() {
+                    let message = format!("You gain {}.", item.description().to_lowercase());
+                    GameUpdate::send(update_tx, Message(MessageCategory::Loot, message));
+                }
+            }),
             Command::TakeItem(item_index) => Command::pickup_item(
                 *item_index,
                 player,
@@ -246,8 +694,9 @@ impl GameState {
                 update_tx,
                 command_tx,
             ),
-            Command::DropItem(item_index) => Command::drop_item(
+            Command::DropItem(item_index, quantity) => Command::drop_item(
                 *item_index,
+                *quantity,
                 player,
                 items,
                 inventories,
@@ -266,16 +715,17 @@ impl GameState {
             Command::UnequipItem(item_id) => {
                 Command::unequip_item(*item_id, player, items, inventories, update_tx, command_tx)
             }
-            Command::TransferItem(item_id, src_inventory, dest_inventory) => {
+            Command::TransferItem(item_id, src_inventory, dest_inventory, quantity) => {
                 Command::transfer_item(
                     *item_id,
+                    *quantity,
                     *src_inventory,
                     *dest_inventory,
                     items,
                     inventories,
                     update_tx,
                     command_tx,
-                );
+                )
             }
             Command::TransferAllItems(src_inventory, dest_inventory) => {
                 Command::transfer_all_items(
@@ -287,9 +737,45 @@ impl GameState {
                     command_tx,
                 )
             }
-            Command::CloseExternalInventory => Command::close_external_inventory(update_tx),
+            Command::SplitStack(item_id, quantity) => Command::split_stack(
+                *item_id,
+                *quantity,
+                player,
+                items,
+                inventories,
+                update_tx,
+                command_tx,
+            ),
+            Command::MergeStacks(item_id, into_id) => Command::merge_stacks(
+                *item_id,
+                *into_id,
+                player,
+                items,
+                inventories,
+                update_tx,
+                command_tx,
+            ),
+            Command::OpenContainer(item_id) => {
+                Command::open_container(*item_id, player, items, inventories, update_tx, command_tx)
+            }
+            Command::CloseExternalInventory => {
+                Command::close_external_inventory(player, update_tx);
+                Ok(())
+            }
             Command::RefreshInventory => Self::refresh_inventory(player, inventories, update_tx),
-            Command::AbortActivity | Command::None => {}
+            Command::ToggleBumpToInteract => {
+                Self::toggle_bump_to_interact(player, update_tx);
+                Ok(())
+            }
+            Command::AbortActivity | Command::None => Ok(()),
+        };
+
+        // a command that does not fit the game is reported rather than allowed to end it.
+        if let Err(error) = result {
+            GameUpdate::send(
+                update_tx,
+                GameUpdate::Message(MessageCategory::Error, error.to_string()),
+            );
         }
     }
 
@@ -299,7 +785,7 @@ impl GameState {
         command: &Command,
         update_tx: Option<&GameUpdateSender>,
     ) {
-        if let None = player.activity_guard {
+        if player.activity.is_none() {
             return;
         }
 
@@ -308,15 +794,37 @@ impl GameState {
             Command::None
             | Command::SpawnItem(_, _, _)
             | Command::RefreshInventory
+            | Command::ToggleBumpToInteract
+            | Command::Sequenced(_, _)
             | Command::TakeItem(_)
-            | Command::DropItem(_) => {}
+            | Command::DropItem(_, _) => {}
+
+            // stepping on while a step is under way queues the next step.
+            Command::Move(_, mode) if player.moving && *mode != MoveCommandMode::Use => {}
+
+            // teleporting someone else leaves the player to their work.
+            Command::Teleport(id, _, _) if *id != player.id => {}
 
             _ => {
-                if let Some(_) = player.activity_guard {
-                    player.activity_guard = None;
+                if let Some(activity) = player.activity {
+                    self.scheduler.cancel(activity);
+                    player.activity = None;
                 }
 
-                GameUpdate::send(update_tx, GameUpdate::ActivityAborted());
+                if player.moving {
+                    player.moving = false;
+                    player.queued_step = None;
+                    GameUpdate::send(update_tx, GameUpdate::MovementAborted(player.id));
+
+                    for sequence in [player.step_sequence.take(), player.queued_sequence.take()]
+                        .iter()
+                        .flatten()
+                    {
+                        GameUpdate::send(update_tx, GameUpdate::CommandProcessed(*sequence));
+                    }
+                } else {
+                    GameUpdate::send(update_tx, GameUpdate::ActivityAborted());
+                }
             }
         };
     }
@@ -325,18 +833,39 @@ impl GameState {
         player: &Player,
         inventories: &InventoryList,
         update_tx: Option<&GameUpdateSender>,
-    ) {
+    ) -> Result<(), GameError> {
+        let inventory = inventories
+            .get(&player.inventory_id())
+            .ok_or(GameError::InventoryNotFound(player.inventory_id()))?;
+
+        GameUpdate::send(update_tx, GameUpdate::InventoryUpdated(inventory.to_vec()));
+        Ok(())
+    }
+
+    pub fn toggle_bump_to_interact(player: &mut Player, update_tx: Option<&GameUpdateSender>) {
+        player.bump_to_interact = !player.bump_to_interact;
+
+        let message = match player.bump_to_interact {
+            true => "Walking into doors and facilities now uses them.",
+            false => "Walking into doors and facilities no longer uses them.",
+        };
         GameUpdate::send(
             update_tx,
-            GameUpdate::InventoryUpdated(
-                inventories
-                    .get(&player.inventory_id())
-                    .expect("unable to find player inventory")
-                    .to_vec(),
-            ),
+            GameUpdate::Message(MessageCategory::System, message.into()),
         );
     }
 
+    pub fn gain_experience(
+        skill: Skill,
+        amount: u32,
+        player: &mut Player,
+        update_tx: Option<&GameUpdateSender>,
+    ) {
+        if let Some(level) = player.skills.gain_experience(skill, amount) {
+            GameUpdate::send(update_tx, GameUpdate::SkillLevelIncreased(skill, level));
+        }
+    }
+
     // for testing purposes
     pub fn teleport_player<U: TryInto<i32>>(
         &mut self,
@@ -347,15 +876,10 @@ impl GameState {
         update_tx: Option<&GameUpdateSender>,
         command_tx: Option<&CommandSender>,
     ) {
-        use crate::game::command::CommandHandler;
-
-        let character = player;
-        let facing = character.facing;
+        use crate::game::command::{CommandHandler, TeleportCommand, Teleportee};
 
-        let obstacles = obstacles;
-        let mut command = game::command::MoveCommand::new(
-            character,
-            facing,
+        let mut command = TeleportCommand::new(
+            Teleportee::Player(player),
             new_x
                 .try_into()
                 .ok()
@@ -369,8 +893,41 @@ impl GameState {
         command.execute(update_tx, command_tx);
     }
 }
+#[cfg(test)]
+mod test_fixture;
+
 #[cfg(test)]
 mod test_external_inventories;
 
 #[cfg(test)]
 mod test_item_type;
+
+#[cfg(test)]
+mod test_activities;
+
+#[cfg(test)]
+mod test_teleport;
+
+#[cfg(test)]
+mod test_replay;
+
+#[cfg(test)]
+mod test_game_errors;
+
+#[cfg(test)]
+mod test_containers;
+
+#[cfg(test)]
+mod test_loot;
+
+#[cfg(test)]
+mod test_spawn_points;
+
+#[cfg(test)]
+mod test_doors;
+
+#[cfg(test)]
+mod test_sneaking;
+
+#[cfg(test)]
+mod test_movement;
@@ -0,0 +1,162 @@
+use super::*;
+
+/// how other characters become aware of the player.
+///   A character notices the player by hearing them, within the distance the noise of their
+///   movement carries, or by seeing them, within its sight and with nothing in the way.
+///   Sneaking is quieter and harder to spot.
+pub struct Perception {}
+
+impl Perception {
+    /// how many tiles away a character can see the player walking.
+    pub const SIGHT_RADIUS: i32 = 8;
+    /// how many tiles away a character can see the player sneaking.
+    pub const SNEAKING_SIGHT_RADIUS: i32 = 3;
+
+    /// returns how many tiles away the player moving in the mode can be heard.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::perception::Perception;
+    /// # use muframework::MoveCommandMode;
+    /// assert!(Perception::noise_of(MoveCommandMode::Sneak) < Perception::noise_of(MoveCommandMode::Normal));
+    /// ```
+    pub fn noise_of(mode: MoveCommandMode) -> i32 {
+        match mode {
+            MoveCommandMode::Normal => 5,
+            MoveCommandMode::Sneak => 1,
+            MoveCommandMode::Use => 2,
+        }
+    }
+
+    fn sight_radius_for(mode: MoveCommandMode) -> i32 {
+        match mode {
+            MoveCommandMode::Sneak => Self::SNEAKING_SIGHT_RADIUS,
+            _ => Self::SIGHT_RADIUS,
+        }
+    }
+
+    /// returns true if the character notices the player moving in the mode.
+    pub fn notices(
+        character: &Character,
+        player: &Player,
+        mode: MoveCommandMode,
+        map: &TileMap,
+    ) -> bool {
+        let distance = (character.x - player.x)
+            .abs()
+            .max((character.y - player.y).abs());
+
+        distance <= Self::noise_of(mode)
+            || (distance <= Self::sight_radius_for(mode)
+                && map.has_line_of_sight(character.x, character.y, player.x, player.y))
+    }
+
+    /// lets every character that is not yet aware of the player try to notice them,
+    ///   announcing those that do.  Characters that can no longer perceive the player lose track
+    ///   of them.
+    pub fn alert_characters(
+        mode: MoveCommandMode,
+        player: &Player,
+        map: &TileMap,
+        characters: &mut CharacterList,
+        update_tx: Option<&GameUpdateSender>,
+    ) {
+        for character in characters.iter_mut() {
+            let notices = Self::notices(character, player, mode, map);
+
+            if notices && !character.aware_of_player {
+                GameUpdate::send(update_tx, PlayerNoticed(character.id));
+            }
+            character.aware_of_player = notices;
+        }
+    }
+}
+
+#[cfg(test)]
+mod perception {
+    use super::*;
+
+    // a 20x5 room divided by a wall at x=10, with a gap at 10,0.
+    fn map() -> TileMap {
+        let mut result = TileMap::new();
+        result.set_dimensions(20, 5, tile_map::Tile::DirtFloor);
+        for y in 1..5 {
+            result.set_tile_at(10, y, tile_map::Tile::StoneWall);
+        }
+        result
+    }
+
+    fn player_at(x: i32, y: i32) -> Player {
+        let mut result = Player::new();
+        result.x = x;
+        result.y = y;
+        result
+    }
+
+    fn rat_at(x: i32, y: i32) -> Character {
+        Character::new(100, x, y, Direction::Up, CharacterType::Rat)
+    }
+
+    #[test]
+    fn a_walking_player_is_seen_from_further_than_a_sneaking_one() {
+        let rat = rat_at(2, 2);
+        let player = player_at(8, 2);
+
+        assert!(Perception::notices(
+            &rat,
+            &player,
+            MoveCommandMode::Normal,
+            &map()
+        ));
+        assert!(!Perception::notices(
+            &rat,
+            &player,
+            MoveCommandMode::Sneak,
+            &map()
+        ));
+    }
+
+    #[test]
+    fn walls_hide_the_player_but_not_their_noise() {
+        let rat = rat_at(7, 3);
+
+        assert!(!Perception::notices(
+            &rat,
+            &player_at(13, 3),
+            MoveCommandMode::Normal,
+            &map()
+        ));
+        assert!(Perception::notices(
+            &rat,
+            &player_at(11, 3),
+            MoveCommandMode::Normal,
+            &map()
+        ));
+        assert!(!Perception::notices(
+            &rat,
+            &player_at(11, 3),
+            MoveCommandMode::Sneak,
+            &map()
+        ));
+    }
+
+    #[test]
+    fn a_character_announces_noticing_the_player_once() {
+        let mut characters = CharacterList::new();
+        characters.add(rat_at(2, 2));
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        for _ in 0..2 {
+            Perception::alert_characters(
+                MoveCommandMode::Normal,
+                &player_at(4, 2),
+                &map(),
+                &mut characters,
+                Some(&sender),
+            );
+        }
+
+        assert!(characters.get(100).unwrap().aware_of_player);
+        assert!(matches!(receiver.try_recv(), Ok(PlayerNoticed(100))));
+        assert!(receiver.try_recv().is_err());
+    }
+}
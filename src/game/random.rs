@@ -0,0 +1,55 @@
+/// a small, seedable random number generator (splitmix64).
+///   The same seed always produces the same rolls, so games can be replayed and tested.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub const DEFAULT_SEED: u64 = 0x2545_F491_4F6C_DD1D;
+
+    pub fn seeded(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// returns a number from 0 up to, but not including, bound.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        if bound == 0 {
+            return 0;
+        }
+        self.next_u64() % bound
+    }
+
+    /// returns a number from low to high inclusive.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::random::Rng;
+    /// let mut subject = Rng::seeded(7);
+    /// for _ in 0..100 {
+    ///     let roll = subject.between(2, 4);
+    ///     assert!((2..=4).contains(&roll));
+    /// }
+    /// assert_eq!(Rng::seeded(7).between(1, 100), Rng::seeded(7).between(1, 100));
+    /// ```
+    pub fn between(&mut self, low: u32, high: u32) -> u32 {
+        if high <= low {
+            return low;
+        }
+        low + self.below((high - low) as u64 + 1) as u32
+    }
+}
+
+impl Default for Rng {
+    fn default() -> Self {
+        Self::seeded(Self::DEFAULT_SEED)
+    }
+}
@@ -0,0 +1,408 @@
+use super::*;
+use regex::Regex;
+use std::io::Write;
+use std::sync::mpsc::{channel, Receiver};
+
+/// what caused a group of GameUpdates.
+#[derive(Debug, Clone)]
+pub enum StepKind {
+    Initialize,
+    Advance(u64),          // (game_time) scheduled events came due
+    Command(u64, Command), // (game_time, command)
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordedStep {
+    pub kind: StepKind,
+    pub updates: Vec<String>,
+}
+
+/// A log of every command the game loop received, and of every update it sent in response.
+///
+/// The log is line based:
+/// ```text
+/// level maps/level1.map
+/// update CharacterEntered { id: 1, x: 8, y: 7, character_type: Player }
+/// command 1250 Move(Up, Normal)
+/// update CharacterMoved(1, 8, 6)
+/// advance 61250
+/// ```
+#[derive(Debug, Clone)]
+pub struct Recording {
+    pub level_path: String,
+    pub steps: Vec<RecordedStep>,
+}
+
+impl Recording {
+    pub fn new<S: ToString>(level_path: S) -> Self {
+        Self {
+            level_path: level_path.to_string(),
+            steps: vec![RecordedStep {
+                kind: StepKind::Initialize,
+                updates: vec![],
+            }],
+        }
+    }
+
+    pub fn read_from_file<S: ToString>(filename: S) -> Recording {
+        let contents =
+            std::fs::read_to_string(filename.to_string()).expect("unable to read recording");
+        Self::parse(&contents)
+    }
+
+    /// parses a recording from the contents of a log.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::recorder::*;
+    /// let log = "level maps/test.map\ncommand 20 TakeItem(1)\nupdate ItemRemoved(4)\n";
+    /// let subject = Recording::parse(log);
+    /// assert_eq!(subject.level_path, "maps/test.map");
+    /// assert_eq!(subject.steps.len(), 2);
+    /// assert_eq!(subject.steps[1].updates, vec!["ItemRemoved(4)".to_string()]);
+    /// ```
+    pub fn parse(contents: &str) -> Recording {
+        let re = Regex::new(r"^(level|update|command|advance) (?:(\d+) )?(.*)$").unwrap();
+        let mut result: Option<Recording> = None;
+
+        for line in contents.lines().filter(|l| !l.trim().is_empty()) {
+            let captures = re
+                .captures(line)
+                .unwrap_or_else(|| panic!("unable to parse recorded line: {}", line));
+            let keyword = capture_string(&captures, 1);
+            let time = captures.get(2).map(|t| t.as_str().parse::<u64>().unwrap());
+            let rest = capture_optional_string(&captures, 3);
+
+            if keyword == "level" {
+                result = Some(Recording::new(rest));
+                continue;
+            }
+
+            let recording = result
+                .as_mut()
+                .expect("recording must begin with its level");
+
+            match keyword {
+                "update" => recording.add_update(unescape(rest)),
+                "advance" => recording.add_step(StepKind::Advance(
+                    time.unwrap_or_else(|| rest.parse::<u64>().expect("unable to parse time")),
+                )),
+                "command" => recording.add_step(StepKind::Command(
+                    time.expect("command recorded without a time"),
+                    Command::from_record(rest)
+                        .unwrap_or_else(|| panic!("unable to parse command: {}", rest)),
+                )),
+                _ => unreachable!(),
+            }
+        }
+
+        result.expect("empty recording")
+    }
+
+    fn add_step(&mut self, kind: StepKind) {
+        self.steps.push(RecordedStep {
+            kind,
+            updates: vec![],
+        });
+    }
+
+    fn add_update(&mut self, update: String) {
+        self.steps
+            .last_mut()
+            .expect("no step to record update in")
+            .updates
+            .push(update);
+    }
+}
+
+/// Writes a Recording to a log as the game runs.
+pub struct Recorder<W: Write> {
+    log: W,
+}
+
+impl<W: Write> Recorder<W> {
+    pub fn new<S: ToString>(mut log: W, level_path: S) -> Self {
+        writeln!(log, "level {}", level_path.to_string()).expect("unable to write recording");
+        Self { log }
+    }
+
+    pub fn record_command(&mut self, game_time: u64, command: &Command) {
+        self.write_line(format!(
+            "command {} {}",
+            game_time,
+            Command::to_record(command)
+        ));
+    }
+
+    pub fn record_advance(&mut self, game_time: u64) {
+        self.write_line(format!("advance {}", game_time));
+    }
+
+    pub fn record_update(&mut self, update: &GameUpdate) {
+        self.write_line(format!("update {}", escape(&normalize(update))));
+    }
+
+    /// returns the underlying log.
+    pub fn into_inner(self) -> W {
+        self.log
+    }
+
+    // flushed line by line so that the log survives a crash.
+    fn write_line(&mut self, line: String) {
+        writeln!(self.log, "{}", line).expect("unable to write recording");
+        self.log.flush().expect("unable to flush recording");
+    }
+}
+
+impl Recorder<std::fs::File> {
+    pub fn create<S: ToString, T: ToString>(filename: S, level_path: T) -> Self {
+        let file = std::fs::File::create(filename.to_string()).expect("unable to create recording");
+        Self::new(file, level_path)
+    }
+}
+
+/// sends every pending update on to the sink, recording it first if there is a recorder.
+pub fn forward_updates<W: Write, S: UpdateSink>(
+    update_rx: &Receiver<GameUpdate>,
+    sink: &mut S,
+    recorder: &mut Option<Recorder<W>>,
+) {
+    for update in update_rx.try_iter() {
+        if let Some(recorder) = recorder {
+            recorder.record_update(&update);
+        }
+        sink.dispatch(update);
+    }
+}
+
+/// the first step at which a replay differed from its recording.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayMismatch {
+    pub step: usize,
+    pub expected: Vec<String>,
+    pub actual: Vec<String>,
+}
+
+pub struct Replay {}
+
+impl Replay {
+    /// feeds the recorded commands back through the game loop on a virtual clock and compares
+    ///   the updates produced at each step with those recorded.
+    ///   Updates within a step are compared without regard to order.
+    pub fn run(recording: &Recording) -> Result<(), ReplayMismatch> {
+        let (update_tx, update_rx) = channel();
+
+        reset_ids();
+        let mut game_state = GameState::new();
+
+        let (
+            mut player,
+            mut map,
+            mut obstacles,
+            mut characters,
+            mut item_class_specifiers,
+            mut items,
+            mut facilities,
+            mut inventories,
+        ) = GameState::initialize_game(&recording.level_path, Some(&update_tx));
+        game_state.schedule_spawn_points(&map);
+
+        for (index, step) in recording.steps.iter().enumerate() {
+            match &step.kind {
+                StepKind::Initialize => {}
+                StepKind::Advance(game_time) => {
+                    let millis = game_time.saturating_sub(game_state.scheduler.now());
+                    game_state.advance_clock(
+                        millis,
+                        &mut player,
+                        &mut map,
+                        &mut obstacles,
+                        &mut characters,
+                        &mut item_class_specifiers,
+                        &mut items,
+                        &mut facilities,
+                        &mut inventories,
+                        Some(&update_tx),
+                        None,
+                    );
+                }
+                StepKind::Command(game_time, command) => {
+                    let millis = game_time.saturating_sub(game_state.scheduler.now());
+                    game_state.scheduler.advance(millis);
+                    game_state.game_loop_iteration(
+                        &mut player,
+                        &mut map,
+                        &mut obstacles,
+                        &mut characters,
+                        &mut item_class_specifiers,
+                        &mut items,
+                        &mut facilities,
+                        &mut inventories,
+                        command,
+                        Some(&update_tx),
+                        None,
+                    );
+                }
+            }
+
+            let mut actual: Vec<String> = update_rx.try_iter().map(|u| normalize(&u)).collect();
+            let mut expected = step.updates.clone();
+            actual.sort();
+            expected.sort();
+
+            if actual != expected {
+                return Err(ReplayMismatch {
+                    step: index,
+                    expected,
+                    actual,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// returns the update as a string, with any lists of items put in order.
+pub fn normalize(update: &GameUpdate) -> String {
+    let sorted = |items: &Vec<Item>| {
+        let mut items = items.clone();
+        items.sort();
+        items
+    };
+
+    let update = match update {
+        EquipmentUpdated(items) => EquipmentUpdated(sorted(items)),
+        InventoryUpdated(items) => InventoryUpdated(sorted(items)),
+        ExternalInventoryOpened(items, id) => ExternalInventoryOpened(sorted(items), *id),
+        ExternalInventoryUpdated(items) => ExternalInventoryUpdated(sorted(items)),
+        update => update.clone(),
+    };
+    format!("{:?}", update)
+}
+
+fn escape(string: &str) -> String {
+    string.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(string: &str) -> String {
+    let mut result = String::new();
+    let mut chars = string.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+impl Command {
+    /// returns the command as it is written to a recording.
+    pub fn to_record(command: &Command) -> String {
+        format!("{:?}", command)
+    }
+
+    /// parses a command written by to_record.
+    /// # Examples:
+    /// ```
+    /// # use muframework::*;
+    /// let command = Command::SpawnItem(1, game::ItemClass::Food, "Apple, \"red\"".into());
+    /// let result = Command::from_record(&Command::to_record(&command)).unwrap();
+    /// assert_eq!(Command::to_record(&result), Command::to_record(&command));
+    /// ```
+    pub fn from_record(record: &str) -> Option<Command> {
+        let re = Regex::new(r"^(\w+)(?:\((.*)\))?$").unwrap();
+        let captures = re.captures(record.trim())?;
+
+        let name = capture_string(&captures, 1);
+        let args = split_arguments(capture_optional_string(&captures, 2));
+        let number = |index: usize| args.get(index)?.parse::<u64>().ok();
+        let coordinate = |index: usize| args.get(index)?.parse::<i32>().ok();
+        let quantity = |index: usize| args.get(index)?.parse::<u8>().ok();
+
+        let command = match name {
+            "None" => Command::None,
+            "QuitGame" => Command::QuitGame,
+            "Move" => Command::Move(
+                direction_from_name(args.first()?)?,
+                move_command_mode_from_name(args.get(1)?)?,
+            ),
+            "Teleport" => Command::Teleport(number(0)?, coordinate(1)?, coordinate(2)?),
+            "SpawnItem" => Command::SpawnItem(
+                number(0)?,
+                ItemClass::from_name(args.get(1)?.to_lowercase()),
+                unquote(args.get(2)?)?,
+            ),
+            "KillCharacter" => Command::KillCharacter(number(0)?),
+            "TakeItem" => Command::TakeItem(number(0)?),
+            "DropItem" => Command::DropItem(number(0)?, quantity(1)?),
+            "EquipItem" => Command::EquipItem(number(0)?),
+            "UnequipItem" => Command::UnequipItem(number(0)?),
+            "TransferItem" => {
+                Command::TransferItem(number(0)?, number(1)?, number(2)?, quantity(3)?)
+            }
+            "TransferAllItems" => Command::TransferAllItems(number(0)?, number(1)?),
+            "SplitStack" => Command::SplitStack(number(0)?, quantity(1)?),
+            "MergeStacks" => Command::MergeStacks(number(0)?, number(1)?),
+            "OpenContainer" => Command::OpenContainer(number(0)?),
+            "CloseExternalInventory" => Command::CloseExternalInventory,
+            "RefreshInventory" => Command::RefreshInventory,
+            "AbortActivity" => Command::AbortActivity,
+            "ToggleBumpToInteract" => Command::ToggleBumpToInteract,
+            // the numbered command is itself a record, arguments and all.
+            "Sequenced" => {
+                let (sequence, command) = capture_optional_string(&captures, 2).split_once(',')?;
+                Command::Sequenced(
+                    sequence.trim().parse::<u64>().ok()?,
+                    Box::new(Command::from_record(command)?),
+                )
+            }
+            _ => return None,
+        };
+        Some(command)
+    }
+}
+
+fn split_arguments(arguments: &str) -> Vec<String> {
+    let re = Regex::new(r#""(?:[^"\\]|\\.)*"|[^,\s]+"#).unwrap();
+    re.find_iter(arguments)
+        .map(|m| m.as_str().to_string())
+        .collect()
+}
+
+fn unquote(string: &str) -> Option<String> {
+    if !(string.len() >= 2 && string.starts_with('"') && string.ends_with('"')) {
+        return None;
+    }
+    Some(unescape(&string[1..string.len() - 1]))
+}
+
+fn direction_from_name(name: &str) -> Option<Direction> {
+    let direction = match name {
+        "Up" => Direction::Up,
+        "Down" => Direction::Down,
+        "Left" => Direction::Left,
+        "Right" => Direction::Right,
+        "UpLeft" => Direction::UpLeft,
+        "UpRight" => Direction::UpRight,
+        "DownLeft" => Direction::DownLeft,
+        "DownRight" => Direction::DownRight,
+        _ => return None,
+    };
+    Some(direction)
+}
+
+fn move_command_mode_from_name(name: &str) -> Option<MoveCommandMode> {
+    let mode = match name {
+        "Normal" => MoveCommandMode::Normal,
+        "Sneak" => MoveCommandMode::Sneak,
+        "Use" => MoveCommandMode::Use,
+        _ => return None,
+    };
+    Some(mode)
+}
@@ -0,0 +1,339 @@
+use super::*;
+
+/// A condition a player must meet, as declared in a map file.
+///
+/// The language is small:
+/// ```text
+/// :can_pick_apples                   the player holds the endorsement
+/// skill(harvesting, 3)               the player's skill is at least the level
+/// has("Reed Basket")                 the player's inventory holds such an item
+/// all(req, ...)  any(req, ...)  not(req)
+/// always
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Requirement {
+    #[default]
+    Always,
+    Endorsement(String),
+    SkillLevel(Skill, u8),
+    Has(String),
+    All(Vec<Requirement>),
+    Any(Vec<Requirement>),
+    Not(Box<Requirement>),
+}
+
+impl Requirement {
+    /// parses a requirement expression.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::requirement::Requirement;
+    /// # use muframework::game::skills::Skill;
+    /// let subject = Requirement::parse("all(:can_pick_apples, not(skill(harvesting, 5)))");
+    /// assert_eq!(
+    ///     subject,
+    ///     Ok(Requirement::All(vec![
+    ///         Requirement::Endorsement(":can_pick_apples".into()),
+    ///         Requirement::Not(Box::new(Requirement::SkillLevel(Skill::Harvesting, 5))),
+    ///     ]))
+    /// );
+    /// assert!(Requirement::parse("all(:can_pick_apples").is_err());
+    /// ```
+    pub fn parse(expression: &str) -> Result<Requirement, String> {
+        let mut parser = RequirementParser::new(expression);
+        let result = parser.parse_requirement()?;
+
+        parser.skip_whitespace();
+        if !parser.is_at_end() {
+            return Err(parser.error("unexpected text after requirement"));
+        }
+        Ok(result)
+    }
+
+    /// returns a requirement met only when both this and the other requirement are.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::requirement::Requirement;
+    /// # use muframework::game::skills::Skill;
+    /// let subject = Requirement::Always.and(Requirement::SkillLevel(Skill::Combat, 3));
+    /// assert_eq!(subject, Requirement::SkillLevel(Skill::Combat, 3));
+    ///
+    /// let subject = subject.and(Requirement::Endorsement(":newb".into()));
+    /// assert_eq!(
+    ///     subject,
+    ///     Requirement::All(vec![
+    ///         Requirement::SkillLevel(Skill::Combat, 3),
+    ///         Requirement::Endorsement(":newb".into()),
+    ///     ])
+    /// );
+    /// ```
+    pub fn and(self, other: Requirement) -> Requirement {
+        match (self, other) {
+            (Requirement::Always, requirement) | (requirement, Requirement::Always) => requirement,
+            (Requirement::All(mut requirements), requirement) => {
+                requirements.push(requirement);
+                Requirement::All(requirements)
+            }
+            (requirement, other) => Requirement::All(vec![requirement, other]),
+        }
+    }
+
+    /// returns the skill levels the requirement insists upon, whatever else it asks for.
+    ///   Skill levels that are only alternatives, or that are ruled out, are not among them.
+    pub fn skill_levels(&self) -> Vec<(Skill, u8)> {
+        match self {
+            Requirement::SkillLevel(skill, level) => vec![(*skill, *level)],
+            Requirement::All(requirements) => requirements
+                .iter()
+                .flat_map(|requirement| requirement.skill_levels())
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    /// returns true if the player meets the requirement.
+    ///   inventory is the player's own inventory.
+    pub fn is_met_by(&self, player: &Player, inventory: Option<&Inventory>) -> bool {
+        match self {
+            Requirement::Always => true,
+            Requirement::Endorsement(endorsement) => player.is_endorsed_with(endorsement),
+            Requirement::SkillLevel(skill, level) => player.skills.meets(*skill, *level),
+            Requirement::Has(description) => inventory.is_some_and(|inventory| {
+                inventory
+                    .to_vec()
+                    .iter()
+                    .any(|item| item.raw_description() == *description)
+            }),
+            Requirement::All(requirements) => {
+                requirements.iter().all(|r| r.is_met_by(player, inventory))
+            }
+            Requirement::Any(requirements) => {
+                requirements.iter().any(|r| r.is_met_by(player, inventory))
+            }
+            Requirement::Not(requirement) => !requirement.is_met_by(player, inventory),
+        }
+    }
+}
+
+struct RequirementParser<'a> {
+    expression: &'a str,
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl<'a> RequirementParser<'a> {
+    fn new(expression: &'a str) -> Self {
+        Self {
+            expression,
+            chars: expression.chars().collect(),
+            position: 0,
+        }
+    }
+
+    fn error(&self, message: &str) -> String {
+        format!(
+            "{} at {} in requirement: {}",
+            message, self.position, self.expression
+        )
+    }
+
+    fn is_at_end(&self) -> bool {
+        self.position >= self.chars.len()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(c) if c.is_whitespace()) {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        if self.peek() == Some(expected) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", expected)))
+        }
+    }
+
+    fn word(&mut self) -> String {
+        let start = self.position;
+        while matches!(self.peek(), Some(c) if c.is_alphanumeric() || c == '_') {
+            self.position += 1;
+        }
+        self.chars[start..self.position].iter().collect()
+    }
+
+    fn quoted(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut result = String::new();
+
+        loop {
+            match self.peek() {
+                None => return Err(self.error("unterminated string")),
+                Some('"') => {
+                    self.position += 1;
+                    return Ok(result);
+                }
+                Some(c) => {
+                    result.push(c);
+                    self.position += 1;
+                }
+            }
+        }
+    }
+
+    fn parse_requirement(&mut self) -> Result<Requirement, String> {
+        self.skip_whitespace();
+
+        if self.peek() == Some(':') {
+            self.position += 1;
+            let name = self.word();
+            if name.is_empty() {
+                return Err(self.error("expected an endorsement"));
+            }
+            return Ok(Requirement::Endorsement(format!(":{}", name)));
+        }
+
+        let function = self.word();
+        let result = match &function[..] {
+            "always" => return Ok(Requirement::Always),
+            "all" => Requirement::All(self.requirement_list()?),
+            "any" => Requirement::Any(self.requirement_list()?),
+            "not" => {
+                self.expect('(')?;
+                let requirement = self.parse_requirement()?;
+                self.expect(')')?;
+                Requirement::Not(Box::new(requirement))
+            }
+            "skill" => {
+                self.expect('(')?;
+                self.skip_whitespace();
+                let skill = self.word();
+                self.expect(',')?;
+                self.skip_whitespace();
+                let level = self
+                    .word()
+                    .parse::<u8>()
+                    .map_err(|_| self.error("expected a level"))?;
+                self.expect(')')?;
+                let skill = Skill::parse(&skill).ok_or_else(|| self.error("unknown skill"))?;
+                Requirement::SkillLevel(skill, level)
+            }
+            "has" => {
+                self.expect('(')?;
+                self.skip_whitespace();
+                let description = self.quoted()?;
+                self.expect(')')?;
+                Requirement::Has(description)
+            }
+            "" => return Err(self.error("expected a requirement")),
+            _ => return Err(self.error(&format!("unknown requirement '{}'", function))),
+        };
+        Ok(result)
+    }
+
+    fn requirement_list(&mut self) -> Result<Vec<Requirement>, String> {
+        let mut result = vec![];
+        self.expect('(')?;
+
+        loop {
+            result.push(self.parse_requirement()?);
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(',') => self.position += 1,
+                Some(')') => {
+                    self.position += 1;
+                    return Ok(result);
+                }
+                _ => return Err(self.error("expected ',' or ')'")),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod requirement {
+    use super::*;
+
+    fn inventory_holding(description: &str) -> Inventory {
+        let mut inventory = Inventory::new(1);
+        inventory.force_accept(&Item::new(
+            9000,
+            ItemType::new(ItemClass::Tool, description),
+            1,
+        ));
+        inventory
+    }
+
+    #[test]
+    fn endorsements_are_checked_against_the_player() {
+        let mut player = Player::new();
+        let subject = Requirement::parse(":can_pick_apples").unwrap();
+
+        assert!(!subject.is_met_by(&player, None));
+        player.endorse_with(":can_pick_apples");
+        assert!(subject.is_met_by(&player, None));
+    }
+
+    #[test]
+    fn skill_levels_are_checked_against_the_players_skills() {
+        let mut player = Player::new();
+        let subject = Requirement::parse("skill(Woodcutting, 2)").unwrap();
+
+        assert!(!subject.is_met_by(&player, None));
+        player.skills.gain_experience(Skill::Woodcutting, 100);
+        assert!(subject.is_met_by(&player, None));
+    }
+
+    #[test]
+    fn has_checks_the_players_inventory() {
+        let player = Player::new();
+        let subject = Requirement::parse(r#"has("Reed Basket")"#).unwrap();
+
+        assert!(!subject.is_met_by(&player, None));
+        assert!(!subject.is_met_by(&player, Some(&inventory_holding("Hatchet"))));
+        assert!(subject.is_met_by(&player, Some(&inventory_holding("Reed Basket"))));
+    }
+
+    #[test]
+    fn requirements_combine() {
+        let player = Player::new();
+
+        let met = |expression: &str| {
+            Requirement::parse(expression)
+                .unwrap()
+                .is_met_by(&player, None)
+        };
+
+        assert!(met("always"));
+        assert!(met("any(:bogus, :newb)"));
+        assert!(!met("all(:bogus, :newb)"));
+        assert!(met("not(:bogus)"));
+        assert!(met("all( :newb , not( any(:bogus, skill(combat, 2)) ) )"));
+    }
+
+    #[test]
+    fn malformed_requirements_are_reported() {
+        for expression in vec![
+            "",
+            "can_pick_apples",
+            "all(:a, :b",
+            "skill(juggling, 3)",
+            "skill(combat)",
+            r#"has("basket)"#,
+            ":a :b",
+        ] {
+            assert!(
+                Requirement::parse(expression).is_err(),
+                "expected an error for {}",
+                expression
+            );
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use super::*;
+use clock::GameClock;
+
+/// something the game does of its own accord when a scheduled event comes due.
+///   Unlike commands, which clients send, these are only ever scheduled by the game itself.
+#[derive(Debug, Clone)]
+pub enum GameEvent {
+    Command(Command), // run as though a client had sent it
+    GainExperience(Skill, u32),
+    Step(Direction, MoveCommandMode), // completes a step under way
+    CloseDoor(u64),                   // (door_id)
+    Repopulate(u64),                  // (spawn_point_index)
+}
+
+impl From<Command> for GameEvent {
+    fn from(command: Command) -> Self {
+        GameEvent::Command(command)
+    }
+}
+
+/// a set of game events to be run at a given game time.
+#[derive(Debug, Clone)]
+pub struct ScheduledEvent {
+    pub id: u64,
+    pub due: u64,
+    pub interval: Option<u64>,
+    pub events: Vec<GameEvent>,
+}
+
+/// The single owner of all timed events in the game.
+pub struct Scheduler {
+    clock: GameClock,
+    events: Vec<ScheduledEvent>,
+    next_id: u64,
+}
+
+impl Scheduler {
+    pub fn new(clock: GameClock) -> Self {
+        Self {
+            clock,
+            events: vec![],
+            next_id: 1,
+        }
+    }
+
+    /// returns the current game time in milliseconds.
+    pub fn now(&self) -> u64 {
+        self.clock.now()
+    }
+
+    pub fn clock(&self) -> &GameClock {
+        &self.clock
+    }
+
+    /// catches a real-time clock up with the wall clock.
+    pub fn synchronize(&mut self) {
+        self.clock.synchronize();
+    }
+
+    /// moves a virtual clock forward.  Due events are not run until they are taken.
+    pub fn advance(&mut self, millis: u64) {
+        self.clock.advance(millis);
+    }
+
+    /// schedules game events to be run once, after delay milliseconds.
+    /// returns the id of the new event.
+    /// # Examples:
+    /// ```
+    /// # use muframework::*;
+    /// # use muframework::game::clock::GameClock;
+    /// # use muframework::game::scheduler::Scheduler;
+    /// let mut subject = Scheduler::new(GameClock::new_virtual());
+    /// let id = subject.schedule(500, vec![Command::RefreshInventory.into()]);
+    /// assert!(subject.is_scheduled(id));
+    /// assert_eq!(subject.time_until_next_event(), Some(500));
+    /// ```
+    pub fn schedule(&mut self, delay: u64, events: Vec<GameEvent>) -> u64 {
+        self.insert(delay, None, events)
+    }
+
+    /// schedules game events to be run every interval milliseconds until cancelled.
+    /// returns the id of the new event.
+    pub fn schedule_repeating(&mut self, interval: u64, events: Vec<GameEvent>) -> u64 {
+        if interval == 0 {
+            panic!("repeating events must have a non-zero interval");
+        }
+        self.insert(interval, Some(interval), events)
+    }
+
+    fn insert(&mut self, delay: u64, interval: Option<u64>, events: Vec<GameEvent>) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.events.push(ScheduledEvent {
+            id,
+            due: self.now() + delay,
+            interval,
+            events,
+        });
+        id
+    }
+
+    /// removes an event.  Does nothing if the event has already run or been cancelled.
+    pub fn cancel(&mut self, id: u64) {
+        self.events.retain(|e| e.id != id);
+    }
+
+    /// returns true if the event will still run.
+    pub fn is_scheduled(&self, id: u64) -> bool {
+        self.events.iter().any(|e| e.id == id)
+    }
+
+    /// returns the number of milliseconds until the next event is due or None if there are no events.
+    pub fn time_until_next_event(&self) -> Option<u64> {
+        let now = self.now();
+        self.events.iter().map(|e| e.due.saturating_sub(now)).min()
+    }
+
+    /// removes and returns every event that has come due, in the order they came due.
+    ///   Repeating events are rescheduled, and are returned once for each interval that has elapsed.
+    pub fn take_due_events(&mut self) -> Vec<ScheduledEvent> {
+        let now = self.now();
+        let mut result = vec![];
+
+        while let Some(index) = self.next_due_index(now) {
+            let event = self.events.remove(index);
+
+            if let Some(interval) = event.interval {
+                let mut next = event.clone();
+                next.due += interval;
+                self.events.push(next);
+            }
+            result.push(event);
+        }
+        result
+    }
+
+    fn next_due_index(&self, now: u64) -> Option<usize> {
+        self.events
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.due <= now)
+            .min_by_key(|(_, e)| (e.due, e.id))
+            .map(|(index, _)| index)
+    }
+}
+
+#[cfg(test)]
+mod scheduler {
+    use super::*;
+
+    #[test]
+    fn events_are_not_due_until_the_clock_reaches_them() {
+        let mut subject = Scheduler::new(GameClock::new_virtual());
+        subject.schedule(1000, vec![Command::RefreshInventory.into()]);
+
+        subject.advance(999);
+        assert!(subject.take_due_events().is_empty());
+
+        subject.advance(1);
+        assert_eq!(subject.take_due_events().len(), 1);
+        assert_eq!(subject.time_until_next_event(), None);
+    }
+
+    #[test]
+    fn due_events_are_returned_in_order() {
+        let mut subject = Scheduler::new(GameClock::new_virtual());
+        let late = subject.schedule(300, vec![Command::None.into()]);
+        let early = subject.schedule(100, vec![Command::None.into()]);
+
+        subject.advance(500);
+
+        let ids: Vec<u64> = subject.take_due_events().iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![early, late]);
+    }
+
+    #[test]
+    fn repeating_events_fire_once_per_elapsed_interval() {
+        let mut subject = Scheduler::new(GameClock::new_virtual());
+        let id = subject.schedule_repeating(100, vec![Command::None.into()]);
+
+        subject.advance(350);
+
+        assert_eq!(subject.take_due_events().len(), 3);
+        assert!(subject.is_scheduled(id));
+        assert_eq!(subject.time_until_next_event(), Some(50));
+    }
+
+    #[test]
+    fn cancelled_events_never_run() {
+        let mut subject = Scheduler::new(GameClock::new_virtual());
+        let id = subject.schedule_repeating(100, vec![Command::None.into()]);
+
+        subject.cancel(id);
+        subject.advance(1000);
+
+        assert!(subject.take_due_events().is_empty());
+        assert!(!subject.is_scheduled(id));
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+
+#[derive(Debug, Hash, Eq, PartialEq, Copy, Clone, Ord, PartialOrd)]
+pub enum Skill {
+    Harvesting,
+    Woodcutting,
+    Mining,
+    Fishing,
+    Combat,
+    Crafting,
+}
+
+impl Skill {
+    pub fn from_name<S: ToString>(name: S) -> Skill {
+        Self::parse(name).expect("unknown skill")
+    }
+
+    /// returns the skill with the given name, ignoring case, or None if there is no such skill.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::skills::Skill;
+    /// assert_eq!(Skill::parse("Woodcutting"), Some(Skill::Woodcutting));
+    /// assert_eq!(Skill::parse("juggling"), None);
+    /// ```
+    pub fn parse<S: ToString>(name: S) -> Option<Skill> {
+        let skill = match &name.to_string().to_lowercase()[..] {
+            "harvesting" => Skill::Harvesting,
+            "woodcutting" => Skill::Woodcutting,
+            "mining" => Skill::Mining,
+            "fishing" => Skill::Fishing,
+            "combat" => Skill::Combat,
+            "crafting" => Skill::Crafting,
+            _ => return None,
+        };
+        Some(skill)
+    }
+
+    pub const MAX_LEVEL: u8 = 99;
+
+    /// returns the experience needed to reach a level.
+    ///   Each level costs 100 more experience than the one before it.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::skills::Skill;
+    /// assert_eq!(Skill::experience_for_level(1), 0);
+    /// assert_eq!(Skill::experience_for_level(2), 100);
+    /// assert_eq!(Skill::experience_for_level(3), 300);
+    /// assert_eq!(Skill::experience_for_level(4), 600);
+    /// ```
+    pub fn experience_for_level(level: u8) -> u32 {
+        let level = level.max(1) as u32;
+        50 * level * (level - 1)
+    }
+
+    /// returns the level reached with the given experience.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::skills::Skill;
+    /// assert_eq!(Skill::level_for_experience(0), 1);
+    /// assert_eq!(Skill::level_for_experience(299), 2);
+    /// assert_eq!(Skill::level_for_experience(300), 3);
+    /// ```
+    pub fn level_for_experience(experience: u32) -> u8 {
+        let mut level = 1;
+        while level < Self::MAX_LEVEL && Self::experience_for_level(level + 1) <= experience {
+            level += 1;
+        }
+        level
+    }
+}
+
+/// the experience a character has gained in each of their skills.
+#[derive(Debug, Clone, Default)]
+pub struct SkillSet {
+    experience: HashMap<Skill, u32>,
+}
+
+impl SkillSet {
+    pub fn new() -> Self {
+        Self {
+            experience: HashMap::new(),
+        }
+    }
+
+    pub fn experience(&self, skill: Skill) -> u32 {
+        *self.experience.get(&skill).unwrap_or(&0)
+    }
+
+    /// returns the level of the skill.  Untrained skills are at level 1.
+    pub fn level(&self, skill: Skill) -> u8 {
+        Skill::level_for_experience(self.experience(skill))
+    }
+
+    /// adds experience to the skill.
+    ///   returns the new level if the experience raised it or None otherwise.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::skills::*;
+    /// let mut subject = SkillSet::new();
+    /// assert_eq!(subject.gain_experience(Skill::Harvesting, 99), None);
+    /// assert_eq!(subject.gain_experience(Skill::Harvesting, 1), Some(2));
+    /// ```
+    pub fn gain_experience(&mut self, skill: Skill, amount: u32) -> Option<u8> {
+        let old_level = self.level(skill);
+
+        let experience = self.experience.entry(skill).or_insert(0);
+        *experience = experience.saturating_add(amount);
+
+        let new_level = self.level(skill);
+        if new_level > old_level {
+            Some(new_level)
+        } else {
+            None
+        }
+    }
+
+    /// returns true if the skill is at or above level.
+    pub fn meets(&self, skill: Skill, level: u8) -> bool {
+        self.level(skill) >= level
+    }
+}
+
+#[cfg(test)]
+mod skill_set {
+    use super::*;
+
+    #[test]
+    fn untrained_skills_are_at_level_1() {
+        let subject = SkillSet::new();
+
+        assert_eq!(subject.level(Skill::Combat), 1);
+        assert!(subject.meets(Skill::Combat, 1));
+        assert!(!subject.meets(Skill::Combat, 2));
+    }
+
+    #[test]
+    fn a_large_gain_can_raise_several_levels() {
+        let mut subject = SkillSet::new();
+
+        assert_eq!(subject.gain_experience(Skill::Woodcutting, 650), Some(4));
+        assert_eq!(subject.experience(Skill::Woodcutting), 650);
+        assert_eq!(subject.level(Skill::Harvesting), 1);
+    }
+
+    #[test]
+    fn levels_stop_at_the_maximum() {
+        let mut subject = SkillSet::new();
+
+        subject.gain_experience(Skill::Crafting, u32::MAX);
+
+        assert_eq!(subject.level(Skill::Crafting), Skill::MAX_LEVEL);
+        assert_eq!(subject.gain_experience(Skill::Crafting, 1), None);
+    }
+}
@@ -0,0 +1,219 @@
+use super::*;
+use regex::Regex;
+
+/// what a spawn point brings into the level.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Spawn {
+    Creature(CharacterType),
+    Item(String), // (item_type)
+}
+
+/// a place that keeps its surroundings stocked with creatures or items, as declared in a map file:
+/// ```text
+/// creature R 44,10 { population: 2; delay: 30000; radius: 4 }
+/// item yellow_potion 50,30 { population: 1; delay: 60000; radius: 3 }
+/// ```
+/// Every delay milliseconds, one more is spawned if fewer than population are within radius.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SpawnPoint {
+    pub spawn: Spawn,
+    pub x: i32,
+    pub y: i32,
+    pub population: u8,
+    pub delay: u64, // in milliseconds
+    pub radius: i32,
+}
+
+impl SpawnPoint {
+    pub fn new(spawn: Spawn, x: i32, y: i32) -> Self {
+        Self {
+            spawn,
+            x,
+            y,
+            population: 1,
+            delay: 60_000,
+            radius: 0,
+        }
+    }
+
+    /// returns true if x,y lies within the region the spawn point keeps stocked.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::spawn_point::*;
+    /// let mut subject = SpawnPoint::new(Spawn::Item("apple".into()), 10, 10);
+    /// subject.radius = 2;
+    /// assert!(subject.covers(12, 8));
+    /// assert!(!subject.covers(13, 10));
+    /// ```
+    pub fn covers(&self, x: i32, y: i32) -> bool {
+        (x - self.x).abs() <= self.radius && (y - self.y).abs() <= self.radius
+    }
+
+    /// returns the number of the spawn point's creatures, or bundles of its items, in its region.
+    pub fn population_in(&self, characters: &CharacterList, items: &ItemList) -> usize {
+        match &self.spawn {
+            Spawn::Creature(character_type) => characters
+                .iter()
+                .filter(|c| c.character_type == *character_type && self.covers(c.x, c.y))
+                .count(),
+            Spawn::Item(item_type) => {
+                let item_type = match items.item_types.get(item_type) {
+                    Some(item_type) => item_type,
+                    None => return 0,
+                };
+                items
+                    .iter()
+                    .filter(|(_, state)| match state {
+                        ItemState::Bundle(item, x, y) => {
+                            item.item_type == *item_type && self.covers(*x, *y)
+                        }
+                        _ => false,
+                    })
+                    .count()
+            }
+        }
+    }
+
+    /// returns the locations in the region that nothing blocks and the player cannot see,
+    ///   ordered by row and then column.
+    pub fn vacant_locations(
+        &self,
+        player: &Player,
+        map: &TileMap,
+        obstacles: &BlockingMap,
+    ) -> Vec<(i32, i32)> {
+        let mut result = vec![];
+
+        for y in self.y - self.radius..=self.y + self.radius {
+            for x in self.x - self.radius..=self.x + self.radius {
+                if obstacles.is_in_bounds(x, y)
+                    && !obstacles.is_blocked_at(x, y)
+                    && (player.x, player.y) != (x, y)
+                    && !player.can_see(x, y, map)
+                {
+                    result.push((x, y));
+                }
+            }
+        }
+        result
+    }
+
+    pub fn read_in_spawn_points(spawn_points: &[String]) -> Vec<SpawnPoint> {
+        let re = Regex::new(
+            r#"^(creature|item)\s+(\w+)\s+(\d+)\s*,\s*(\d+)\s*(?:\{([^}]*)\})?\s*(?://.*)?$"#,
+        )
+        .unwrap();
+
+        spawn_points
+            .iter()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with("//"))
+            .map(|line| {
+                let captures = re
+                    .captures(line)
+                    .unwrap_or_else(|| panic!("unable to parse spawn point: {}", line));
+                let spawn = match capture_string(&captures, 1) {
+                    "creature" => {
+                        Spawn::Creature(CharacterType::from_symbol(capture_string(&captures, 2)))
+                    }
+                    _ => Spawn::Item(capture_string(&captures, 2).to_string()),
+                };
+                let mut result = SpawnPoint::new(
+                    spawn,
+                    capture_coordinate(&captures, 3),
+                    capture_coordinate(&captures, 4),
+                );
+                result.read_in_attributes(capture_optional_string(&captures, 5));
+                result
+            })
+            .collect()
+    }
+
+    fn read_in_attributes(&mut self, attributes: &str) {
+        for attribute in attributes
+            .split(';')
+            .map(|a| a.trim())
+            .filter(|a| !a.is_empty())
+        {
+            let (name, value) = attribute
+                .split_once(':')
+                .unwrap_or_else(|| panic!("unable to parse spawn point attribute: {}", attribute));
+            let value = value.trim();
+
+            match name.trim() {
+                "population" => self.population = Self::parse_attribute(attribute, value),
+                "delay" => self.delay = Self::parse_attribute(attribute, value),
+                "radius" => self.radius = Self::parse_attribute(attribute, value),
+                _ => panic!("unrecognized spawn point attribute: {}", attribute),
+            }
+        }
+
+        if self.delay == 0 {
+            panic!("spawn point delay must be greater than zero");
+        }
+    }
+
+    fn parse_attribute<T: std::str::FromStr>(attribute: &str, value: &str) -> T {
+        value
+            .parse()
+            .unwrap_or_else(|_| panic!("unable to parse spawn point attribute: {}", attribute))
+    }
+}
+
+#[cfg(test)]
+mod spawn_points {
+    use super::*;
+
+    #[test]
+    fn spawn_points_are_read_in_with_their_attributes() {
+        let subject = SpawnPoint::read_in_spawn_points(&[
+            "creature R 44,10 { population: 2; delay: 30000; radius: 4 } // rats".into(),
+            "item yellow_potion 50,30".into(),
+        ]);
+
+        assert_eq!(
+            subject,
+            vec![
+                SpawnPoint {
+                    spawn: Spawn::Creature(CharacterType::Rat),
+                    x: 44,
+                    y: 10,
+                    population: 2,
+                    delay: 30000,
+                    radius: 4,
+                },
+                SpawnPoint::new(Spawn::Item("yellow_potion".into()), 50, 30),
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn a_spawn_point_needs_a_delay() {
+        SpawnPoint::read_in_spawn_points(&["creature S 1,1 { delay: 0 }".into()]);
+    }
+
+    #[test]
+    fn locations_the_player_can_see_are_not_vacant() {
+        let mut map = TileMap::new();
+        map.set_dimensions(30, 5, tile_map::Tile::DirtFloor);
+        map.set_tile_at(20, 1, tile_map::Tile::StoneWall);
+        let mut obstacles = BlockingMap::new();
+        obstacles.refresh(&map);
+        obstacles.block_at(21, 2);
+        let mut player = Player::new();
+        player.x = 18;
+        player.y = 1;
+        let mut subject = SpawnPoint::new(Spawn::Creature(CharacterType::Rat), 22, 1);
+        subject.radius = 1;
+
+        // the wall casts a shadow that widens behind it, and 21,2 is blocked.
+        assert_eq!(
+            subject.vacant_locations(&player, &map, &obstacles),
+            vec![(23, 0), (21, 1), (22, 1), (23, 1), (23, 2)]
+        );
+
+        player.x = 2;
+        assert_eq!(subject.vacant_locations(&player, &map, &obstacles).len(), 8);
+    }
+}
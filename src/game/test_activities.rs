@@ -0,0 +1,106 @@
+use super::test_fixture::TestGame;
+use super::*;
+use command::ActivateAppleTreeCommand;
+
+fn start_picking_apples() -> TestGame {
+    let mut subject = TestGame::load("maps/test.map");
+
+    subject.player.endorse_with(":can_pick_apples");
+    subject.teleport_player(9, 8);
+    subject.use_towards(Direction::Down);
+    subject
+}
+
+fn apples_held(subject: &TestGame) -> u8 {
+    subject.inventories[&subject.player.inventory_id()]
+        .to_vec()
+        .iter()
+        .filter(|i| i.raw_description() == "Apple")
+        .map(|i| i.quantity)
+        .sum()
+}
+
+#[test]
+fn picking_apples_completes_only_when_the_clock_advances() {
+    let mut subject = start_picking_apples();
+
+    assert!(subject.player.activity.is_some());
+    assert_eq!(apples_held(&subject), 0);
+
+    let updates = subject.wait(59999);
+    assert_eq!(apples_held(&subject), 0);
+    assert!(updates.is_empty());
+
+    let updates = subject.wait(1);
+    assert_eq!(apples_held(&subject), 1);
+
+    match &updates[..] {
+        [GameUpdate::ActivityExpired(), GameUpdate::Message(MessageCategory::Loot, message), ..] => {
+            assert_eq!(message, "You gain an apple.")
+        }
+        updates => panic!("unexpected updates: {:?}", updates),
+    }
+}
+
+#[test]
+fn picking_apples_repeats_until_aborted() {
+    let mut subject = start_picking_apples();
+
+    subject.wait(120000);
+    assert_eq!(apples_held(&subject), 2);
+
+    subject.run(Command::AbortActivity);
+    assert_eq!(subject.player.activity, None);
+    assert_eq!(subject.game_state.scheduler.time_until_next_event(), None);
+
+    subject.wait(120000);
+    assert_eq!(apples_held(&subject), 2);
+}
+
+#[test]
+fn picking_apples_trains_harvesting() {
+    let mut subject = start_picking_apples();
+
+    let updates = subject.wait(4 * ActivateAppleTreeCommand::DURATION as u64);
+
+    assert_eq!(
+        subject.player.skills.experience(Skill::Harvesting),
+        4 * ActivateAppleTreeCommand::EXPERIENCE
+    );
+    assert_eq!(subject.player.skills.level(Skill::Harvesting), 2);
+    assert!(subject.player.activity.is_some());
+
+    let level_ups: Vec<GameUpdate> = updates
+        .into_iter()
+        .filter(|u| matches!(u, GameUpdate::SkillLevelIncreased(_, _)))
+        .collect();
+    assert_eq!(
+        format!("{:?}", level_ups),
+        "[SkillLevelIncreased(Harvesting, 2)]"
+    );
+}
+
+#[test]
+fn an_apple_tree_is_not_used_unless_its_requirement_is_met() {
+    let mut subject = TestGame::load("maps/test.map");
+
+    subject.teleport_player(9, 8);
+    subject.use_towards(Direction::Down);
+
+    assert_eq!(subject.player.activity, None);
+    assert_eq!(subject.game_state.scheduler.time_until_next_event(), None);
+}
+
+#[test]
+fn killing_a_creature_aborts_picking_apples() {
+    let mut subject = start_picking_apples();
+    let rat = subject.character_at(8, 10).id;
+    subject.run(Command::Teleport(rat, 8, 8));
+    assert!(subject.player.activity.is_some());
+
+    let updates = subject.run(Command::KillCharacter(rat));
+
+    assert!(matches!(updates[0], GameUpdate::ActivityAborted()));
+    assert_eq!(subject.player.activity, None);
+    assert_eq!(subject.game_state.scheduler.time_until_next_event(), None);
+}
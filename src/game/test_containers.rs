@@ -0,0 +1,154 @@
+use super::test_fixture::TestGame;
+use super::*;
+use command::Rejection;
+
+// in test.map the player starts with a backpack and the chest is facility 5.
+fn load() -> TestGame {
+    TestGame::load("maps/test.map")
+}
+
+fn backpack(game: &TestGame) -> (u64, u64) {
+    let id = game.held("Backpack");
+    (id, game.items.container_of(id).expect("a container"))
+}
+
+fn rejection(updates: &[GameUpdate]) -> Option<Rejection> {
+    updates.iter().find_map(|update| match update {
+        GameUpdate::CommandRejected(rejection) => Some(rejection.clone()),
+        _ => None,
+    })
+}
+
+#[test]
+fn containers_get_inventories_of_their_own() {
+    let game = load();
+    let (_backpack_id, container_id) = backpack(&game);
+
+    let container = &game.inventories[&container_id];
+
+    assert!(container.is_empty());
+    assert_eq!(container.capacity(), 12);
+    assert_ne!(container_id, 1);
+    assert_ne!(container_id, 5);
+}
+
+#[test]
+fn a_held_or_worn_container_can_be_opened() {
+    let mut game = load();
+    let (backpack_id, container_id) = backpack(&game);
+
+    let updates = game.run(Command::OpenContainer(backpack_id));
+    assert!(matches!(
+        updates[..],
+        [GameUpdate::ExternalInventoryOpened(ref items, id)] if items.is_empty() && id == container_id
+    ));
+
+    game.run(Command::CloseExternalInventory);
+    game.run(Command::EquipItem(backpack_id));
+    let updates = game.run(Command::OpenContainer(backpack_id));
+    assert!(matches!(
+        updates[..],
+        [GameUpdate::ExternalInventoryOpened(_, id)] if id == container_id
+    ));
+}
+
+#[test]
+fn other_items_cannot_be_opened() {
+    let mut game = load();
+    let cap_id = game.held("Amber Leather Cap");
+
+    let updates = game.run(Command::OpenContainer(cap_id));
+
+    assert_eq!(rejection(&updates), Some(Rejection::NotAContainer(cap_id)));
+}
+
+#[test]
+fn contents_travel_with_their_container() {
+    let mut game = load();
+    let (backpack_id, container_id) = backpack(&game);
+    let cap_id = game.held("Amber Leather Cap");
+
+    game.run(Command::TransferItem(cap_id, 1, container_id, 1));
+    game.run(Command::DropItem(backpack_id, 1));
+    game.run(Command::TakeItem(1));
+    game.run(Command::TransferItem(backpack_id, 1, 5, 1));
+    game.run(Command::TransferItem(backpack_id, 5, 1, 1));
+
+    assert!(game.inventories[&1].holds(backpack_id));
+    assert_eq!(game.items.container_of(backpack_id), Some(container_id));
+    assert!(game.inventories[&container_id].holds(cap_id));
+}
+
+#[test]
+fn a_container_cannot_be_put_inside_itself() {
+    let mut game = load();
+    let (backpack_id, container_id) = backpack(&game);
+    let cap_id = game.held("Amber Leather Cap");
+
+    let updates = game.run(Command::TransferItem(backpack_id, 1, container_id, 1));
+    assert_eq!(
+        rejection(&updates),
+        Some(Rejection::ContainerIntoItself(backpack_id))
+    );
+
+    game.run(Command::TransferAllItems(1, container_id));
+    assert!(game.inventories[&1].holds(backpack_id));
+    assert!(game.inventories[&container_id].holds(cap_id));
+}
+
+#[test]
+fn containers_dropped_by_creatures_can_be_opened() {
+    let mut game = load();
+    game.items.loot_tables = LootTableList::read_in_loot_tables(&vec![
+        "rat_loot {".into(),
+        "    dropped_by: Rat".into(),
+        "    1 carrying_bag".into(),
+        "}".into(),
+    ]);
+    let rat = game.character_at(8, 10);
+
+    game.stand_next_to(8, 10);
+    game.run(Command::KillCharacter(rat.id));
+    game.run(Command::Teleport(game.player.id, 8, 10));
+    game.run(Command::TakeItem(1));
+
+    let bag_id = game.held("Carrying Bag");
+    let container_id = game.items.container_of(bag_id).expect("a container");
+    assert_eq!(game.inventories[&container_id].capacity(), 6);
+    let updates = game.run(Command::OpenContainer(bag_id));
+    assert_eq!(rejection(&updates), None);
+}
+
+#[test]
+fn containers_from_spawn_points_get_inventories_of_their_own() {
+    let mut game = load();
+    let index = game.map.spawn_points.len() as u64;
+    game.map
+        .spawn_points
+        .push(SpawnPoint::new(Spawn::Item("dwarven_sack".into()), 40, 3));
+
+    game.run_event(GameEvent::Repopulate(index));
+
+    let sack = game
+        .items
+        .find_nth_at(40, 3, 0)
+        .map(ItemState::extract_item)
+        .expect("a sack should have been spawned");
+    let container_id = game.items.container_of(sack.id).expect("a container");
+    assert_eq!(game.inventories[&container_id].capacity(), 8);
+}
+
+#[test]
+fn dropping_an_open_container_closes_it() {
+    let mut game = load();
+    let (backpack_id, _container_id) = backpack(&game);
+    game.run(Command::OpenContainer(backpack_id));
+
+    let updates = game.run(Command::DropItem(backpack_id, 1));
+
+    assert!(matches!(
+        updates.last(),
+        Some(GameUpdate::ExternalInventoryClosed)
+    ));
+    assert_eq!(game.player.external_inventory, None);
+}
@@ -0,0 +1,190 @@
+use super::test_fixture::TestGame;
+use super::*;
+use command::Rejection;
+
+// level1.map has a door at 31,7 locked with a brass key, and one at 43,18 that closes after 5s.
+fn closed_at(updates: &[GameUpdate], x: i32, y: i32) -> bool {
+    updates.iter().any(|update| match update {
+        TileChangedAt(tx, ty, tile_map::Tile::ClosedDoor, _) => (*tx, *ty) == (x, y),
+        _ => false,
+    })
+}
+
+#[test]
+fn doors_are_read_in_with_the_level() {
+    let subject = TestGame::load("maps/level1.map");
+
+    assert_eq!(subject.map.doors.len(), 4);
+    assert_eq!(
+        subject.map.doors.at(31, 7).unwrap().key,
+        Some("brass_key".to_string())
+    );
+    assert_eq!(
+        subject.map.doors.at(43, 18).unwrap().closes_after,
+        Some(5000)
+    );
+    assert_eq!(subject.map.doors.at(19, 7).unwrap().closes_after, None);
+}
+
+#[test]
+fn a_door_closes_itself_after_its_delay() {
+    let mut subject = TestGame::load("maps/level1.map");
+    subject.teleport_player(43, 17);
+
+    assert!(closed_at(&subject.use_towards(Direction::Down), 43, 18));
+    subject.use_towards(Direction::Down);
+    assert_eq!(subject.map.at(43, 18), tile_map::Tile::OpenDoor);
+
+    assert!(!closed_at(&subject.wait(4999), 43, 18));
+    assert!(closed_at(&subject.wait(1), 43, 18));
+    assert!(subject.obstacles.is_blocked_at(43, 18));
+}
+
+#[test]
+fn a_door_waits_to_close_until_its_doorway_is_clear() {
+    let mut subject = TestGame::load("maps/level1.map");
+    subject.teleport_player(43, 17);
+    subject.use_towards(Direction::Down);
+    subject.use_towards(Direction::Down);
+    subject.step(Direction::Down);
+
+    assert!(!closed_at(&subject.wait(5000), 43, 18));
+    assert_eq!(subject.map.at(43, 18), tile_map::Tile::OpenDoor);
+
+    subject.step(Direction::Down);
+    assert!(closed_at(&subject.wait(5000), 43, 18));
+}
+
+#[test]
+fn closing_a_door_by_hand_cancels_its_closing_by_itself() {
+    let mut subject = TestGame::load("maps/level1.map");
+    subject.teleport_player(43, 17);
+    subject.use_towards(Direction::Down);
+    subject.use_towards(Direction::Down);
+    subject.use_towards(Direction::Down);
+
+    assert!(subject.wait(5000).is_empty());
+    assert_eq!(subject.map.doors.at(43, 18).unwrap().pending_close, None);
+}
+
+#[test]
+fn a_locked_door_opens_only_for_the_holder_of_its_key() {
+    let mut subject = TestGame::load("maps/level1.map");
+    subject.teleport_player(30, 7);
+
+    let updates = subject.use_towards(Direction::Right);
+    assert!(updates
+        .iter()
+        .any(|update| matches!(update, CommandRejected(Rejection::DoorLocked(31, 7)))));
+    assert_eq!(subject.map.at(31, 7), tile_map::Tile::ClosedDoor);
+
+    let key = Item::spawn_from_type("brass_key", 1, &subject.items.item_types);
+    subject
+        .inventories
+        .get_mut(&subject.player.inventory_id())
+        .unwrap()
+        .force_accept(&key);
+    subject.use_towards(Direction::Right);
+
+    assert_eq!(subject.map.at(31, 7), tile_map::Tile::OpenDoor);
+}
+
+#[test]
+fn closing_an_unknown_door_is_reported() {
+    let mut subject = TestGame::load("maps/level1.map");
+
+    let updates = subject.run_event(GameEvent::CloseDoor(99));
+
+    assert!(updates.iter().any(|update| matches!(
+        update,
+        GameUpdate::Message(_, message) if message == "Door 99 does not exist."
+    )));
+}
+
+// with bump to interact, walking into a door or facility uses it.
+#[test]
+fn walking_into_a_closed_door_only_turns_to_face_it_by_default() {
+    let mut subject = TestGame::load("maps/level1.map");
+    subject.teleport_player(30, 7);
+
+    subject.walk(Direction::Right);
+
+    assert_eq!(subject.map.at(31, 7), tile_map::Tile::ClosedDoor);
+    assert_eq!(subject.player.facing, Direction::Right);
+}
+
+#[test]
+fn bumping_into_a_door_opens_it() {
+    let mut subject = TestGame::load("maps/level1.map");
+    subject.teleport_player(43, 17);
+    subject.use_towards(Direction::Down);
+
+    let updates = subject.run(Command::ToggleBumpToInteract);
+    assert!(subject.player.bump_to_interact);
+    assert!(matches!(
+        updates[..],
+        [GameUpdate::Message(MessageCategory::System, _)]
+    ));
+
+    subject.walk(Direction::Down);
+    assert_eq!(subject.map.at(43, 18), tile_map::Tile::OpenDoor);
+    assert_eq!((subject.player.x, subject.player.y), (43, 17));
+
+    // an open door is walked through rather than closed.
+    subject.step(Direction::Down);
+    assert_eq!(subject.map.at(43, 18), tile_map::Tile::OpenDoor);
+    assert_eq!((subject.player.x, subject.player.y), (43, 18));
+}
+
+#[test]
+fn bumping_into_a_chest_opens_it() {
+    let mut subject = TestGame::load("maps/level1.map");
+    subject.run(Command::ToggleBumpToInteract);
+
+    // the player starts beside the chest at 7,7.
+    let updates = subject.walk(Direction::Left);
+
+    assert!(updates
+        .iter()
+        .any(|update| matches!(update, ExternalInventoryOpened(_, _))));
+    assert!(subject.player.external_inventory.is_some());
+}
+
+#[test]
+fn bumping_into_a_wall_turns_to_face_it() {
+    let mut subject = TestGame::load("maps/level1.map");
+    subject.run(Command::ToggleBumpToInteract);
+    subject.teleport_player(1, 1);
+
+    subject.walk(Direction::Left);
+
+    assert_eq!(subject.player.facing, Direction::Left);
+    assert_eq!((subject.player.x, subject.player.y), (1, 1));
+}
+
+#[test]
+fn the_player_is_told_when_a_door_opens_and_closes() {
+    let mut subject = TestGame::load("maps/level1.map");
+    subject.teleport_player(43, 17);
+
+    let messages = |updates: Vec<GameUpdate>| -> Vec<String> {
+        updates
+            .into_iter()
+            .filter_map(|update| match update {
+                GameUpdate::Message(MessageCategory::System, message) => Some(message),
+                _ => None,
+            })
+            .collect()
+    };
+
+    // the door starts open.
+    assert_eq!(
+        messages(subject.use_towards(Direction::Down)),
+        vec!["The door closes."]
+    );
+    assert_eq!(
+        messages(subject.use_towards(Direction::Down)),
+        vec!["The door opens."]
+    );
+    assert_eq!(messages(subject.wait(5000)), vec!["The door closes."]);
+}
@@ -2,7 +2,6 @@ use super::*;
 use std::sync::mpsc::channel;
 use ui::input::InputState;
 use ui::UIState;
-use ItemClass::*;
 
 #[test]
 fn entering_external_inventory_state() {
@@ -131,25 +130,32 @@ fn opening_external_inventory() {
         Item {
             id: 38,
             quantity: 1,
-            item_type: ItemType::new(Headwear, "Old leather cap"),
+            item_type: ItemType::new(ItemClass::Headwear, "Old leather cap"),
         },
         Item {
             id: 35,
             quantity: 1,
-            item_type: ItemType::new(Dagger, "Shiny Dagger"),
+            item_type: ItemType::new(ItemClass::Dagger, "Shiny Dagger"),
         },
         Item {
             id: 36,
             quantity: 1,
-            item_type: ItemType::new(BladeWeapon, "Sharp Short Sword"),
+            item_type: ItemType::new(ItemClass::BladeWeapon, "Sharp Short Sword"),
         },
         Item {
             id: 37,
             quantity: 1,
-            item_type: ItemType::new(Potion, "pink potion"),
+            item_type: ItemType::new(ItemClass::Potion, "pink potion"),
         },
     ];
-    if let ExternalInventoryOpened(inventory, 9) = update {
+    // ids are allocated per thread and this test does not reset them, so the chest is looked up.
+    let chest_id = match map.at(7, 7) {
+        tile_map::Tile::Facility(id) => id,
+        tile => panic!("chest not found: {:?}", tile),
+    };
+    if let ExternalInventoryOpened(inventory, inventory_id) = update {
+        assert_eq!(inventory_id, chest_id);
+
         // assert given inventory is the appropriate external inventory
         assert_eq!(inventory.clone().sort(), exp_inventory.clone().sort());
 
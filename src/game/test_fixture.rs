@@ -0,0 +1,178 @@
+use super::*;
+use std::sync::mpsc::{channel, Receiver};
+
+/// a freshly loaded level that tests play through a command, or a wait, at a time.
+///   Each returns the updates it caused.
+pub(super) struct TestGame {
+    pub(super) game_state: GameState,
+    pub(super) player: Player,
+    pub(super) map: TileMap,
+    pub(super) obstacles: BlockingMap,
+    pub(super) characters: CharacterList,
+    pub(super) item_class_specifiers: ItemClassSpecifierList,
+    pub(super) items: ItemList,
+    pub(super) facilities: FacilityList,
+    pub(super) inventories: InventoryList,
+    pub(super) sender: GameUpdateSender,
+    pub(super) receiver: Receiver<GameUpdate>,
+}
+
+impl TestGame {
+    pub(super) fn load(level_path: &str) -> Self {
+        Self::load_seeded(level_path, Rng::DEFAULT_SEED)
+    }
+
+    /// loads the level with its random rolls, such as those of its loot tables, made from seed.
+    pub(super) fn load_seeded(level_path: &str, seed: u64) -> Self {
+        reset_ids();
+        seed_random(seed);
+        let (
+            player,
+            map,
+            obstacles,
+            characters,
+            item_class_specifiers,
+            items,
+            facilities,
+            inventories,
+        ) = GameState::initialize_game(level_path, None);
+        let (sender, receiver) = channel();
+
+        Self {
+            game_state: GameState::new(),
+            player,
+            map,
+            obstacles,
+            characters,
+            item_class_specifiers,
+            items,
+            facilities,
+            inventories,
+            sender,
+            receiver,
+        }
+    }
+
+    pub(super) fn run(&mut self, command: Command) -> Vec<GameUpdate> {
+        self.game_state.game_loop_iteration(
+            &mut self.player,
+            &mut self.map,
+            &mut self.obstacles,
+            &mut self.characters,
+            &mut self.item_class_specifiers,
+            &mut self.items,
+            &mut self.facilities,
+            &mut self.inventories,
+            &command,
+            Some(&self.sender),
+            None,
+        );
+        self.receiver.try_iter().collect()
+    }
+
+    pub(super) fn run_event(&mut self, game_event: GameEvent) -> Vec<GameUpdate> {
+        self.game_state.run_game_event(
+            &mut self.player,
+            &mut self.map,
+            &mut self.obstacles,
+            &mut self.characters,
+            &mut self.item_class_specifiers,
+            &mut self.items,
+            &mut self.facilities,
+            &mut self.inventories,
+            &game_event,
+            Some(&self.sender),
+            None,
+        );
+        self.receiver.try_iter().collect()
+    }
+
+    pub(super) fn wait(&mut self, millis: u64) -> Vec<GameUpdate> {
+        self.game_state.advance_clock(
+            millis,
+            &mut self.player,
+            &mut self.map,
+            &mut self.obstacles,
+            &mut self.characters,
+            &mut self.item_class_specifiers,
+            &mut self.items,
+            &mut self.facilities,
+            &mut self.inventories,
+            Some(&self.sender),
+            None,
+        );
+        self.receiver.try_iter().collect()
+    }
+
+    /// lets the clock run on by millis, then advances the world as the game loop does each tick.
+    pub(super) fn tick_after(&mut self, millis: u64) -> Vec<GameUpdate> {
+        self.game_state.scheduler.advance(millis);
+        self.game_state.tick(
+            &mut self.player,
+            &mut self.map,
+            &mut self.obstacles,
+            &mut self.characters,
+            &mut self.item_class_specifiers,
+            &mut self.items,
+            &mut self.facilities,
+            &mut self.inventories,
+            Some(&self.sender),
+            None,
+        );
+        self.receiver.try_iter().collect()
+    }
+
+    pub(super) fn teleport_player(&mut self, x: i32, y: i32) {
+        self.run(Command::Teleport(self.player.id, x, y));
+    }
+
+    /// teleports the player onto the first open tile next to x,y.
+    pub(super) fn stand_next_to(&mut self, x: i32, y: i32) {
+        let (x, y) = (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| (x + dx, y + dy)))
+            .find(|&(x, y)| !self.obstacles.is_blocked_at(x, y))
+            .unwrap_or_else(|| panic!("there should be room next to {},{}", x, y));
+        self.teleport_player(x, y);
+    }
+
+    pub(super) fn use_towards(&mut self, direction: Direction) -> Vec<GameUpdate> {
+        self.run(Command::Move(direction, MoveCommandMode::Use))
+    }
+
+    pub(super) fn walk(&mut self, direction: Direction) -> Vec<GameUpdate> {
+        self.run(Command::Move(direction, MoveCommandMode::Normal))
+    }
+
+    pub(super) fn sneak(&mut self, direction: Direction) -> Vec<GameUpdate> {
+        self.run(Command::Move(direction, MoveCommandMode::Sneak))
+    }
+
+    /// walks and waits for the step, and any that follow on from it, to be over.
+    pub(super) fn step(&mut self, direction: Direction) -> Vec<GameUpdate> {
+        let mut updates = self.walk(direction);
+
+        while self.player.moving {
+            let delay = self.game_state.scheduler.time_until_next_event().unwrap();
+            updates.extend(self.wait(delay));
+        }
+        updates
+    }
+
+    /// returns the id of the item of the description the player holds.
+    pub(super) fn held(&self, description: &str) -> u64 {
+        self.inventories[&self.player.inventory_id()]
+            .to_vec()
+            .iter()
+            .find(|item| item.raw_description() == description)
+            .unwrap_or_else(|| panic!("the player should hold a {}", description))
+            .id
+    }
+
+    /// returns the creature at x,y.
+    pub(super) fn character_at(&self, x: i32, y: i32) -> Character {
+        self.characters
+            .iter()
+            .find(|c| (c.x, c.y) == (x, y))
+            .unwrap_or_else(|| panic!("there should be a creature at {},{}", x, y))
+    }
+}
@@ -0,0 +1,97 @@
+use super::test_fixture::TestGame;
+use super::*;
+
+/// plays the commands chosen for the freshly loaded test map and returns every update sent.
+fn play(choose_commands: impl FnOnce(&ItemList) -> Vec<Command>) -> Vec<GameUpdate> {
+    let mut subject = TestGame::load("maps/test.map");
+
+    choose_commands(&subject.items)
+        .into_iter()
+        .flat_map(|command| subject.run(command))
+        .collect()
+}
+
+fn messages(updates: &[GameUpdate]) -> Vec<String> {
+    updates
+        .iter()
+        .filter_map(|update| match update {
+            GameUpdate::Message(_, message) => Some(message.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn find_item(items: &ItemList, predicate: impl Fn(&ItemState) -> bool) -> u64 {
+    items
+        .iter()
+        .find(|(_id, state)| predicate(state))
+        .map(|(id, _state)| *id)
+        .expect("test.map should have such an item")
+}
+
+#[test]
+fn commands_for_missing_things_are_reported_and_the_game_carries_on() {
+    let updates = play(|_items| {
+        vec![
+            Command::DropItem(9999, 1),
+            Command::EquipItem(9999),
+            Command::TakeItem(5),
+            Command::Teleport(9999, 10, 10),
+            Command::TransferAllItems(1, 4242),
+            Command::RefreshInventory,
+        ]
+    });
+
+    assert_eq!(
+        messages(&updates),
+        vec![
+            "Item 9999 does not exist.",
+            "Item 9999 does not exist.",
+            "There is no item 5 here to take.",
+            "Character 9999 does not exist.",
+            "Inventory 4242 does not exist.",
+        ]
+    );
+    assert!(matches!(
+        updates.last(),
+        Some(GameUpdate::InventoryUpdated(_))
+    ));
+}
+
+#[test]
+fn commands_for_items_in_the_wrong_state_are_reported() {
+    let mut bundled_id = 0;
+    let mut stored_id = 0;
+    let updates = play(|items| {
+        bundled_id = find_item(items, |state| matches!(state, ItemState::Bundle(..)));
+        stored_id = find_item(items, |state| matches!(state, ItemState::Stored(..)));
+        vec![
+            Command::DropItem(bundled_id, 1),
+            Command::EquipItem(bundled_id),
+            Command::UnequipItem(stored_id),
+        ]
+    });
+
+    assert_eq!(
+        messages(&updates),
+        vec![
+            format!("Item {} is not being held.", bundled_id),
+            format!("Item {} is not being held.", bundled_id),
+            format!("Item {} is not equipped.", stored_id),
+        ]
+    );
+}
+
+#[test]
+fn using_a_facility_that_is_missing_is_reported() {
+    let mut subject = TestGame::load("maps/test.map");
+    subject.teleport_player(9, 8);
+    subject
+        .map
+        .set_tile_at(9, 9, tile_map::Tile::Facility(9999));
+
+    let updates = subject.use_towards(Direction::Down);
+
+    assert_eq!(messages(&updates), vec!["Facility 9999 does not exist."]);
+    assert_eq!(subject.player.activity, None);
+}
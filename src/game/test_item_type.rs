@@ -1,28 +1,146 @@
 use super::*;
-use ItemClass::*;
 
 #[test]
 fn items_have_a_type() {
-    let subject = Item::spawn(BladeWeapon, "A large knife");
+    let subject = Item::spawn(ItemClass::BladeWeapon, "A large knife");
 
     let _item_type = subject.item_type;
 }
 #[test]
 fn items_class_is_provided_by_its_type() {
-    let mut subject = Item::spawn(BladeWeapon, "large knife");
+    let mut subject = Item::spawn(ItemClass::BladeWeapon, "large knife");
 
-    let new_type = ItemType::new(Headwear, "hat");
+    let new_type = ItemType::new(ItemClass::Headwear, "hat");
     subject.item_type = new_type;
 
-    assert_eq!(subject.class(), Headwear);
+    assert_eq!(subject.class(), ItemClass::Headwear);
 }
 
 #[test]
 fn items_raw_description_is_provided_by_its_type() {
-    let mut subject = Item::spawn(BladeWeapon, "large knife");
+    let mut subject = Item::spawn(ItemClass::BladeWeapon, "large knife");
 
-    let new_type = ItemType::new(Headwear, "bold cap");
+    let new_type = ItemType::new(ItemClass::Headwear, "bold cap");
     subject.item_type = new_type;
 
     assert_eq!(subject.raw_description(), "bold cap");
 }
+
+#[test]
+fn item_types_can_require_a_skill_level() {
+    let mut item_types = vec![
+        r#"sharp_short_sword bladeweapon "Sharp Short Sword" {"#.to_string(),
+        "    required_skill: Combat".to_string(),
+        "    required_level: 3 // a sword for the practiced".to_string(),
+        "}".to_string(),
+        r#"reed_basket tool "Reed Basket""#.to_string(),
+    ];
+
+    let subject = ItemType::read_in_item_types(&mut item_types);
+
+    assert_eq!(
+        subject["sharp_short_sword"].skill_requirement(),
+        Some((Skill::Combat, 3))
+    );
+    assert_eq!(subject["reed_basket"].skill_requirement(), None);
+}
+
+#[test]
+fn a_required_skill_level_joins_any_declared_requirement() {
+    let mut item_types = vec![
+        r#"ranger_blade bladeweapon "Ranger Blade" {"#.to_string(),
+        "    required_level: 4".to_string(),
+        "    required_skill: Combat".to_string(),
+        "    requires: :ranger".to_string(),
+        "}".to_string(),
+    ];
+
+    let subject = ItemType::read_in_item_types(&mut item_types);
+
+    assert_eq!(
+        subject["ranger_blade"].requirement(),
+        &Requirement::All(vec![
+            Requirement::Endorsement(":ranger".into()),
+            Requirement::SkillLevel(Skill::Combat, 4),
+        ])
+    );
+    assert_eq!(
+        subject["ranger_blade"].skill_requirement(),
+        Some((Skill::Combat, 4))
+    );
+}
+
+#[test]
+fn item_types_can_declare_a_requirement_to_equip() {
+    let mut item_types = vec![
+        r#"orchard_gloves gloves "Orchard Gloves" {"#.to_string(),
+        r#"    requires: all(:newb, has("Reed Basket")) // only with a basket"#.to_string(),
+        "}".to_string(),
+    ];
+
+    let subject = ItemType::read_in_item_types(&mut item_types);
+
+    assert_eq!(
+        subject["orchard_gloves"].requirement(),
+        &Requirement::All(vec![
+            Requirement::Endorsement(":newb".into()),
+            Requirement::Has("Reed Basket".into()),
+        ])
+    );
+}
+
+#[test]
+fn item_types_can_declare_their_mounting_points() {
+    use MountingPoint::*;
+
+    let mut item_types = vec![
+        r#"two_handed_sword bladeweapon "Two-Handed Sword" {"#.to_string(),
+        "    mounting_points: OnHand + OffHand".to_string(),
+        "}".to_string(),
+        r#"copper_ring ring "Copper Ring""#.to_string(),
+    ];
+
+    let subject = ItemType::read_in_item_types(&mut item_types);
+
+    assert_eq!(
+        subject["two_handed_sword"].fitting(),
+        Some(&Fitting::on(&[OnHand, OffHand]))
+    );
+    assert_eq!(subject["copper_ring"].fitting(), None);
+    assert_eq!(
+        Item::new(1, subject["copper_ring"].clone(), 1).class(),
+        ItemClass::Ring
+    );
+}
+
+#[test]
+fn item_types_can_change_the_speed_of_whoever_equips_them() {
+    let mut item_types = vec![
+        r#"two_handed_sword bladeweapon "Two-Handed Sword" {"#.to_string(),
+        "    speed: -2".to_string(),
+        "}".to_string(),
+        r#"winged_cap headwear "Winged Cap" { speed: +3 }"#.to_string(),
+        r#"copper_ring ring "Copper Ring""#.to_string(),
+    ];
+
+    let subject = ItemType::read_in_item_types(&mut item_types);
+
+    assert_eq!(subject["two_handed_sword"].speed(), -2);
+    assert_eq!(subject["winged_cap"].speed(), 3);
+    assert_eq!(subject["copper_ring"].speed(), 0);
+}
+
+#[test]
+fn item_types_can_use_classes_defined_only_in_the_data_file() {
+    let mut item_types = vec![r#"gold_amulet amulet "Gold Amulet""#.to_string()];
+
+    let subject = ItemType::read_in_item_types(&mut item_types);
+    let item = Item::new(1, subject["gold_amulet"].clone(), 1);
+
+    assert_eq!(item.class(), ItemClass::from_symbol('♀'));
+    assert_eq!(
+        ItemClassSpecifier::fitting_for(&item, &ItemClassSpecifier::initialize()),
+        Fitting::on(&[MountingPoint::Neck])
+    );
+    assert!(!item.is_stackable());
+}
@@ -0,0 +1,54 @@
+use super::test_fixture::TestGame;
+use super::*;
+
+// level1.map stocks "A dusty chest" from chest_loot; its rats drop rat_loot.
+fn dusty_chest_contents(seed: u64) -> Vec<(String, u8)> {
+    let subject = TestGame::load_seeded("maps/level1.map", seed);
+
+    let (_id, chest) = subject
+        .facilities
+        .iter()
+        .find(|(_id, facility)| facility.description == "A dusty chest")
+        .expect("level1.map should have a dusty chest");
+    let mut result: Vec<(String, u8)> = subject.inventories[&chest.inventory.unwrap()]
+        .to_vec()
+        .iter()
+        .map(|item| (item.raw_description(), item.quantity))
+        .collect();
+
+    result.sort();
+    result
+}
+
+#[test]
+fn chests_are_stocked_from_their_loot_tables_when_the_level_loads() {
+    let contents: Vec<Vec<(String, u8)>> = (0..10).map(dusty_chest_contents).collect();
+
+    assert!(contents.iter().any(|items| !items.is_empty()));
+    for items in contents.iter() {
+        assert!(items.len() <= 3);
+    }
+    assert_eq!(contents[3], dusty_chest_contents(3));
+}
+
+#[test]
+fn a_slain_rat_drops_its_loot_where_it_stood() {
+    for seed in 0..10 {
+        let mut subject = TestGame::load_seeded("maps/test.map", seed);
+        let rat = subject.character_at(8, 10);
+        subject.stand_next_to(8, 10);
+
+        let updates = subject.run(Command::KillCharacter(rat.id));
+
+        assert!(matches!(updates[0], GameUpdate::CharacterExited(id) if id == rat.id));
+        assert!(subject.characters.get(rat.id).is_none());
+        assert!(!subject.obstacles.is_blocked_at(8, 10));
+
+        let dropped = subject
+            .items
+            .iter()
+            .filter(|(_id, state)| state.is_bundled_at(8, 10))
+            .count();
+        assert_eq!(dropped, updates.len() - 1);
+    }
+}
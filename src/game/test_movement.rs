@@ -0,0 +1,301 @@
+use super::test_fixture::TestGame;
+use super::*;
+
+fn started(updates: &[GameUpdate]) -> Option<(i32, i32, u32)> {
+    updates.iter().find_map(|update| match update {
+        MovementStarted(1, x, y, duration) => Some((*x, *y, *duration)),
+        _ => None,
+    })
+}
+
+fn position(subject: &TestGame) -> (i32, i32) {
+    (subject.player.x, subject.player.y)
+}
+
+#[test]
+fn a_step_is_taken_once_it_is_over() {
+    let mut subject = TestGame::load("maps/level1.map");
+    subject.teleport_player(44, 2);
+
+    let updates = subject.walk(Direction::Down);
+    assert_eq!(
+        started(&updates),
+        Some((44, 3, Player::WALKING_STEP as u32))
+    );
+    assert_eq!(position(&subject), (44, 2));
+    assert!(subject.player.moving);
+
+    subject.wait(Player::WALKING_STEP - 1);
+    assert_eq!(position(&subject), (44, 2));
+
+    let updates = subject.wait(1);
+    assert_eq!(position(&subject), (44, 3));
+    assert!(matches!(
+        updates[..],
+        [
+            CharacterMoved(1, 44, 3),
+            CharacterFacingChanged(1, Direction::Down),
+            MovementCompleted(1)
+        ]
+    ));
+    assert!(!subject.player.moving);
+    assert_eq!(subject.player.activity, None);
+}
+
+#[test]
+fn a_step_taken_during_a_step_follows_on_from_it() {
+    let mut subject = TestGame::load("maps/level1.map");
+    subject.teleport_player(44, 5);
+
+    subject.walk(Direction::Down);
+    // only the latest of the steps taken meanwhile is queued.
+    assert_eq!(started(&subject.walk(Direction::Left)), None);
+    subject.walk(Direction::Right);
+
+    let updates = subject.wait(Player::WALKING_STEP);
+    assert_eq!(position(&subject), (44, 6));
+    assert_eq!(
+        started(&updates),
+        Some((45, 6, Player::WALKING_STEP as u32))
+    );
+
+    subject.wait(Player::WALKING_STEP);
+    assert_eq!(position(&subject), (45, 6));
+    assert!(!subject.player.moving);
+}
+
+#[test]
+fn doing_something_else_interrupts_a_step() {
+    let mut subject = TestGame::load("maps/level1.map");
+    subject.teleport_player(44, 1);
+    subject.walk(Direction::Down);
+    subject.walk(Direction::Down);
+
+    // using the wall behind the player.
+    let updates = subject.use_towards(Direction::Up);
+    assert!(updates
+        .iter()
+        .any(|update| matches!(update, MovementAborted(1))));
+    assert!(!subject.player.moving);
+    assert_eq!(subject.player.queued_step, None);
+
+    subject.wait(Player::WALKING_STEP * 2);
+    assert_eq!(position(&subject), (44, 1));
+}
+
+#[test]
+fn difficult_terrain_takes_longer_to_cross() {
+    let mut subject = TestGame::load("maps/level1.map");
+    // level1.map has grass at 50-53,2-3.
+    subject.teleport_player(50, 1);
+
+    let updates = subject.walk(Direction::Down);
+
+    assert_eq!(
+        started(&updates),
+        Some((50, 2, (Player::WALKING_STEP * 125 / 100) as u32))
+    );
+}
+
+#[test]
+fn heavy_equipment_slows_the_player_down() {
+    let mut subject = TestGame::load("maps/level1.map");
+    subject.teleport_player(44, 5);
+    let sword = Item::spawn_from_type("two_handed_sword", 1, &subject.items.item_types);
+    let inventory_id = subject.player.inventory_id();
+    subject
+        .items
+        .add(ItemState::Stored(sword.clone(), inventory_id));
+    subject
+        .inventories
+        .get_mut(&inventory_id)
+        .unwrap()
+        .force_accept(&sword);
+    subject.run(Command::EquipItem(sword.id));
+    assert_eq!(subject.player.speed(&subject.items), Player::BASE_SPEED - 2);
+
+    let updates = subject.walk(Direction::Down);
+
+    let duration =
+        Player::WALKING_STEP * Player::BASE_SPEED as u64 / (Player::BASE_SPEED as u64 - 2);
+    assert_eq!(started(&updates), Some((44, 6, duration as u32)));
+}
+
+#[test]
+fn walking_into_a_wall_turns_to_face_it_at_once() {
+    let mut subject = TestGame::load("maps/level1.map");
+    // level1.map has a wall at 31,1.
+    subject.teleport_player(32, 1);
+
+    let updates = subject.walk(Direction::Left);
+
+    assert!(matches!(
+        updates[..],
+        [CharacterFacingChanged(1, Direction::Left)]
+    ));
+    assert!(!subject.player.moving);
+}
+
+#[test]
+fn using_an_open_tile_only_turns_to_face_it() {
+    let mut subject = TestGame::load("maps/level1.map");
+    subject.teleport_player(44, 2);
+
+    let updates = subject.use_towards(Direction::Down);
+
+    assert!(matches!(
+        updates[..],
+        [CharacterFacingChanged(1, Direction::Down)]
+    ));
+    assert_eq!(position(&subject), (44, 2));
+    assert!(!subject.player.moving);
+}
+
+#[test]
+fn a_step_cannot_be_completed_unless_one_is_under_way() {
+    let mut subject = TestGame::load("maps/level1.map");
+    subject.teleport_player(44, 2);
+
+    let updates = subject.run_event(GameEvent::Step(Direction::Down, MoveCommandMode::Normal));
+
+    assert!(updates.is_empty());
+    assert_eq!(position(&subject), (44, 2));
+}
+
+#[test]
+fn a_step_is_completed_by_the_tick_it_is_over_in() {
+    let mut subject = TestGame::load("maps/level1.map");
+    subject.teleport_player(44, 2);
+    subject.walk(Direction::Down);
+
+    let tick = subject.game_state.tick_rate.tick_length().as_millis() as u64;
+    for _ in 0..Player::WALKING_STEP / tick - 1 {
+        assert!(subject.tick_after(tick).is_empty());
+    }
+    let updates = subject.tick_after(tick);
+
+    assert!(updates
+        .iter()
+        .any(|update| matches!(update, MovementCompleted(1))));
+    assert_eq!(position(&subject), (44, 3));
+}
+
+fn numbered(sequence: u64, direction: Direction) -> Command {
+    Command::Sequenced(
+        sequence,
+        Box::new(Command::Move(direction, MoveCommandMode::Normal)),
+    )
+}
+
+fn processed(updates: &[GameUpdate]) -> Vec<u64> {
+    updates
+        .iter()
+        .filter_map(|update| match update {
+            CommandProcessed(sequence) => Some(*sequence),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn a_numbered_step_is_reported_over_once_it_is_taken() {
+    let mut subject = TestGame::load("maps/level1.map");
+    subject.teleport_player(44, 2);
+
+    let updates = subject.run(numbered(1, Direction::Down));
+    assert!(started(&updates).is_some());
+    assert_eq!(processed(&updates), vec![]);
+
+    let updates = subject.wait(Player::WALKING_STEP);
+    assert_eq!(position(&subject), (44, 3));
+    // the client hears where the player is before it hears the step is over.
+    assert!(matches!(
+        updates[..],
+        [
+            CharacterMoved(1, 44, 3),
+            CharacterFacingChanged(1, Direction::Down),
+            MovementCompleted(1),
+            CommandProcessed(1)
+        ]
+    ));
+}
+
+#[test]
+fn a_numbered_move_that_goes_nowhere_is_reported_over_at_once() {
+    let mut subject = TestGame::load("maps/level1.map");
+    subject.teleport_player(32, 1);
+
+    let updates = subject.run(numbered(7, Direction::Left));
+
+    assert_eq!(position(&subject), (32, 1));
+    assert!(matches!(
+        updates[..],
+        [
+            CharacterFacingChanged(1, Direction::Left),
+            CommandProcessed(7)
+        ]
+    ));
+}
+
+#[test]
+fn numbered_steps_queued_during_a_step_are_each_reported_over() {
+    let mut subject = TestGame::load("maps/level1.map");
+    subject.teleport_player(44, 5);
+
+    subject.run(numbered(1, Direction::Down));
+    subject.run(numbered(2, Direction::Left));
+    // the step it replaces will never be taken.
+    let updates = subject.run(numbered(3, Direction::Right));
+    assert_eq!(processed(&updates), vec![2]);
+
+    let updates = subject.wait(Player::WALKING_STEP);
+    assert_eq!(processed(&updates), vec![1]);
+
+    let updates = subject.wait(Player::WALKING_STEP);
+    assert_eq!(position(&subject), (45, 6));
+    assert_eq!(processed(&updates), vec![3]);
+}
+
+#[test]
+fn only_the_last_of_three_numbered_steps_taken_during_a_step_is_kept() {
+    let mut subject = TestGame::load("maps/level1.map");
+    subject.teleport_player(44, 5);
+
+    subject.run(numbered(1, Direction::Down));
+    assert_eq!(
+        processed(&subject.run(numbered(2, Direction::Left))),
+        vec![]
+    );
+    assert_eq!(
+        processed(&subject.run(numbered(3, Direction::Down))),
+        vec![2]
+    );
+    assert_eq!(
+        processed(&subject.run(numbered(4, Direction::Right))),
+        vec![3]
+    );
+
+    let updates = subject.wait(Player::WALKING_STEP);
+    assert_eq!(position(&subject), (44, 6));
+    assert_eq!(processed(&updates), vec![1]);
+
+    let updates = subject.wait(Player::WALKING_STEP);
+    assert_eq!(position(&subject), (45, 6));
+    assert_eq!(processed(&updates), vec![4]);
+    assert!(!subject.player.moving);
+}
+
+#[test]
+fn numbered_steps_that_are_interrupted_are_reported_over() {
+    let mut subject = TestGame::load("maps/level1.map");
+    subject.teleport_player(44, 1);
+    subject.run(numbered(1, Direction::Down));
+    subject.run(numbered(2, Direction::Down));
+
+    let updates = subject.use_towards(Direction::Up);
+
+    assert_eq!(processed(&updates), vec![1, 2]);
+    assert_eq!(position(&subject), (44, 1));
+    assert_eq!(subject.player.step_sequence, None);
+    assert_eq!(subject.player.queued_sequence, None);
+}
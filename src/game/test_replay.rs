@@ -0,0 +1,201 @@
+use super::*;
+use recorder::StepKind;
+use std::sync::mpsc::channel;
+
+/// plays the commands the way game_loop does, but on a virtual clock, and returns the log.
+fn record_session(level_path: &str, commands: Vec<(u64, Command)>) -> String {
+    reset_ids();
+    let (game_update_tx, game_update_rx) = channel();
+    let (mut update_tx, _update_rx) = channel();
+    let mut recorder = Some(Recorder::new(vec![], level_path));
+
+    let (
+        mut player,
+        mut map,
+        mut obstacles,
+        mut characters,
+        mut item_class_specifiers,
+        mut items,
+        mut facilities,
+        mut inventories,
+    ) = GameState::initialize_game(level_path, Some(&game_update_tx));
+    forward_updates(&game_update_rx, &mut update_tx, &mut recorder);
+
+    let mut game_state = GameState::new();
+    game_state.schedule_spawn_points(&map);
+    for (game_time, command) in commands {
+        game_state
+            .scheduler
+            .advance(game_time - game_state.scheduler.now());
+
+        let events_run = game_state.process_scheduled_events(
+            &mut player,
+            &mut map,
+            &mut obstacles,
+            &mut characters,
+            &mut item_class_specifiers,
+            &mut items,
+            &mut facilities,
+            &mut inventories,
+            Some(&game_update_tx),
+            None,
+        );
+        if events_run > 0 {
+            recorder.as_mut().unwrap().record_advance(game_time);
+        }
+        forward_updates(&game_update_rx, &mut update_tx, &mut recorder);
+
+        recorder
+            .as_mut()
+            .unwrap()
+            .record_command(game_time, &command);
+        game_state.game_loop_iteration(
+            &mut player,
+            &mut map,
+            &mut obstacles,
+            &mut characters,
+            &mut item_class_specifiers,
+            &mut items,
+            &mut facilities,
+            &mut inventories,
+            &command,
+            Some(&game_update_tx),
+            None,
+        );
+        forward_updates(&game_update_rx, &mut update_tx, &mut recorder);
+    }
+
+    String::from_utf8(recorder.unwrap().into_inner()).unwrap()
+}
+
+// with ids reset, the chest is facility 5 and its reed basket is item 11.
+fn session() -> Vec<(u64, Command)> {
+    vec![
+        (100, Command::Move(Direction::Down, MoveCommandMode::Normal)),
+        (400, Command::Move(Direction::Up, MoveCommandMode::Normal)),
+        (700, Command::Move(Direction::Left, MoveCommandMode::Use)),
+        (800, Command::TransferAllItems(5, 1)),
+        (900, Command::CloseExternalInventory),
+        (1000, Command::Move(Direction::Up, MoveCommandMode::Normal)),
+    ]
+}
+
+#[test]
+fn a_recording_lists_the_level_commands_and_updates() {
+    let log = record_session("maps/test.map", session());
+
+    let recording = Recording::parse(&log);
+
+    assert_eq!(recording.level_path, "maps/test.map");
+    // each step is over before the next command, and is recorded as an advance of the clock.
+    assert_eq!(recording.steps.len(), 9);
+    assert!(!recording.steps[0].updates.is_empty());
+
+    if let StepKind::Command(game_time, Command::Move(Direction::Down, MoveCommandMode::Normal)) =
+        recording.steps[1].kind
+    {
+        assert_eq!(game_time, 100);
+    } else {
+        panic!("unexpected step: {:?}", recording.steps[1].kind);
+    }
+    assert_eq!(
+        recording.steps[1].updates,
+        vec!["MovementStarted(1, 8, 8, 250)"]
+    );
+    match recording.steps[2].kind {
+        StepKind::Advance(400) => {}
+        ref kind => panic!("unexpected step: {:?}", kind),
+    }
+    assert_eq!(
+        recording.steps[2].updates,
+        vec![
+            "CharacterMoved(1, 8, 8)",
+            "CharacterFacingChanged(1, Down)",
+            "MovementCompleted(1)",
+            "PlayerNoticed(4)"
+        ]
+    );
+}
+
+#[test]
+fn replaying_a_recording_reproduces_its_updates() {
+    let log = record_session("maps/test.map", session());
+
+    assert_eq!(Replay::run(&Recording::parse(&log)), Ok(()));
+}
+
+#[test]
+fn replaying_reproduces_scheduled_events() {
+    let log = record_session(
+        "maps/level1.map",
+        vec![
+            (100, Command::Move(Direction::Left, MoveCommandMode::Use)),
+            (200, Command::TransferAllItems(5, 1)),
+            (300, Command::CloseExternalInventory),
+            (400, Command::EquipItem(11)),
+            (
+                500,
+                Command::Move(Direction::Right, MoveCommandMode::Normal),
+            ),
+            (800, Command::Move(Direction::Down, MoveCommandMode::Normal)),
+            (1100, Command::Move(Direction::Down, MoveCommandMode::Use)),
+            (130000, Command::RefreshInventory),
+        ],
+    );
+    let recording = Recording::parse(&log);
+
+    match recording.steps[10].kind {
+        StepKind::Advance(130000) => {}
+        ref kind => panic!("unexpected step: {:?}", kind),
+    }
+    assert!(recording.steps[10]
+        .updates
+        .contains(&"ActivityExpired".to_string()));
+
+    assert_eq!(Replay::run(&recording), Ok(()));
+}
+
+#[test]
+fn replaying_an_altered_recording_reports_where_it_diverged() {
+    let log = record_session("maps/test.map", session());
+    let altered = log.replace("CharacterMoved(1, 8, 8)", "CharacterMoved(1, 8, 9)");
+
+    let mismatch = Replay::run(&Recording::parse(&altered)).unwrap_err();
+
+    assert_eq!(mismatch.step, 2);
+    assert!(mismatch
+        .actual
+        .contains(&"CharacterMoved(1, 8, 8)".to_string()));
+}
+
+#[test]
+fn numbered_commands_are_recorded_and_replayed() {
+    let log = record_session(
+        "maps/test.map",
+        vec![
+            (
+                100,
+                Command::Sequenced(
+                    1,
+                    Box::new(Command::Move(Direction::Down, MoveCommandMode::Normal)),
+                ),
+            ),
+            (
+                200,
+                Command::Sequenced(
+                    2,
+                    Box::new(Command::Move(Direction::Up, MoveCommandMode::Normal)),
+                ),
+            ),
+            (1000, Command::None),
+        ],
+    );
+
+    let recording = Recording::parse(&log);
+    assert!(matches!(
+        &recording.steps[1].kind,
+        StepKind::Command(100, Command::Sequenced(1, command))
+            if matches!(**command, Command::Move(Direction::Down, MoveCommandMode::Normal))
+    ));
+    assert_eq!(Replay::run(&recording), Ok(()));
+}
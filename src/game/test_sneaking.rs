@@ -0,0 +1,79 @@
+use super::test_fixture::TestGame;
+use super::*;
+
+// level1.map has a rat at 44,13 in the middle of the north-east room.
+fn rat(subject: &TestGame) -> Character {
+    subject
+        .characters
+        .iter()
+        .find(|c| (c.x, c.y) == (44, 13))
+        .unwrap()
+}
+
+fn noticed(updates: &[GameUpdate], id: u64) -> bool {
+    updates
+        .iter()
+        .any(|update| matches!(update, PlayerNoticed(noticer) if *noticer == id))
+}
+
+#[test]
+fn sneaking_takes_a_step_only_once_its_activity_is_over() {
+    let mut subject = TestGame::load("maps/level1.map");
+    subject.teleport_player(44, 5);
+
+    let updates = subject.sneak(Direction::Down);
+    assert!(updates.iter().any(|update| matches!(
+        update,
+        MovementStarted(1, 44, 6, duration) if *duration as u64 == Player::SNEAKING_STEP
+    )));
+    assert_eq!((subject.player.x, subject.player.y), (44, 5));
+
+    subject.wait(Player::SNEAKING_STEP - 1);
+    assert_eq!((subject.player.x, subject.player.y), (44, 5));
+
+    subject.wait(1);
+    assert_eq!((subject.player.x, subject.player.y), (44, 6));
+    assert_eq!(subject.player.activity, None);
+}
+
+#[test]
+fn sneaking_into_a_wall_turns_to_face_it() {
+    let mut subject = TestGame::load("maps/level1.map");
+    subject.teleport_player(44, 1);
+
+    let updates = subject.sneak(Direction::Up);
+
+    assert_eq!(subject.player.facing, Direction::Up);
+    assert_eq!(subject.player.activity, None);
+    assert!(!updates
+        .iter()
+        .any(|update| matches!(update, MovementStarted(..))));
+}
+
+#[test]
+fn a_creature_notices_the_player_walking_into_view() {
+    let mut subject = TestGame::load("maps/level1.map");
+    let rat = rat(&subject);
+    subject.teleport_player(44, 3);
+
+    assert!(!noticed(&subject.step(Direction::Down), rat.id));
+    assert!(noticed(&subject.step(Direction::Down), rat.id));
+    assert!(subject.characters.get(rat.id).unwrap().aware_of_player);
+
+    // it is only announced once.
+    assert!(!noticed(&subject.step(Direction::Down), rat.id));
+}
+
+#[test]
+fn a_creature_does_not_notice_the_player_sneaking_at_the_same_distance() {
+    let mut subject = TestGame::load("maps/level1.map");
+    let rat = rat(&subject);
+    subject.teleport_player(44, 6);
+
+    subject.sneak(Direction::Down);
+    let updates = subject.wait(Player::SNEAKING_STEP);
+
+    assert_eq!((subject.player.x, subject.player.y), (44, 7));
+    assert!(!noticed(&updates, rat.id));
+    assert!(!subject.characters.get(rat.id).unwrap().aware_of_player);
+}
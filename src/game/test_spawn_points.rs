@@ -0,0 +1,87 @@
+use super::test_fixture::TestGame;
+use super::*;
+
+// level1.map keeps two rats around 44,10, one of them the rat that starts at 44,13.
+fn load() -> TestGame {
+    let mut subject = TestGame::load("maps/level1.map");
+    subject.game_state.schedule_spawn_points(&subject.map);
+    subject
+}
+
+fn rats_near_the_spawn_point(subject: &TestGame) -> Vec<Character> {
+    subject
+        .characters
+        .iter()
+        .filter(|c| {
+            c.character_type == CharacterType::Rat && subject.map.spawn_points[0].covers(c.x, c.y)
+        })
+        .collect()
+}
+
+#[test]
+fn spawn_points_are_read_in_with_the_level() {
+    let subject = load();
+
+    assert_eq!(subject.map.spawn_points.len(), 3);
+    assert_eq!(
+        subject.map.spawn_points[0].spawn,
+        Spawn::Creature(CharacterType::Rat)
+    );
+    assert_eq!(
+        subject.map.spawn_points[2].spawn,
+        Spawn::Item("yellow_potion".into())
+    );
+}
+
+#[test]
+fn creatures_are_respawned_until_the_population_is_restored() {
+    let mut subject = load();
+
+    assert_eq!(rats_near_the_spawn_point(&subject).len(), 1);
+
+    let updates: Vec<GameUpdate> = (0..3).flat_map(|_| subject.wait(30_000)).collect();
+
+    let rats = rats_near_the_spawn_point(&subject);
+    assert_eq!(rats.len(), 2);
+    let newcomer = rats.iter().find(|rat| (rat.x, rat.y) != (44, 13)).unwrap();
+    assert!(subject.obstacles.is_blocked_at(newcomer.x, newcomer.y));
+    assert!(updates.into_iter().any(|update| matches!(
+        update,
+        CharacterEntered {
+            character_type: CharacterType::Rat,
+            id,
+            ..
+        } if id == newcomer.id
+    )));
+
+    let (x, y) = (subject.player.x, subject.player.y);
+    subject.stand_next_to(newcomer.x, newcomer.y);
+    subject.run(Command::KillCharacter(newcomer.id));
+    subject.teleport_player(x, y);
+    assert_eq!(rats_near_the_spawn_point(&subject).len(), 1);
+
+    subject.wait(30_000);
+    assert_eq!(rats_near_the_spawn_point(&subject).len(), 2);
+}
+
+#[test]
+fn nothing_is_spawned_in_sight_of_the_player() {
+    let mut subject = load();
+    subject.teleport_player(44, 8);
+
+    subject.wait(30_000);
+
+    assert_eq!(rats_near_the_spawn_point(&subject).len(), 1);
+}
+
+#[test]
+fn repopulating_an_unknown_spawn_point_is_reported() {
+    let mut subject = load();
+
+    let updates = subject.run_event(GameEvent::Repopulate(9));
+
+    assert!(matches!(
+        &updates[..],
+        [GameUpdate::Message(_, message)] if message == "Spawn point 9 does not exist."
+    ));
+}
@@ -0,0 +1,109 @@
+use super::test_fixture::TestGame;
+use super::*;
+
+// test.map starts the player at 8,7 with a rat at 8,10.
+fn load() -> TestGame {
+    TestGame::load("maps/test.map")
+}
+
+fn describe(updates: &[GameUpdate]) -> Vec<String> {
+    updates.iter().map(|u| format!("{:?}", u)).collect()
+}
+
+#[test]
+fn teleporting_the_player_moves_them_and_their_obstacle() {
+    let mut subject = load();
+    subject
+        .obstacles
+        .block_at(subject.player.x, subject.player.y);
+
+    let updates = subject.run(Command::Teleport(1, 40, 20));
+
+    assert_eq!(describe(&updates), vec!["CharacterTeleported(1, 40, 20)"]);
+    assert_eq!((subject.player.x, subject.player.y), (40, 20));
+    assert!(subject.obstacles.is_blocked_at(40, 20));
+    assert!(!subject.obstacles.is_blocked_at(8, 7));
+}
+
+#[test]
+fn other_characters_can_be_teleported() {
+    let mut subject = load();
+    let rat = subject.character_at(8, 10).id;
+
+    let updates = subject.run(Command::Teleport(rat, 12, 3));
+
+    assert_eq!(
+        describe(&updates),
+        vec![format!("CharacterTeleported({}, 12, 3)", rat)]
+    );
+    let rat = subject.characters.get(rat).unwrap();
+    assert_eq!((rat.x, rat.y), (12, 3));
+    assert!(subject.obstacles.is_blocked_at(12, 3));
+    assert!(!subject.obstacles.is_blocked_at(8, 10));
+    assert_eq!((subject.player.x, subject.player.y), (8, 7));
+}
+
+#[test]
+fn blocked_or_out_of_bounds_destinations_are_refused() {
+    let mut subject = load();
+    let rat = subject.character_at(8, 10).id;
+
+    for (id, x, y, rejection) in vec![
+        (1, 0, 0, "Blocked"),
+        (1, -1, 5, "OutOfBounds"),
+        (rat, 8, 1000, "OutOfBounds"),
+        (rat, 7, 7, "Blocked"),
+    ] {
+        let updates = subject.run(Command::Teleport(id, x, y));
+
+        assert_eq!(
+            describe(&updates),
+            vec![format!("CommandRejected({}({}, {}))", rejection, x, y)]
+        );
+    }
+    assert_eq!((subject.player.x, subject.player.y), (8, 7));
+    let rat = subject.characters.get(rat).unwrap();
+    assert_eq!((rat.x, rat.y), (8, 10));
+}
+
+#[test]
+fn teleporting_the_player_closes_their_external_inventory() {
+    let mut subject = load();
+
+    subject.use_towards(Direction::Left);
+    assert!(subject.player.external_inventory.is_some());
+
+    let updates = subject.run(Command::Teleport(1, 40, 20));
+
+    assert_eq!(
+        describe(&updates),
+        vec!["ExternalInventoryClosed", "CharacterTeleported(1, 40, 20)"]
+    );
+    assert!(subject.player.external_inventory.is_none());
+}
+
+#[test]
+fn only_teleporting_the_player_aborts_their_activity() {
+    let mut subject = load();
+
+    subject.player.endorse_with(":can_pick_apples");
+    subject.teleport_player(9, 8);
+    subject.use_towards(Direction::Down);
+    let activity = subject.player.activity.expect("should be picking apples");
+
+    let rat = subject.character_at(8, 10).id;
+    let updates = subject.run(Command::Teleport(rat, 12, 3));
+    assert_eq!(
+        describe(&updates),
+        vec![format!("CharacterTeleported({}, 12, 3)", rat)]
+    );
+    assert!(subject.game_state.scheduler.is_scheduled(activity));
+
+    let updates = subject.run(Command::Teleport(1, 40, 20));
+    assert_eq!(
+        describe(&updates),
+        vec!["ActivityAborted", "CharacterTeleported(1, 40, 20)"]
+    );
+    assert!(!subject.game_state.scheduler.is_scheduled(activity));
+    assert_eq!(subject.player.activity, None);
+}
@@ -0,0 +1,155 @@
+use std::time::Duration;
+
+/// how often the game loop advances the world.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct TickRate {
+    ticks_per_second: u32,
+}
+
+impl TickRate {
+    pub const DEFAULT_TICKS_PER_SECOND: u32 = 20;
+
+    /// returns a rate of the given number of ticks per second, which must be from 1 to 1000.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::tick::TickRate;
+    /// assert!(TickRate::new(0).is_err());
+    /// assert!(TickRate::new(1001).is_err());
+    /// assert_eq!(TickRate::new(50).unwrap().ticks_per_second(), 50);
+    /// ```
+    pub fn new(ticks_per_second: u32) -> Result<Self, String> {
+        match ticks_per_second {
+            1..=1000 => Ok(Self { ticks_per_second }),
+            _ => Err(format!(
+                "tick rate must be from 1 to 1000 ticks per second: {}",
+                ticks_per_second
+            )),
+        }
+    }
+
+    /// reads a rate given as a number of ticks per second, as on the command line.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::tick::TickRate;
+    /// assert_eq!(TickRate::parse("10"), TickRate::new(10));
+    /// assert!(TickRate::parse("fast").is_err());
+    /// ```
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let ticks_per_second = value
+            .trim()
+            .parse::<u32>()
+            .map_err(|_| format!("unable to parse tick rate: {}", value))?;
+
+        Self::new(ticks_per_second)
+    }
+
+    pub fn ticks_per_second(&self) -> u32 {
+        self.ticks_per_second
+    }
+
+    /// returns the time between the starts of consecutive ticks.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::tick::TickRate;
+    /// # use std::time::Duration;
+    /// assert_eq!(TickRate::default().tick_length(), Duration::from_millis(50));
+    /// ```
+    pub fn tick_length(&self) -> Duration {
+        Duration::from_micros(1_000_000 / self.ticks_per_second as u64)
+    }
+}
+
+impl Default for TickRate {
+    fn default() -> Self {
+        Self {
+            ticks_per_second: Self::DEFAULT_TICKS_PER_SECOND,
+        }
+    }
+}
+
+/// how long the game loop spends working in its ticks, as opposed to waiting for commands.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct TickMetrics {
+    pub ticks: u64,
+    pub total: Duration,
+    pub longest: Duration,
+    pub overruns: u64, // ticks whose work took longer than a tick
+}
+
+impl TickMetrics {
+    /// how often the game loop reports its metrics, in seconds' worth of ticks.
+    pub const REPORT_INTERVAL: u32 = 60;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// counts a tick whose work took duration.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::tick::{TickMetrics, TickRate};
+    /// # use std::time::Duration;
+    /// let rate = TickRate::new(10).unwrap();
+    /// let mut subject = TickMetrics::new();
+    ///
+    /// subject.record(Duration::from_millis(20), &rate);
+    /// subject.record(Duration::from_millis(130), &rate);
+    ///
+    /// assert_eq!(subject.ticks, 2);
+    /// assert_eq!(subject.average(), Duration::from_millis(75));
+    /// assert_eq!(subject.longest, Duration::from_millis(130));
+    /// assert_eq!(subject.overruns, 1);
+    /// ```
+    pub fn record(&mut self, duration: Duration, rate: &TickRate) {
+        self.ticks += 1;
+        self.total += duration;
+        self.longest = self.longest.max(duration);
+
+        if duration > rate.tick_length() {
+            self.overruns += 1;
+        }
+    }
+
+    /// returns whether another report interval's worth of ticks has been counted at the rate.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::tick::{TickMetrics, TickRate};
+    /// # use std::time::Duration;
+    /// let rate = TickRate::new(1).unwrap();
+    /// let mut subject = TickMetrics::new();
+    /// assert!(!subject.is_report_due(&rate));
+    ///
+    /// for _ in 1..TickMetrics::REPORT_INTERVAL {
+    ///     subject.record(Duration::from_millis(5), &rate);
+    /// }
+    /// assert!(!subject.is_report_due(&rate));
+    ///
+    /// subject.record(Duration::from_millis(5), &rate);
+    /// assert!(subject.is_report_due(&rate));
+    /// ```
+    pub fn is_report_due(&self, rate: &TickRate) -> bool {
+        let interval = rate.ticks_per_second() as u64 * Self::REPORT_INTERVAL as u64;
+        self.ticks > 0 && self.ticks.is_multiple_of(interval)
+    }
+
+    /// returns the mean time the work of a tick has taken, or zero if there have been none.
+    pub fn average(&self) -> Duration {
+        match self.ticks {
+            0 => Duration::ZERO,
+            ticks => Duration::from_nanos((self.total.as_nanos() / ticks as u128) as u64),
+        }
+    }
+}
+
+impl std::fmt::Display for TickMetrics {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            formatter,
+            "{} ticks, averaging {:?} and at most {:?}, {} overrun",
+            self.ticks,
+            self.average(),
+            self.longest,
+            self.overruns
+        )
+    }
+}
@@ -2,27 +2,94 @@ use super::*;
 
 use regex::Regex;
 use std::fmt;
+use std::fmt::Write;
 use std::fs;
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+/// what lies at a location of the map: terrain of a type defined in the tile data file,
+///   or a facility.
+#[derive(Copy, Clone, PartialEq, Eq)]
 pub enum Tile {
-    Empty,
-    StoneWall,
-    DirtFloor,
-    ClosedDoor,
-    OpenDoor,
-    Facility(u64),
+    Terrain(TileType),
+    Facility(u64), // (facility_id)
 }
 
+#[allow(non_upper_case_globals)]
 impl Tile {
-    pub fn to_str(tile: Tile) -> &'static str {
-        match tile {
-            Tile::Empty => " ",
-            Tile::StoneWall => "#",
-            Tile::DirtFloor => ".",
-            Tile::ClosedDoor => "|",
-            Tile::OpenDoor => "/",
-            Tile::Facility(_) => "\u{ff}",
+    pub const Empty: Tile = Tile::Terrain(TileType::Empty);
+    pub const StoneWall: Tile = Tile::Terrain(TileType::StoneWall);
+    pub const DirtFloor: Tile = Tile::Terrain(TileType::DirtFloor);
+    pub const ClosedDoor: Tile = Tile::Terrain(TileType::ClosedDoor);
+    pub const OpenDoor: Tile = Tile::Terrain(TileType::OpenDoor);
+}
+
+impl Tile {
+    pub fn definition(&self) -> &'static TileDefinition {
+        match self {
+            Tile::Terrain(tile_type) => tile_type.definition(),
+            Tile::Facility(_) => TileType::Facility.definition(),
+        }
+    }
+
+    /// returns the character that marks the tile in map files.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::tile_map::Tile;
+    /// assert_eq!(Tile::StoneWall.symbol(), '#');
+    /// assert_eq!(Tile::Facility(4).symbol(), '\u{ff}');
+    /// ```
+    pub fn symbol(&self) -> char {
+        match self {
+            Tile::Facility(_) => '\u{ff}',
+            _ => self.definition().symbol.unwrap_or('?'),
+        }
+    }
+
+    /// returns true if characters can walk onto the tile.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::tile_map::Tile;
+    /// assert!(Tile::OpenDoor.is_passable());
+    /// assert!(!Tile::ClosedDoor.is_passable());
+    /// assert!(!Tile::Facility(4).is_passable());
+    /// ```
+    pub fn is_passable(&self) -> bool {
+        self.definition().passable
+    }
+
+    /// returns true if nothing can be seen through the tile.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::tile_map::Tile;
+    /// assert!(Tile::StoneWall.blocks_sight());
+    /// assert!(!Tile::OpenDoor.blocks_sight());
+    /// ```
+    pub fn blocks_sight(&self) -> bool {
+        self.definition().blocks_sight
+    }
+
+    /// returns true if the player can use the tile, as a door is opened or closed.
+    pub fn is_usable(&self) -> bool {
+        self.definition().usable
+    }
+
+    /// returns how long stepping onto the tile takes, as a percentage of a step onto floor.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::tile_map::Tile;
+    /// # use muframework::game::tile_type::TileType;
+    /// assert_eq!(Tile::DirtFloor.movement_cost(), 100);
+    /// assert!(Tile::Terrain(TileType::parse("rubble").unwrap()).movement_cost() > 100);
+    /// ```
+    pub fn movement_cost(&self) -> u32 {
+        self.definition().movement_cost
+    }
+}
+
+impl fmt::Debug for Tile {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Tile::Terrain(tile_type) => write!(formatter, "{:?}", tile_type),
+            Tile::Facility(id) => write!(formatter, "Facility({})", id),
         }
     }
 }
@@ -32,6 +99,9 @@ pub struct TileMap {
     pub map_width: usize,
     pub map_height: usize,
     map: Vec<Tile>,
+    pub spawn_points: Vec<SpawnPoint>,
+    pub doors: DoorList,
+    chunk_versions: Vec<u64>, // by chunk, in reading order
 }
 
 impl fmt::Debug for TileMap {
@@ -39,9 +109,9 @@ impl fmt::Debug for TileMap {
         let mut index: usize = 0;
         for _y in 0..self.map_height {
             for _x in 0..self.map_width {
-                let tile_char = Tile::to_str(self.map[index]);
+                let tile_char = self.map[index].symbol();
                 index += 1;
-                formatter.write_str(tile_char).unwrap();
+                formatter.write_char(tile_char).unwrap();
             }
             formatter.write_str("\n").unwrap();
         }
@@ -64,12 +134,24 @@ impl TileMap {
             map_width: 0,
             map_height: 0,
             map: vec![],
+            spawn_points: vec![],
+            doors: DoorList::new(),
+            chunk_versions: vec![],
         }
     }
 
     /// loads a level from a file.
     /// # Arguments:
     /// * `filename` - A string slice that
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::tile_map::{Tile,TileMap};
+    /// # use muframework::game::tile_type::TileType;
+    /// let (subject, ..) = TileMap::load_from_file("maps/level1.map");
+    /// assert_eq!(subject.at(0, 0), Tile::StoneWall);
+    /// assert_eq!(subject.at(15, 31), Tile::Terrain(TileType::parse("water").unwrap()));
+    /// assert!(!subject.at(15, 31).is_passable());
+    /// ```
     pub fn load_from_file<S: ToString>(
         filename: S,
     ) -> (
@@ -79,11 +161,14 @@ impl TileMap {
         Vec<String>,
         Vec<String>,
         Vec<String>,
+        Vec<String>,
+        Vec<String>,
+        Vec<String>,
     ) {
         let contents = fs::read_to_string(filename.to_string()).expect("unable to read level file");
 
         let re = Regex::new(
-            r"(?s)(.+)===END OF MAP===\n(.+)===END OF CHARACTERS===\n(.*)===END OF ITEM TYPES===\n(.*)===END OF ITEMS===\n(.*)===END OF FACILITIES===\n(.*)===END OF STORED ITEMS===",
+            r"(?s)(.+)===END OF MAP===\n(.+)===END OF CHARACTERS===\n(.*)===END OF ITEM TYPES===\n(.*)===END OF ITEMS===\n(.*)===END OF FACILITIES===\n(.*)===END OF STORED ITEMS===(?:\n(.*)===END OF LOOT TABLES===)?(?:\n(.*)===END OF SPAWN POINTS===)?(?:\n(.*)===END OF DOORS===)?",
         )
         .expect("unable to initialize regex");
 
@@ -98,6 +183,19 @@ impl TileMap {
         let items = capture_section(&captures, 4);
         let facilities: Vec<String> = capture_section(&captures, 5);
         let stored_items = capture_section(&captures, 6);
+        // loot tables are optional; older levels end with their stored items.
+        let loot_tables = match captures.get(7) {
+            Some(_) => capture_section(&captures, 7),
+            None => vec![],
+        };
+        let spawn_points = match captures.get(8) {
+            Some(_) => capture_section(&captures, 8),
+            None => vec![],
+        };
+        let doors = match captures.get(9) {
+            Some(_) => capture_section(&captures, 9),
+            None => vec![],
+        };
 
         (
             Self::load_map_from_vector(&map_rows, map_width, map_height),
@@ -106,6 +204,9 @@ impl TileMap {
             items,
             facilities,
             stored_items,
+            loot_tables,
+            spawn_points,
+            doors,
         )
     }
 
@@ -170,6 +271,91 @@ impl TileMap {
         }
         let index = self.xy_to_index(x, y);
         self.map[index] = tile;
+
+        let chunk = self.chunk_index(MapChunk::containing(x, y));
+        self.chunk_versions[chunk] += 1;
+    }
+
+    /// returns how many chunks across and down the map is divided into.
+    pub fn chunk_dimensions(&self) -> (i32, i32) {
+        let size = MapChunk::SIZE as usize;
+
+        (
+            self.map_width.div_ceil(size) as i32,
+            self.map_height.div_ceil(size) as i32,
+        )
+    }
+
+    fn chunk_index(&self, (chunk_x, chunk_y): (i32, i32)) -> usize {
+        (chunk_y * self.chunk_dimensions().0 + chunk_x) as usize
+    }
+
+    fn reset_chunk_versions(&mut self) {
+        let (across, down) = self.chunk_dimensions();
+        self.chunk_versions = vec![0; (across * down) as usize];
+    }
+
+    /// returns the version of the chunk holding x,y, which goes up with every change to its tiles.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::tile_map::{Tile,TileMap};
+    /// let mut subject = TileMap::new();
+    /// subject.set_dimensions(40, 20, Tile::DirtFloor);
+    /// assert_eq!(subject.chunk_version_at(3, 3), 0);
+    ///
+    /// subject.set_tile_at(3, 3, Tile::StoneWall);
+    ///
+    /// assert_eq!(subject.chunk_version_at(15, 15), 1);
+    /// assert_eq!(subject.chunk_version_at(16, 15), 0);
+    /// ```
+    pub fn chunk_version_at(&self, x: i32, y: i32) -> u64 {
+        if self.out_of_bounds(x, y) {
+            return 0;
+        }
+        self.chunk_versions[self.chunk_index(MapChunk::containing(x, y))]
+    }
+
+    /// returns the chunk at the given chunk coordinates, or None if it lies off the map.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::tile_map::{Tile,TileMap};
+    /// let mut subject = TileMap::new();
+    /// subject.set_dimensions(40, 20, Tile::DirtFloor);
+    /// subject.set_tile_at(33, 17, Tile::StoneWall);
+    ///
+    /// let chunk = subject.chunk(2, 1).unwrap();
+    ///
+    /// assert_eq!((chunk.width, chunk.height), (8, 4));
+    /// assert_eq!(chunk.version, 1);
+    /// assert_eq!(chunk.tiles[1 * 8 + 1], Tile::StoneWall);
+    /// assert!(subject.chunk(3, 0).is_none());
+    /// ```
+    pub fn chunk(&self, chunk_x: i32, chunk_y: i32) -> Option<MapChunk> {
+        let (across, down) = self.chunk_dimensions();
+        if chunk_x < 0 || chunk_y < 0 || chunk_x >= across || chunk_y >= down {
+            return None;
+        }
+
+        let left = chunk_x * MapChunk::SIZE;
+        let top = chunk_y * MapChunk::SIZE;
+        let width = MapChunk::SIZE.min(self.map_width as i32 - left);
+        let height = MapChunk::SIZE.min(self.map_height as i32 - top);
+
+        let mut tiles = Vec::with_capacity((width * height) as usize);
+        for y in top..top + height {
+            for x in left..left + width {
+                tiles.push(self.at(x, y));
+            }
+        }
+
+        Some(MapChunk {
+            x: chunk_x,
+            y: chunk_y,
+            version: self.chunk_versions[self.chunk_index((chunk_x, chunk_y))],
+            width: width as usize,
+            height: height as usize,
+            tiles,
+        })
     }
 
     /// converts x,y to a direct single-dimension index into the map.
@@ -204,19 +390,19 @@ impl TileMap {
             map_width: width as usize,
             map_height: height as usize,
             map: vec![Tile::Empty; width * height],
+            spawn_points: vec![],
+            doors: DoorList::new(),
+            chunk_versions: vec![],
         };
+        map.reset_chunk_versions();
 
         let mut index: usize = 0;
 
         for row in rows {
             for character in row.chars() {
-                let tile = match character {
-                    '#' => Tile::StoneWall,
-                    '.' => Tile::DirtFloor,
-                    ' ' => Tile::Empty,
-                    '|' => Tile::ClosedDoor,
-                    '/' => Tile::OpenDoor,
-                    char => panic!("unrecognized character: {}", char),
+                let tile = match TileType::from_symbol(character) {
+                    Some(tile_type) => Tile::Terrain(tile_type),
+                    None => panic!("unrecognized character: {}", character),
                 };
                 map.map[index] = tile;
                 index += 1;
@@ -269,6 +455,44 @@ impl TileMap {
         }
     }
 
+    /// returns true if nothing that blocks sight lies on the line between the two locations.
+    ///   The locations themselves may block sight; a wall can be seen.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::tile_map::{Tile,TileMap};
+    /// let mut subject = TileMap::new();
+    /// subject.set_dimensions(10,10, Tile::DirtFloor);
+    /// subject.set_tile_at(5,5, Tile::StoneWall);
+    /// assert!(subject.has_line_of_sight(2,5, 4,5));
+    /// assert!(subject.has_line_of_sight(2,5, 5,5));
+    /// assert!(!subject.has_line_of_sight(2,5, 8,5));
+    /// assert!(subject.has_line_of_sight(2,4, 8,4));
+    /// ```
+    pub fn has_line_of_sight(&self, from_x: i32, from_y: i32, to_x: i32, to_y: i32) -> bool {
+        let (dx, dy) = ((to_x - from_x).abs(), -(to_y - from_y).abs());
+        let (step_x, step_y) = ((to_x - from_x).signum(), (to_y - from_y).signum());
+        let (mut x, mut y) = (from_x, from_y);
+        let mut error = dx + dy;
+
+        loop {
+            if x == to_x && y == to_y {
+                return true;
+            }
+            if (x, y) != (from_x, from_y) && self.at(x, y).blocks_sight() {
+                return false;
+            }
+            let doubled = 2 * error;
+            if doubled >= dy {
+                error += dy;
+                x += step_x;
+            }
+            if doubled <= dx {
+                error += dx;
+                y += step_y;
+            }
+        }
+    }
+
     /// Expands the dimensions of the map.  Will not reduce the size.
     /// Currently used only for testing
     /// # Arguments:
@@ -292,6 +516,7 @@ impl TileMap {
         self.map_width = width as usize;
         self.map_height = height as usize;
         self.map = vec![default; (width * height) as usize];
+        self.reset_chunk_versions();
 
         let old_index = 0;
         for y in 0..old_height {
@@ -299,5 +524,6 @@ impl TileMap {
                 self.set_tile_at(x as i32, y as i32, old_map[old_index]);
             }
         }
+        self.reset_chunk_versions();
     }
 }
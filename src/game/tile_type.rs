@@ -0,0 +1,237 @@
+use super::*;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::fmt;
+
+/// the kind of a map tile, named in the tile data file.
+///   The types the code refers to by name are provided as constants.
+#[derive(Hash, Eq, PartialEq, Copy, Clone, Ord, PartialOrd)]
+pub struct TileType(&'static str);
+
+#[allow(non_upper_case_globals)]
+impl TileType {
+    pub const Empty: TileType = TileType("empty");
+    pub const StoneWall: TileType = TileType("stone_wall");
+    pub const DirtFloor: TileType = TileType("dirt_floor");
+    pub const ClosedDoor: TileType = TileType("closed_door");
+    pub const OpenDoor: TileType = TileType("open_door");
+    pub const Facility: TileType = TileType("facility"); // how every facility's tile looks
+}
+
+impl TileType {
+    /// returns every type defined in the data file, in the order defined.
+    pub fn all() -> Vec<TileType> {
+        TILE_TYPES
+            .definitions
+            .iter()
+            .map(|definition| TileType(&definition.name))
+            .collect()
+    }
+
+    /// returns the type with the given name, ignoring case, or None if it is not defined.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::tile_type::TileType;
+    /// assert_eq!(TileType::parse("Stone_Wall"), Some(TileType::StoneWall));
+    /// assert_eq!(TileType::parse("water").map(|t| t.name()), Some("water"));
+    /// assert_eq!(TileType::parse("lava"), None);
+    /// ```
+    pub fn parse<S: ToString>(name: S) -> Option<TileType> {
+        let name = name.to_string().to_lowercase();
+
+        TILE_TYPES
+            .find(|definition| definition.name == name)
+            .map(|definition| TileType(&definition.name))
+    }
+
+    /// returns the type marked by the symbol in map files, if any.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::tile_type::TileType;
+    /// assert_eq!(TileType::from_symbol('#'), Some(TileType::StoneWall));
+    /// assert_eq!(TileType::from_symbol(' '), Some(TileType::Empty));
+    /// assert_eq!(TileType::from_symbol('\u{263a}'), None);
+    /// ```
+    pub fn from_symbol(symbol: char) -> Option<TileType> {
+        TILE_TYPES
+            .find(|definition| definition.symbol == Some(symbol))
+            .map(|definition| TileType(&definition.name))
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.0
+    }
+
+    pub fn definition(&self) -> &'static TileDefinition {
+        TILE_TYPES
+            .find(|definition| definition.name == self.0)
+            .unwrap_or(&UNDEFINED_TILE)
+    }
+}
+
+impl fmt::Debug for TileType {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}", self.0)
+    }
+}
+
+/// what the tile data file says about a type of tile.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TileDefinition {
+    pub name: String,
+    pub symbol: Option<char>,
+    pub glyph: char,
+    pub fg: String, // as #RRGGBB
+    pub bg: String, // as #RRGGBB
+    pub passable: bool,
+    pub blocks_sight: bool,
+    pub usable: bool,
+    pub movement_cost: u32, // percent of the time a step onto floor takes
+}
+
+impl TileDefinition {
+    pub fn new<S: ToString>(name: S) -> Self {
+        Self {
+            name: name.to_string(),
+            symbol: None,
+            glyph: '?',
+            fg: "#FFFFFF".into(),
+            bg: "#000000".into(),
+            passable: true,
+            blocks_sight: false,
+            usable: false,
+            movement_cost: 100,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TileTypeList {
+    definitions: Vec<TileDefinition>,
+}
+
+impl TileTypeList {
+    /// the tile data file, built into the game so that it runs from any directory.
+    pub const DATA: &'static str = include_str!("../../data/tiles.txt");
+
+    /// returns the tile types of the tile data file.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::tile_type::*;
+    /// let subject = TileTypeList::built_in();
+    /// assert!(!subject.get("stone_wall").unwrap().passable);
+    /// ```
+    pub fn built_in() -> TileTypeList {
+        Self::read_in_tile_types(Self::DATA).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// reads tile definitions in the form used by the tile data file.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::tile_type::*;
+    /// let subject = TileTypeList::read_in_tile_types(
+    ///     "lava {\n  symbol: ~\n  fg: #FF4500\n  passable: false\n}\nvoid {\n  symbol: ' '\n}",
+    /// )
+    /// .unwrap();
+    ///
+    /// let lava = subject.get("lava").unwrap();
+    /// assert_eq!(lava.glyph, '~');
+    /// assert!(!lava.passable);
+    /// assert!(!lava.blocks_sight);
+    /// assert_eq!(lava.movement_cost, 100);
+    /// assert_eq!(subject.get("void").unwrap().symbol, Some(' '));
+    /// assert!(TileTypeList::read_in_tile_types("lava {\n  hot: true\n}").is_err());
+    /// ```
+    pub fn read_in_tile_types(contents: &str) -> Result<TileTypeList, String> {
+        let mut result = TileTypeList::default();
+
+        let re = Regex::new(r#"(?m)^(\w+)[ \t]*(?:\{([^}]*)\})?[ \t]*(?://.*)?$"#).unwrap();
+
+        for captures in re.captures_iter(contents) {
+            let name = capture_string(&captures, 1).to_lowercase();
+            let attributes = capture_optional_string(&captures, 2);
+
+            let mut definition = TileDefinition::new(name);
+            Self::read_in_tile_attributes_for(&mut definition, attributes)?;
+            result.definitions.push(definition);
+        }
+
+        Ok(result)
+    }
+
+    fn read_in_tile_attributes_for(
+        definition: &mut TileDefinition,
+        attributes: &str,
+    ) -> Result<(), String> {
+        let re = Regex::new(r#"^(\w+):\s+(.+?)(?:\s+//.*)?$"#).unwrap();
+        let mut glyph = None;
+
+        for attribute in attributes
+            .lines()
+            .map(|a| a.trim())
+            .filter(|a| !a.is_empty())
+        {
+            let captures = re
+                .captures(attribute)
+                .ok_or_else(|| format!("unable to parse attribute: {}", attribute))?;
+
+            let attribute_name = capture_string(&captures, 1);
+            let attribute_value = capture_string(&captures, 2);
+            // a character may be quoted, so that a space can be given.
+            let character = || {
+                let unquoted = match attribute_value.chars().count() {
+                    3 if attribute_value.starts_with('\'') && attribute_value.ends_with('\'') => {
+                        &attribute_value[1..attribute_value.len() - 1]
+                    }
+                    _ => attribute_value,
+                };
+                unquoted
+                    .chars()
+                    .next()
+                    .filter(|_| unquoted.chars().count() == 1)
+                    .ok_or_else(|| format!("expected a single character: {}", attribute))
+            };
+            let flag = || {
+                attribute_value
+                    .parse::<bool>()
+                    .map_err(|_| format!("expected true or false: {}", attribute))
+            };
+
+            match attribute_name {
+                "symbol" => definition.symbol = Some(character()?),
+                "glyph" => glyph = Some(character()?),
+                "fg" => definition.fg = attribute_value.to_string(),
+                "bg" => definition.bg = attribute_value.to_string(),
+                "passable" => definition.passable = flag()?,
+                "blocks_sight" => definition.blocks_sight = flag()?,
+                "usable" => definition.usable = flag()?,
+                "movement_cost" => {
+                    definition.movement_cost = attribute_value
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|cost| *cost > 0)
+                        .ok_or_else(|| format!("expected a positive number: {}", attribute))?
+                }
+                _ => return Err(format!("unrecognized attribute: {}", attribute_name)),
+            }
+        }
+
+        definition.glyph = glyph.or(definition.symbol).unwrap_or('?');
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&TileDefinition> {
+        self.find(|definition| definition.name == name)
+    }
+
+    fn find<P: Fn(&TileDefinition) -> bool>(&self, predicate: P) -> Option<&TileDefinition> {
+        self.definitions
+            .iter()
+            .find(|definition| predicate(definition))
+    }
+}
+
+lazy_static! {
+    static ref TILE_TYPES: TileTypeList = TileTypeList::built_in();
+    static ref UNDEFINED_TILE: TileDefinition = TileDefinition::new("undefined");
+}
@@ -38,22 +38,28 @@ pub enum MapLayer {
     Player,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Command {
     None,
     QuitGame,
     Move(Direction, MoveCommandMode),
     Teleport(u64, i32, i32),
+    KillCharacter(u64),
     SpawnItem(u64, ItemClass, String),
     TakeItem(u64),
-    DropItem(u64),
+    DropItem(u64, u8), // (item_id, quantity)
     EquipItem(u64),
     UnequipItem(u64),
-    TransferItem(u64, u64, u64), // (item_id, src_inventory_id, dest_inventory_id)
-    TransferAllItems(u64, u64),  // (src_inventory_id, dest_inventory_id )
+    TransferItem(u64, u64, u64, u8), // (item_id, src_inventory_id, dest_inventory_id, quantity)
+    TransferAllItems(u64, u64),      // (src_inventory_id, dest_inventory_id )
+    SplitStack(u64, u8),             // (item_id, quantity)
+    MergeStacks(u64, u64),           // (item_id, into_item_id)
+    OpenContainer(u64),              // (item_id)
     CloseExternalInventory,
     RefreshInventory,
     AbortActivity,
+    ToggleBumpToInteract,
+    Sequenced(u64, Box<Command>), // (sequence, command) numbered by a client that predicts it
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -63,11 +69,23 @@ pub enum MoveCommandMode {
     Use,
 }
 
+/// what a message is about, which decides how it is shown.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum MessageCategory {
+    System,
+    Combat,
+    Loot,
+    Chat,
+    Error,
+}
+
 #[derive(Debug, Clone)]
 pub enum GameUpdate {
-    Message(String),
-    SetBackground(TileMap),
-    TileChangedAt(i32, i32, game::tile_map::Tile),
+    Message(MessageCategory, String),
+    SetBackground(TileMap), // the whole level, which clients are sent in chunks
+    MapDimensions(usize, usize), // (width, height) of the level a client is sent chunks of
+    ChunkLoaded(game::MapChunk),
+    TileChangedAt(i32, i32, game::tile_map::Tile, u64), // (x, y, tile, chunk_version)
     CharacterEntered {
         id: u64,
         x: i32,
@@ -76,6 +94,11 @@ pub enum GameUpdate {
     },
     CharacterMoved(u64, i32, i32),      // Normal move to a new position
     CharacterTeleported(u64, i32, i32), // Instantaneous move to a new position
+    MovementStarted(u64, i32, i32, u32), // (character_id, x, y, duration) toward x,y
+    MovementCompleted(u64),             // (character_id)
+    MovementAborted(u64),               // (character_id) the character stays where it was
+    CharacterExited(u64),
+    PlayerNoticed(u64), // (character_id)
     CharacterFacingChanged(u64, Direction),
     ItemAdded {
         id: u64,
@@ -92,6 +115,7 @@ pub enum GameUpdate {
         description: String,
         class: FacilityClass,
     },
+    FacilityRemoved(u64),
     FacilityUpdated {
         id: u64,
         description: String,
@@ -105,6 +129,9 @@ pub enum GameUpdate {
     ActivityStarted(u32),
     ActivityExpired(),
     ActivityAborted(),
+    SkillLevelIncreased(game::skills::Skill, u8),
+    CommandRejected(game::command::Rejection),
+    CommandProcessed(u64), // (sequence) the numbered command is over, whatever came of it
     Exit,
 }
 impl GameUpdate {
@@ -8,14 +8,53 @@ use std::thread;
 use ui::window::BasicWindow;
 use ui::UIState;
 
+/// returns the value following the given flag on the command line, if any.
+fn argument_value(flag: &str) -> Option<String> {
+    let args: Vec<String> = std::env::args().collect();
+    let position = args.iter().position(|a| a == flag)?;
+    args.get(position + 1).cloned()
+}
+
 fn main() -> BError {
+    if let Some(replay_path) = argument_value("--replay") {
+        let recording = game::Recording::read_from_file(&replay_path);
+        match game::Replay::run(&recording) {
+            Ok(()) => println!("replay matches recording: {}", replay_path),
+            Err(mismatch) => {
+                println!("replay diverged at step {}", mismatch.step);
+                println!("expected: {:#?}", mismatch.expected);
+                println!("actual: {:#?}", mismatch.actual);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
     let (update_tx, update_rx) = mpsc::channel();
     let (command_tx, command_rx) = mpsc::channel();
 
     let cloned_command_tx = command_tx.clone();
 
-    let _game_handle =
-        thread::spawn(move || game::GameState::game_loop(update_tx, command_rx, cloned_command_tx));
+    let tick_rate = match argument_value("--tick-rate") {
+        Some(value) => game::TickRate::parse(&value).unwrap_or_else(|error| {
+            println!("{}", error);
+            std::process::exit(1);
+        }),
+        None => game::TickRate::default(),
+    };
+
+    let recorder = argument_value("--record")
+        .map(|path| game::Recorder::create(path, game::GameState::LEVEL_PATH));
+
+    let _game_handle = thread::spawn(move || {
+        game::GameState::game_loop(
+            update_tx,
+            command_rx,
+            cloned_command_tx,
+            tick_rate,
+            recorder,
+        )
+    });
 
     let width = 84;
     let height = 60;
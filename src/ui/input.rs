@@ -1,5 +1,5 @@
 use super::*;
-use window::InventoryWindowMode;
+use window::{InventoryWindowMode, QuantityAction, QuantityPrompt};
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum InputState {
@@ -7,6 +7,7 @@ pub enum InputState {
     PickupSelection,
     ExternalInventoryOpen,
     Activity,
+    QuantityEntry,
 }
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
@@ -39,6 +40,7 @@ impl UIState {
         let mut input = Input::new(context);
 
         self.process_mouse_input(context);
+        self.update_tooltip(context);
         Self::set_modifier_keys(&mut input);
         self.process_keyboard_input(&input);
     }
@@ -54,9 +56,32 @@ impl UIState {
         }
     }
 
+    /// shows a tooltip for whatever the mouse is over, if anything.
+    fn update_tooltip(&mut self, context: &mut BTerm) {
+        self.tooltip = self.tooltip_at(context.mouse_point());
+
+        let over_map = self.tooltip.as_ref().is_some_and(|tooltip| {
+            self.map_window
+                .rect()
+                .point_in_rect(Point::new(tooltip.x, tooltip.y))
+        });
+        self.map_window.window_mode = match (self.map_window.window_mode, over_map) {
+            (MapWindowMode::Normal, true) => MapWindowMode::ToolTip,
+            (MapWindowMode::ToolTip, false) => MapWindowMode::Normal,
+            (mode, _) => mode,
+        };
+    }
+
     fn process_left_click(&mut self, context: &mut BTerm) {
         let mouse_pos = context.mouse_point();
 
+        // this version of bracket-lib reports no mouse wheel, so clicking the upper or lower
+        //   half of the messages window scrolls it instead.
+        if self.message_window.rect().point_in_rect(mouse_pos) {
+            let mouse_point = self.message_window.mouse_point(context);
+            self.scroll_messages_towards(mouse_point.y);
+        }
+
         let mut windows: [Box<&mut dyn MouseReceiver>; 4] = [
             Box::new(&mut self.inventory_window),
             Box::new(&mut self.map_window),
@@ -74,8 +99,25 @@ impl UIState {
         }
     }
 
+    /// scrolls the messages back a few lines for a point in the upper half of the window,
+    ///   and forward for one in the lower half.
+    /// public for testing purposes.
+    pub fn scroll_messages_towards(&mut self, y: i32) {
+        const LINES: usize = 3;
+
+        if y < self.message_window.height() / 2 {
+            self.message_log.scroll_back(LINES);
+        } else {
+            self.message_log.scroll_forward(LINES);
+        }
+    }
+
     fn process_keyboard_input(&mut self, input: &Input) {
         let command = self.get_command_from_keyboard_input(input);
+        let command = self.prediction.predict(command);
+        if let Command::Sequenced(_, _) = command {
+            self.show_predicted_player();
+        }
         &self.command_tx.send(command);
     }
 
@@ -88,6 +130,7 @@ impl UIState {
                 self.process_external_inventory_selection_keyboard_input(input)
             }
             InputState::Activity => self.process_activity_keyboard_input(input),
+            InputState::QuantityEntry => self.process_quantity_entry_keyboard_input(input),
         }
     }
 
@@ -127,6 +170,19 @@ impl UIState {
                     Command::None
                 }
                 VirtualKeyCode::D => self.handle_d(input),
+                VirtualKeyCode::B => Command::ToggleBumpToInteract,
+                VirtualKeyCode::PageUp => {
+                    self.message_log.scroll_back(self.message_area().1);
+                    Command::None
+                }
+                VirtualKeyCode::PageDown => {
+                    self.message_log.scroll_forward(self.message_area().1);
+                    Command::None
+                }
+                VirtualKeyCode::End => {
+                    self.message_log.scroll_to_latest();
+                    Command::None
+                }
                 VirtualKeyCode::T => {
                     self.input_state = InputState::PickupSelection;
                     Command::None
@@ -170,6 +226,37 @@ impl UIState {
                         Command::None
                     }
                 }
+                VirtualKeyCode::O => {
+                    let selection = match self.inventory_window.window_mode {
+                        InventoryWindowMode::Inventory => &self.inventory,
+                        InventoryWindowMode::Equipment => &self.equipment,
+                    };
+                    match self.inventory_window.get_selected_item_id(selection) {
+                        Some(item_id) => Command::OpenContainer(item_id),
+                        None => Command::None,
+                    }
+                }
+                VirtualKeyCode::X => match self.selected_inventory_item() {
+                    Some(item) if item.quantity > 1 => {
+                        self.prompt_for_quantity(QuantityAction::Split, &item, item.quantity - 1)
+                    }
+                    _ => Command::None,
+                },
+                VirtualKeyCode::M => match self.selected_inventory_item() {
+                    Some(item) => {
+                        // merge into the first other stack of the same type.
+                        let into = self
+                            .inventory
+                            .iter()
+                            .find(|other| other.id != item.id && other.is_same_type_as(&item));
+
+                        match into {
+                            Some(into) => Command::MergeStacks(item.id, into.id),
+                            None => Command::None,
+                        }
+                    }
+                    None => Command::None,
+                },
 
                 _ => Command::None,
             },
@@ -181,16 +268,15 @@ impl UIState {
             self.map_window.scroll_by(-1, 0);
             Command::None
         } else {
-            let mut result = Command::None;
             let window = &self.inventory_window;
             if window.window_mode == InventoryWindowMode::Inventory {
-                if window.selected_item != None {
-                    let item_id = self.inventory_window.get_selected_item_id(&self.inventory);
-                    if let Some(item_id) = item_id {
-                        result = Command::DropItem(item_id)
+                match self.selected_inventory_item() {
+                    Some(item) if item.quantity > 1 => {
+                        self.prompt_for_quantity(QuantityAction::Drop, &item, item.quantity)
                     }
+                    Some(item) => Command::DropItem(item.id, 1),
+                    None => Command::None,
                 }
-                result
             } else {
                 println!("d pressed in equipment pane");
                 Command::None
@@ -224,20 +310,22 @@ impl UIState {
             Some(VirtualKeyCode::T) => {
                 if let Some(selection) = self.map_window.active_pane().unwrap().selection {
                     let item = if let Some(ref external_inventory) = self.external_inventory {
-                        &external_inventory[(selection - 1) as usize]
+                        external_inventory[(selection - 1) as usize].clone()
                     } else {
                         panic!("inventory not found");
                     };
 
-                    Command::TransferItem(item.id, inventory_id, self.player.inventory_id)
+                    let action = QuantityAction::Transfer(inventory_id, self.player.inventory_id);
+                    self.transfer_or_prompt(action, &item)
                 } else {
                     Command::None
                 }
             }
             Some(VirtualKeyCode::D) => {
                 if let Some(selection) = self.inventory_window.selected_item {
-                    let item = &self.inventory[(selection - 1) as usize];
-                    Command::TransferItem(item.id, self.player.inventory_id, inventory_id)
+                    let item = self.inventory[(selection - 1) as usize].clone();
+                    let action = QuantityAction::Transfer(self.player.inventory_id, inventory_id);
+                    self.transfer_or_prompt(action, &item)
                 } else {
                     Command::None
                 }
@@ -255,6 +343,69 @@ impl UIState {
         command
     }
 
+    /// transfers a single item at once, but asks how many of a stack to transfer.
+    fn transfer_or_prompt(&mut self, action: QuantityAction, item: &Item) -> Command {
+        match action {
+            QuantityAction::Transfer(source_id, destination_id) if item.quantity == 1 => {
+                Command::TransferItem(item.id, source_id, destination_id, 1)
+            }
+            _ => self.prompt_for_quantity(action, item, item.quantity),
+        }
+    }
+
+    fn selected_inventory_item(&self) -> Option<Item> {
+        if self.inventory_window.window_mode != InventoryWindowMode::Inventory {
+            return None;
+        }
+        let item_id = self
+            .inventory_window
+            .get_selected_item_id(&self.inventory)?;
+        self.inventory
+            .iter()
+            .find(|item| item.id == item_id)
+            .cloned()
+    }
+
+    fn prompt_for_quantity(&mut self, action: QuantityAction, item: &Item, max: u8) -> Command {
+        self.inventory_window.quantity_prompt =
+            Some(QuantityPrompt::new(action, item.id, max, self.input_state));
+        self.input_state = InputState::QuantityEntry;
+        Command::None
+    }
+
+    fn process_quantity_entry_keyboard_input(&mut self, input: &Input) -> Command {
+        let prompt = match self.inventory_window.quantity_prompt {
+            Some(ref mut prompt) => prompt,
+            None => {
+                self.input_state = InputState::Normal;
+                return Command::None;
+            }
+        };
+
+        match input.key {
+            Some(key) if digit_from_key(key).is_some() => {
+                prompt.push_digit(digit_from_key(key).unwrap());
+                Command::None
+            }
+            Some(VirtualKeyCode::Back) => {
+                prompt.pop_digit();
+                Command::None
+            }
+            Some(VirtualKeyCode::Return) => {
+                let command = prompt.command();
+                self.input_state = prompt.return_state;
+                self.inventory_window.quantity_prompt = None;
+                command
+            }
+            Some(VirtualKeyCode::Escape) => {
+                self.input_state = prompt.return_state;
+                self.inventory_window.quantity_prompt = None;
+                Command::None
+            }
+            _ => Command::None,
+        }
+    }
+
     fn process_activity_keyboard_input(&mut self, input: &Input) -> Command {
         let command: Command = match input.key {
             Some(VirtualKeyCode::Escape) => Command::AbortActivity,
@@ -299,6 +450,25 @@ impl UIState {
     }
 }
 
+fn digit_from_key(key: VirtualKeyCode) -> Option<u8> {
+    use VirtualKeyCode::*;
+
+    let digit = match key {
+        Key0 | Numpad0 => 0,
+        Key1 | Numpad1 => 1,
+        Key2 | Numpad2 => 2,
+        Key3 | Numpad3 => 3,
+        Key4 | Numpad4 => 4,
+        Key5 | Numpad5 => 5,
+        Key6 | Numpad6 => 6,
+        Key7 | Numpad7 => 7,
+        Key8 | Numpad8 => 8,
+        Key9 | Numpad9 => 9,
+        _ => return None,
+    };
+    Some(digit)
+}
+
 #[cfg(test)]
 mod normal_input_mode {
     use super::*;
@@ -454,3 +624,129 @@ mod normal_input_mode {
         }
     }
 }
+
+#[cfg(test)]
+mod message_scrolling {
+    use super::*;
+
+    fn key(key: VirtualKeyCode) -> Input {
+        Input {
+            key: Some(key),
+            shift: false,
+            control: false,
+            alt: false,
+        }
+    }
+
+    #[test]
+    fn page_keys_scroll_the_messages_a_window_at_a_time() {
+        let (_update_tx, update_rx) = mpsc::channel();
+        let (command_tx, _command_rx) = mpsc::channel();
+        let mut subject = UIState::new(update_rx, command_tx);
+        let page = subject.message_area().1;
+
+        subject.get_command_from_keyboard_input(&key(VirtualKeyCode::PageUp));
+        subject.get_command_from_keyboard_input(&key(VirtualKeyCode::PageUp));
+        assert_eq!(subject.message_log.scroll(), page * 2);
+
+        subject.get_command_from_keyboard_input(&key(VirtualKeyCode::PageDown));
+        assert_eq!(subject.message_log.scroll(), page);
+
+        subject.get_command_from_keyboard_input(&key(VirtualKeyCode::End));
+        assert_eq!(subject.message_log.scroll(), 0);
+    }
+
+    #[test]
+    fn clicking_the_messages_scrolls_back_above_the_middle_and_forward_below_it() {
+        let (_update_tx, update_rx) = mpsc::channel();
+        let (command_tx, _command_rx) = mpsc::channel();
+        let mut subject = UIState::new(update_rx, command_tx);
+
+        subject.scroll_messages_towards(1);
+        subject.scroll_messages_towards(1);
+        subject.scroll_messages_towards(subject.message_window.height() - 1);
+
+        assert_eq!(subject.message_log.scroll(), 3);
+    }
+}
+
+#[cfg(test)]
+mod quantity_entry_input_mode {
+    use super::*;
+    use game::items::{ItemClass, ItemType};
+
+    fn key(key: VirtualKeyCode) -> Input {
+        Input {
+            key: Some(key),
+            shift: false,
+            control: false,
+            alt: false,
+        }
+    }
+
+    fn holding_apples(quantity: u8) -> UIState {
+        let (_update_tx, update_rx) = mpsc::channel();
+        let (command_tx, _command_rx) = mpsc::channel();
+        let mut subject = UIState::new(update_rx, command_tx);
+
+        subject.inventory = vec![Item::new(
+            1776,
+            ItemType::new(ItemClass::Food, "Apple"),
+            quantity,
+        )];
+        subject.inventory_window.max_selection_items = 1;
+        subject.inventory_window.selected_item = Some(1);
+        subject
+    }
+
+    #[test]
+    fn dropping_a_stack_asks_how_many() {
+        let mut subject = holding_apples(10);
+
+        assert!(matches!(
+            subject.get_command_from_keyboard_input(&key(VirtualKeyCode::D)),
+            Command::None
+        ));
+        assert_eq!(subject.input_state, InputState::QuantityEntry);
+
+        subject.get_command_from_keyboard_input(&key(VirtualKeyCode::Key3));
+        let command = subject.get_command_from_keyboard_input(&key(VirtualKeyCode::Return));
+
+        assert!(matches!(command, Command::DropItem(1776, 3)));
+        assert_eq!(subject.input_state, InputState::Normal);
+        assert_eq!(subject.inventory_window.quantity_prompt, None);
+    }
+
+    #[test]
+    fn entries_larger_than_the_stack_are_ignored() {
+        let mut subject = holding_apples(10);
+        subject.get_command_from_keyboard_input(&key(VirtualKeyCode::X));
+
+        for digit in [VirtualKeyCode::Key1, VirtualKeyCode::Key2] {
+            subject.get_command_from_keyboard_input(&key(digit));
+        }
+        let command = subject.get_command_from_keyboard_input(&key(VirtualKeyCode::Return));
+
+        assert!(matches!(command, Command::SplitStack(1776, 1)));
+    }
+
+    #[test]
+    fn escape_abandons_the_prompt() {
+        let mut subject = holding_apples(10);
+        subject.get_command_from_keyboard_input(&key(VirtualKeyCode::D));
+
+        let command = subject.get_command_from_keyboard_input(&key(VirtualKeyCode::Escape));
+
+        assert!(matches!(command, Command::None));
+        assert_eq!(subject.input_state, InputState::Normal);
+    }
+
+    #[test]
+    fn a_single_item_is_dropped_without_asking() {
+        let mut subject = holding_apples(1);
+
+        let command = subject.get_command_from_keyboard_input(&key(VirtualKeyCode::D));
+
+        assert!(matches!(command, Command::DropItem(1776, 1)));
+    }
+}
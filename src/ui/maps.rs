@@ -1,4 +1,6 @@
 use super::*;
+use game::tile_map::Tile;
+use game::MapChunk;
 use std::collections::HashSet;
 use ui::Sprite;
 
@@ -7,6 +9,8 @@ pub struct BackgroundMap {
     pub width: usize,
     pub height: usize,
     pub map: Vec<SpriteStyle>,
+    tiles: Vec<Option<Tile>>, // None until their chunk is filled in
+    chunk_versions: HashMap<(i32, i32), u64>, // of the chunks filled in so far
 }
 
 impl BackgroundMap {
@@ -15,9 +19,85 @@ impl BackgroundMap {
             width: 0,
             height: 0,
             map: vec![],
+            tiles: vec![],
+            chunk_versions: HashMap::new(),
         }
     }
 
+    /// returns a map of the given size with nothing filled in yet.
+    pub fn blank(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            map: vec![
+                SpriteStyle {
+                    glyph: b' ',
+                    fg: RGB::named(rltk::BLACK),
+                    bg: RGB::named(rltk::BLACK),
+                };
+                width * height
+            ],
+            tiles: vec![None; width * height],
+            chunk_versions: HashMap::new(),
+        }
+    }
+
+    /// fills in the chunk, unless what is filled in already is as new.
+    ///   returns whether it was filled in.
+    pub fn load_chunk<F: Fn(Tile) -> SpriteStyle>(&mut self, chunk: &MapChunk, style: F) -> bool {
+        let position = (chunk.x, chunk.y);
+        if self
+            .chunk_versions
+            .get(&position)
+            .is_some_and(|version| *version >= chunk.version)
+        {
+            return false;
+        }
+
+        for (x, y, tile) in chunk.tiles_with_locations() {
+            self.set_at(x, y, style(tile));
+            self.set_tile_at(x, y, tile);
+        }
+        self.chunk_versions.insert(position, chunk.version);
+        true
+    }
+
+    /// changes a tile of a chunk that has been filled in, if the change is newer than the chunk.
+    ///   A change to a chunk not yet filled in will be part of the chunk when it comes.
+    ///   returns whether the tile was changed.
+    pub fn change_tile(
+        &mut self,
+        x: i32,
+        y: i32,
+        tile: Tile,
+        style: SpriteStyle,
+        version: u64,
+    ) -> bool {
+        let position = MapChunk::containing(x, y);
+
+        match self.chunk_versions.get_mut(&position) {
+            Some(known) if version > *known => {
+                *known = version;
+                self.set_at(x, y, style);
+                self.set_tile_at(x, y, tile);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// returns the tile at x,y, if it lies within the map and its chunk has been filled in.
+    pub fn tile_at(&self, x: i32, y: i32) -> Option<Tile> {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return None;
+        }
+        self.tiles[y as usize * self.width + x as usize]
+    }
+
+    fn set_tile_at(&mut self, x: i32, y: i32, tile: Tile) {
+        self.tiles[y as usize * self.width + x as usize] = Some(tile);
+    }
+
     pub fn at(&self, x: i32, y: i32) -> SpriteStyle {
         // check for bounds
         self.map[y as usize * self.width + x as usize]
@@ -32,6 +112,7 @@ impl BackgroundMap {
 pub struct SparseMap {
     pub sprites: HashMap<u64, Sprite>,
     positions: HashMap<(i32, i32), OneMany<u64>>,
+    descriptions: HashMap<u64, String>, // as shown in tooltips
 }
 
 #[derive(Debug, Clone)]
@@ -45,6 +126,7 @@ impl SparseMap {
         SparseMap {
             sprites: HashMap::new(),   // indexed on id
             positions: HashMap::new(), // indexed on position
+            descriptions: HashMap::new(),
         }
     }
 
@@ -84,57 +166,20 @@ impl SparseMap {
     }
 
     fn lookup_sprite_for_item_class(class: ItemClass) -> ui::Sprite {
-        match class {
-            ItemClass::Dagger => ui::Sprite {
-                style: SpriteStyle {
-                    glyph: 25 as u8, // '↓'
-                    fg: RGB::named(rltk::WHITE),
-                    bg: RGB::named(rltk::BLACK),
-                },
-                x: 0,
-                y: 0,
-                facing: Direction::Up,
-            },
-            ItemClass::Headwear => ui::Sprite {
-                style: SpriteStyle {
-                    glyph: 94 as u8, // ^
-                    fg: RGB::named(rltk::WHITE),
-                    bg: RGB::named(rltk::BLACK),
-                },
-                x: 0,
-                y: 0,
-                facing: Direction::Up,
-            },
-            ItemClass::BladeWeapon => ui::Sprite {
-                style: SpriteStyle {
-                    glyph: 33 as u8, // !
-                    fg: RGB::named(rltk::WHITE),
-                    bg: RGB::named(rltk::BLACK),
-                },
-                x: 0,
-                y: 0,
-                facing: Direction::Up,
-            },
-            ItemClass::Potion => ui::Sprite {
-                style: SpriteStyle {
-                    glyph: 173 as u8, // ¡
-                    fg: RGB::named(rltk::WHITE),
-                    bg: RGB::named(rltk::BLACK),
-                },
-                x: 0,
-                y: 0,
-                facing: Direction::Up,
-            },
-            _ => ui::Sprite {
-                style: SpriteStyle {
-                    glyph: '?' as u8,
-                    fg: RGB::named(rltk::WHITE),
-                    bg: RGB::named(rltk::BLACK),
+        let definition = class.definition();
+
+        ui::Sprite {
+            style: SpriteStyle {
+                glyph: match rltk::to_cp437(definition.glyph) {
+                    0 => b'?',
+                    glyph => glyph as u8,
                 },
-                x: 0,
-                y: 0,
-                facing: Direction::Up,
+                fg: RGB::from_hex(&definition.color).unwrap_or_else(|_| RGB::named(rltk::WHITE)),
+                bg: RGB::named(rltk::BLACK),
             },
+            x: 0,
+            y: 0,
+            facing: Direction::Up,
         }
     }
 
@@ -180,13 +225,15 @@ impl SparseMap {
         sprite.y = y;
 
         self.sprites.insert(id, sprite);
+        self.descriptions
+            .insert(id, format!("{:?}", character_type));
         self.insert_at(x, y, id)
     }
 
     pub fn add_item(
         &mut self,
         id: u64,
-        _description: &String,
+        description: &String,
         item_class: ItemClass,
         x: i32,
         y: i32,
@@ -196,6 +243,10 @@ impl SparseMap {
         sprite.y = y;
 
         self.sprites.insert(id, sprite);
+        self.descriptions.insert(
+            id,
+            format!("{} ({})", description, Tooltip::label(item_class.name())),
+        );
         self.insert_at(x, y, id)
     }
 
@@ -205,15 +256,38 @@ impl SparseMap {
         x: i32,
         y: i32,
         class: FacilityClass,
-        _description: String,
+        description: String,
     ) {
         let mut sprite = Self::lookup_sprite_for_facility_class(class);
         sprite.x = x;
         sprite.y = y;
 
         self.sprites.insert(id, sprite);
+        self.descriptions.insert(id, description);
         self.insert_at(x, y, id)
     }
+
+    /// returns what a tooltip says of the sprite.
+    pub fn describe(&self, id: u64) -> Option<&String> {
+        self.descriptions.get(&id)
+    }
+
+    pub fn set_description<S: ToString>(&mut self, id: u64, description: S) {
+        if self.sprites.contains_key(&id) {
+            self.descriptions.insert(id, description.to_string());
+        }
+    }
+
+    /// returns the ids of the sprites at x,y, lowest first.
+    pub fn ids_at(&self, x: i32, y: i32) -> Vec<u64> {
+        let mut ids: Vec<u64> = match self.at(x, y) {
+            Some(OneMany::One(id)) => vec![*id],
+            Some(OneMany::Many(set)) => set.iter().copied().collect(),
+            None => vec![],
+        };
+        ids.sort_unstable();
+        ids
+    }
     pub fn lookup(&self, id: u64) -> Option<&Sprite> {
         self.sprites.get(&id)
     }
@@ -242,6 +316,7 @@ impl SparseMap {
     pub fn remove(&mut self, id: u64) {
         println!("removing: {}", id);
         let sprite = self.sprites.remove(&id).expect("sprite not found");
+        self.descriptions.remove(&id);
 
         let entry = self
             .positions
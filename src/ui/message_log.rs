@@ -0,0 +1,260 @@
+use super::*;
+use std::collections::VecDeque;
+
+/// a message as it is kept in the log, stamped with the time it arrived.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LogEntry {
+    pub time: String, // HH:MM:SS
+    pub category: MessageCategory,
+    pub text: String,
+}
+
+/// the most recent messages, and how far back through them the messages window is scrolled.
+#[derive(Debug, Default)]
+pub struct MessageLog {
+    entries: VecDeque<LogEntry>,
+    scroll: usize, // in lines back from the latest
+}
+
+impl MessageLog {
+    /// how many messages are kept before the oldest are forgotten.
+    pub const CAPACITY: usize = 200;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// returns the entries from oldest to latest.
+    pub fn entries(&self) -> impl Iterator<Item = &LogEntry> {
+        self.entries.iter()
+    }
+
+    /// logs a message as arriving now.
+    pub fn add<S: ToString>(&mut self, category: MessageCategory, text: S) {
+        let time = chrono::Local::now().format("%H:%M:%S").to_string();
+        self.add_at(time, category, text);
+    }
+
+    /// logs a message as arriving at the given time.
+    /// # Examples:
+    /// ```
+    /// # use muframework::MessageCategory;
+    /// # use muframework::ui::message_log::MessageLog;
+    /// let mut subject = MessageLog::new();
+    /// for n in 0..MessageLog::CAPACITY + 1 {
+    ///     subject.add_at("12:00:00", MessageCategory::System, n);
+    /// }
+    ///
+    /// assert_eq!(subject.len(), MessageLog::CAPACITY);
+    /// assert_eq!(subject.entries().next().unwrap().text, "1");
+    /// ```
+    pub fn add_at<S: ToString, T: ToString>(
+        &mut self,
+        time: S,
+        category: MessageCategory,
+        text: T,
+    ) {
+        if self.entries.len() == Self::CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(LogEntry {
+            time: time.to_string(),
+            category,
+            text: text.to_string(),
+        });
+    }
+
+    pub fn scroll_back(&mut self, lines: usize) {
+        self.scroll += lines;
+    }
+
+    pub fn scroll_forward(&mut self, lines: usize) {
+        self.scroll = self.scroll.saturating_sub(lines);
+    }
+
+    /// returns to following the latest messages.
+    pub fn scroll_to_latest(&mut self) {
+        self.scroll = 0;
+    }
+
+    pub fn scroll(&self) -> usize {
+        self.scroll
+    }
+
+    /// returns every entry wrapped to width, oldest first.  The first line of an entry starts
+    ///   with its time, and the lines after it are indented to match.
+    pub fn lines(&self, width: usize) -> Vec<(MessageCategory, String)> {
+        let mut lines = vec![];
+
+        for entry in self.entries.iter() {
+            let indent = " ".repeat(entry.time.len() + 1);
+            let wrapped = wrap(&entry.text, width.saturating_sub(indent.len()).max(1));
+
+            for (index, line) in wrapped.into_iter().enumerate() {
+                let line = match index {
+                    0 => format!("{} {}", entry.time, line),
+                    _ => format!("{}{}", indent, line),
+                };
+                lines.push((entry.category, line));
+            }
+        }
+        lines
+    }
+
+    /// returns the lines that fit in a window of the given size, as scrolled.
+    ///   Scrolling back past the oldest line is taken back to it.
+    /// # Examples:
+    /// ```
+    /// # use muframework::MessageCategory;
+    /// # use muframework::ui::message_log::MessageLog;
+    /// let mut subject = MessageLog::new();
+    /// for text in ["one", "two", "three"].iter() {
+    ///     subject.add_at("12:00:00", MessageCategory::System, text);
+    /// }
+    ///
+    /// assert_eq!(subject.visible_lines(20, 2)[0].1, "12:00:00 two");
+    ///
+    /// subject.scroll_back(5);
+    /// assert_eq!(subject.visible_lines(20, 2)[0].1, "12:00:00 one");
+    /// assert_eq!(subject.scroll(), 1);
+    /// ```
+    pub fn visible_lines(&mut self, width: usize, height: usize) -> Vec<(MessageCategory, String)> {
+        let mut lines = self.lines(width);
+
+        self.scroll = self.scroll.min(lines.len().saturating_sub(height));
+        let end = lines.len() - self.scroll;
+        let start = end.saturating_sub(height);
+
+        lines.truncate(end);
+        lines.split_off(start)
+    }
+}
+
+/// breaks text into lines of at most width, between words where it can.
+/// # Examples:
+/// ```
+/// # use muframework::ui::message_log::wrap;
+/// assert_eq!(wrap("You pick up an apple.", 12), vec!["You pick up", "an apple."]);
+/// assert_eq!(wrap("Aaaaargh!", 4), vec!["Aaaa", "argh", "!"]);
+/// assert_eq!(wrap("", 4), vec![""]);
+/// ```
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = vec![];
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+
+        if !line.is_empty() && line.chars().count() + 1 + word.len() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+
+        // a word too long for a line of its own is broken wherever it must be.
+        while word.len() > width {
+            let rest = word.split_off(width);
+            lines.push(word.into_iter().collect());
+            word = rest;
+        }
+
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.extend(word);
+    }
+
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// returns the color messages of the category are shown in.
+pub fn color_of(category: MessageCategory) -> RGB {
+    match category {
+        MessageCategory::System => RGB::named(rltk::WHITE),
+        MessageCategory::Combat => RGB::named(rltk::ORANGE),
+        MessageCategory::Loot => RGB::named(rltk::GOLD),
+        MessageCategory::Chat => RGB::named(rltk::CYAN),
+        MessageCategory::Error => RGB::named(rltk::RED),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_messages_are_wrapped_under_their_time() {
+        let mut subject = MessageLog::new();
+        subject.add_at(
+            "09:15:00",
+            MessageCategory::Loot,
+            "You pick up a reed basket.",
+        );
+
+        assert_eq!(
+            subject.lines(20),
+            vec![
+                (MessageCategory::Loot, "09:15:00 You pick up".to_string()),
+                (MessageCategory::Loot, "         a reed".to_string()),
+                (MessageCategory::Loot, "         basket.".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn scrolling_moves_through_the_history() {
+        let mut subject = MessageLog::new();
+        for n in 1..=5 {
+            subject.add_at("00:00:00", MessageCategory::System, n);
+        }
+
+        let texts = |subject: &mut MessageLog| -> Vec<String> {
+            subject
+                .visible_lines(20, 2)
+                .into_iter()
+                .map(|(_, line)| line[9..].to_string())
+                .collect()
+        };
+        assert_eq!(texts(&mut subject), vec!["4", "5"]);
+
+        subject.scroll_back(2);
+        assert_eq!(texts(&mut subject), vec!["2", "3"]);
+
+        subject.scroll_forward(1);
+        assert_eq!(texts(&mut subject), vec!["3", "4"]);
+
+        subject.scroll_forward(10);
+        assert_eq!(texts(&mut subject), vec!["4", "5"]);
+
+        subject.scroll_back(10);
+        subject.scroll_to_latest();
+        assert_eq!(subject.scroll(), 0);
+    }
+
+    #[test]
+    fn fewer_lines_than_fit_are_all_shown() {
+        let mut subject = MessageLog::new();
+        subject.add_at("00:00:00", MessageCategory::Error, "That is locked.");
+        subject.scroll_back(3);
+
+        let lines = subject.visible_lines(40, 10);
+
+        assert_eq!(
+            lines,
+            vec![(
+                MessageCategory::Error,
+                "00:00:00 That is locked.".to_string()
+            )]
+        );
+        assert_eq!(subject.scroll(), 0);
+    }
+}
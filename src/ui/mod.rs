@@ -11,11 +11,20 @@ use game::items::Item;
 use game::tile_map::{Tile, TileMap};
 use maps::{BackgroundMap, SparseMap};
 pub mod window;
-use window::MapWindowMode;
+use window::{MapWindowMode, ScreenObject};
 
 pub mod pane;
 pub use pane::*;
 
+pub mod prediction;
+use prediction::Prediction;
+
+pub mod message_log;
+use message_log::MessageLog;
+
+pub mod tooltip;
+use tooltip::Tooltip;
+
 #[derive(Debug, Copy, Clone)]
 pub struct SpriteStyle {
     pub glyph: u8,
@@ -72,6 +81,7 @@ pub struct UIState {
     pub facilities: SparseMap,
 
     pub player: UIPlayer,
+    pub prediction: Prediction,
 
     pub inventory: Vec<Item>,
     pub equipment: Vec<Item>,
@@ -84,6 +94,9 @@ pub struct UIState {
     pub mouse_state: MouseState,
 
     pub activity_time: Option<u64>,
+
+    pub message_log: MessageLog,
+    pub tooltip: Option<Tooltip>,
 }
 
 impl GameState for UIState {
@@ -103,16 +116,27 @@ impl UIState {
                 std::process::exit(0)
             }
             Ok(SetBackground(tile_map)) => self.set_background(&tile_map),
-            Ok(TileChangedAt(x, y, new_tile)) => {
+            Ok(MapDimensions(width, height)) => self.set_map_dimensions(width, height),
+            Ok(ChunkLoaded(chunk)) => {
+                if self.background.load_chunk(&chunk, Self::style_from_tile) {
+                    self.prediction.load_chunk(&chunk);
+                }
+            }
+            Ok(TileChangedAt(x, y, new_tile, version)) => {
                 let new_style = Self::style_from_tile(new_tile);
-                self.background.set_at(x, y, new_style);
+                if self
+                    .background
+                    .change_tile(x, y, new_tile, new_style, version)
+                {
+                    self.prediction.change_tile(x, y, new_tile);
+                }
             }
-            Ok(Message(m)) => println!("Message: {}", m),
+            Ok(Message(category, m)) => self.add_message(category, m),
             Ok(CharacterTeleported(id, new_x, new_y)) => {
-                self.player.locate(new_x, new_y);
                 self.characters.reposition(id, new_x, new_y);
+                self.prediction.locate_character(id, new_x, new_y);
                 if id == 1 {
-                    self.focus_on_player(new_x, new_y);
+                    self.show_predicted_player();
                 }
             }
             Ok(CharacterEntered {
@@ -121,23 +145,33 @@ impl UIState {
                 y,
                 character_type,
             }) => {
-                self.player.locate(x, y);
                 self.characters.add_character(id, character_type, x, y);
+                self.prediction.locate_character(id, x, y);
                 if id == 1 {
-                    self.focus_on_player(x, y);
+                    self.show_predicted_player();
                 }
             }
+            Ok(CharacterExited(id)) => {
+                self.characters.remove(id);
+                self.prediction.remove_character(id);
+            }
+            Ok(PlayerNoticed(_)) => {
+                self.add_message(MessageCategory::Combat, "Something has noticed you.")
+            }
             Ok(CharacterMoved(id, new_x, new_y)) => {
-                self.player.locate(new_x, new_y);
                 self.characters.reposition(id, new_x, new_y);
+                self.prediction.locate_character(id, new_x, new_y);
                 if id == 1 {
-                    self.focus_on_player(new_x, new_y);
+                    self.show_predicted_player();
                 }
             }
+            Ok(MovementStarted(_, _, _, _)) | Ok(MovementCompleted(_)) | Ok(MovementAborted(_)) => {
+            }
             Ok(CharacterFacingChanged(id, facing)) => {
                 self.characters.change_facing(id, facing);
+                self.prediction.change_facing(id, facing);
                 if id == 1 {
-                    self.player.facing = facing;
+                    self.show_predicted_player();
                 }
             }
             Ok(ItemAdded {
@@ -155,11 +189,12 @@ impl UIState {
                 description,
                 class,
             }) => self.facilities.add_facility(id, x, y, class, description),
+            Ok(FacilityRemoved(id)) => self.facilities.remove(id),
             Ok(FacilityUpdated {
-                id: _,
-                description: _,
+                id,
+                description,
                 class: _,
-            }) => {}
+            }) => self.facilities.set_description(id, description),
             Ok(EquipmentUpdated(items)) => {
                 self.inventory_window.max_selection_equipment = items.len() as u8;
                 self.inventory_window
@@ -210,6 +245,16 @@ impl UIState {
             Ok(ActivityAborted()) => {
                 self.input_state = InputState::Normal;
                 self.activity_time = None;
+                self.add_message(MessageCategory::System, "You stop what you were doing.");
+            }
+            Ok(SkillLevelIncreased(skill, level)) => self.add_message(
+                MessageCategory::System,
+                format!("Your {:?} skill is now level {}.", skill, level),
+            ),
+            Ok(CommandRejected(rejection)) => self.add_message(MessageCategory::Error, rejection),
+            Ok(CommandProcessed(sequence)) => {
+                self.prediction.acknowledge(sequence);
+                self.show_predicted_player();
             }
             Err(_) => {}
         }
@@ -218,6 +263,10 @@ impl UIState {
             self.draw_gui(context);
             self.draw_map(context);
             self.process_input(context);
+
+            if let Some(tooltip) = &self.tooltip {
+                tooltip.draw(context);
+            }
         }
     }
 
@@ -237,6 +286,7 @@ impl UIState {
             background: BackgroundMap::empty(),
 
             player: UIPlayer::new(),
+            prediction: Prediction::new(),
 
             inventory: vec![],
             equipment: vec![],
@@ -247,74 +297,67 @@ impl UIState {
             mouse_state: MouseState::LeftButtonUp,
 
             activity_time: None,
+
+            message_log: MessageLog::new(),
+            tooltip: None,
         }
     }
 
+    fn add_message<S: ToString>(&mut self, category: MessageCategory, message: S) {
+        self.message_log.add(category, message);
+    }
+
     fn update_equipment(&mut self, items: Vec<Item>) {
         self.equipment = items;
     }
 
-    fn set_background(&mut self, tile_map: &TileMap) {
-        self.background.width = tile_map.map_width;
-        self.background.height = tile_map.map_height;
-
-        self.map_window
-            .set_max_scroll(tile_map.map_width as i32, tile_map.map_height as i32);
-
-        self.background = BackgroundMap {
-            width: tile_map.map_width,
-            height: tile_map.map_height,
-            map: vec![
-                SpriteStyle {
-                    glyph: ' ' as u8,
-                    fg: RGB::named(rltk::BLACK),
-                    bg: RGB::named(rltk::BLACK),
-                };
-                tile_map.map_width * tile_map.map_height
-            ],
-        };
+    /// starts a map of the given size, to be filled in as its chunks arrive.
+    fn set_map_dimensions(&mut self, width: usize, height: usize) {
+        self.map_window.set_max_scroll(width as i32, height as i32);
+        self.background = BackgroundMap::blank(width, height);
+        self.prediction.set_map_dimensions(width, height);
+    }
 
-        for y in 0..self.background.height as i32 {
-            for x in 0..self.background.width as i32 {
-                let style = Self::style_from_tile(tile_map.at(x, y));
-                self.background.set_at(x, y, style);
+    /// fills in the whole map at once.
+    fn set_background(&mut self, tile_map: &TileMap) {
+        self.set_map_dimensions(tile_map.map_width, tile_map.map_height);
+
+        let (across, down) = tile_map.chunk_dimensions();
+        for chunk_y in 0..down {
+            for chunk_x in 0..across {
+                if let Some(chunk) = tile_map.chunk(chunk_x, chunk_y) {
+                    self.background.load_chunk(&chunk, Self::style_from_tile);
+                    self.prediction.load_chunk(&chunk);
+                }
             }
         }
     }
+
     fn style_from_tile(tile: Tile) -> SpriteStyle {
-        let style = match tile {
-            Tile::Empty => SpriteStyle {
-                glyph: ' ' as u8,
-                fg: RGB::named(rltk::BLACK),
-                bg: RGB::named(rltk::BLACK),
-            },
-            Tile::StoneWall => SpriteStyle {
-                glyph: '#' as u8,
-                fg: RGB::named(rltk::WHITE),
-                bg: RGB::named(rltk::BLACK),
-            },
-            Tile::DirtFloor => SpriteStyle {
-                glyph: '.' as u8,
-                fg: RGB::named(rltk::LIGHT_GRAY),
-                bg: RGB::named(rltk::BLACK),
-            },
-            Tile::ClosedDoor => SpriteStyle {
-                glyph: '|' as u8,
-                fg: RGB::named(rltk::WHITE),
-                bg: RGB::named(rltk::BLACK),
-            },
-            Tile::OpenDoor => SpriteStyle {
-                glyph: '/' as u8,
-                fg: RGB::named(rltk::WHITE),
-                bg: RGB::named(rltk::BLACK),
-            },
-            Tile::Facility(_) => SpriteStyle {
-                glyph: '\u{ff}' as u8,
-                fg: RGB::named(rltk::BLACK),
-                bg: RGB::named(rltk::WHITE),
+        let definition = tile.definition();
+
+        SpriteStyle {
+            glyph: match rltk::to_cp437(definition.glyph) {
+                0 => b'?',
+                glyph => glyph as u8,
             },
-        };
-        style
+            fg: RGB::from_hex(&definition.fg).unwrap_or_else(|_| RGB::named(rltk::WHITE)),
+            bg: RGB::from_hex(&definition.bg).unwrap_or_else(|_| RGB::named(rltk::BLACK)),
+        }
+    }
+
+    /// shows the player where they are expected to be, which may be ahead of the server.
+    fn show_predicted_player(&mut self) {
+        let (x, y) = self.prediction.position();
+        let facing = self.prediction.facing();
+
+        if self.characters.sprites.contains_key(&1) {
+            self.characters.reposition(1, x, y);
+            self.characters.change_facing(1, facing);
+        }
+        self.player.locate(x, y);
+        self.player.facing = facing;
+        self.focus_on_player(x, y);
     }
 
     fn focus_on_player(&mut self, x: i32, y: i32) {
@@ -327,7 +370,12 @@ impl UIState {
 
     fn draw_gui(&mut self, context: &mut BTerm) {
         context.cls();
-        self.message_window.draw_frame(context, "messages");
+        let title = match self.message_log.scroll() {
+            0 => "messages",
+            _ => "messages (End: latest)",
+        };
+        self.message_window.draw_frame(context, title);
+        self.draw_messages(context);
 
         self.inventory_window.draw_frame(context, "inventory");
 
@@ -346,6 +394,36 @@ impl UIState {
         self.map_window.draw_frame(context, &message[..]);
     }
 
+    /// prints the messages that fit, oldest first, as far back as the log is scrolled.
+    fn draw_messages(&mut self, context: &mut BTerm) {
+        let window = &self.message_window;
+        let (width, height) = self.message_area();
+
+        for (index, (category, line)) in self
+            .message_log
+            .visible_lines(width, height)
+            .into_iter()
+            .enumerate()
+        {
+            context.print_color(
+                window.x() + 1,
+                window.y() + 1 + index as i32,
+                message_log::color_of(category),
+                RGB::named(rltk::BLACK),
+                line,
+            );
+        }
+    }
+
+    /// returns the width and height of the messages window inside its frame.
+    fn message_area(&self) -> (usize, usize) {
+        let window = &self.message_window;
+        (
+            (window.width() - 1).max(0) as usize,
+            (window.height() - 1).max(0) as usize,
+        )
+    }
+
     fn draw_background(&self, context: &mut BTerm) {
         for y in 0..self.background.height as i32 {
             for x in 0..self.background.width as i32 {
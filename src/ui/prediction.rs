@@ -0,0 +1,329 @@
+use super::*;
+use game::blocking_map::BlockingMap;
+use game::command::get_deltas_from_direction;
+use game::MapChunk;
+use std::collections::VecDeque;
+
+/// where the client expects the player to end up, ahead of the server saying so.
+///   Each move is numbered as it is sent and played out at once against what the client
+///   knows of the map.  Once the server reports a numbered move over, the player is put
+///   back where the server last placed them and the moves still outstanding are replayed,
+///   which undoes any the server would not allow.  Moves are reported over one by one, and
+///   not always in order: a step queued behind another is dropped at once when replaced.
+#[derive(Debug)]
+pub struct Prediction {
+    next_sequence: u64,
+    pending: VecDeque<(u64, Direction, MoveCommandMode)>, // moves sent but not yet reported over
+    confirmed: (i32, i32, Direction),                     // where the server last placed the player
+    predicted: (i32, i32, Direction),
+    obstacles: BlockingMap,               // tiles that can't be entered
+    characters: HashMap<u64, (i32, i32)>, // other than the player, who block as well
+}
+
+impl Prediction {
+    const PLAYER_ID: u64 = 1;
+
+    pub fn new() -> Self {
+        Self {
+            next_sequence: 1,
+            pending: VecDeque::new(),
+            confirmed: (0, 0, Direction::Up),
+            predicted: (0, 0, Direction::Up),
+            obstacles: BlockingMap::new(),
+            characters: HashMap::new(),
+        }
+    }
+
+    /// returns where the player is expected to be.
+    pub fn position(&self) -> (i32, i32) {
+        (self.predicted.0, self.predicted.1)
+    }
+
+    /// returns which way the player is expected to face.
+    pub fn facing(&self) -> Direction {
+        self.predicted.2
+    }
+
+    /// starts a map of the given size, none of which can be entered until its chunks arrive.
+    pub fn set_map_dimensions(&mut self, width: usize, height: usize) {
+        self.obstacles = BlockingMap {
+            width,
+            height,
+            map: vec![true; width * height],
+        };
+    }
+
+    pub fn load_chunk(&mut self, chunk: &MapChunk) {
+        for (x, y, tile) in chunk.tiles_with_locations() {
+            self.change_tile(x, y, tile);
+        }
+    }
+
+    pub fn change_tile(&mut self, x: i32, y: i32, tile: Tile) {
+        if !self.obstacles.is_in_bounds(x, y) {
+            return;
+        }
+        match tile.is_passable() {
+            true => self.obstacles.unblock_at(x, y),
+            false => self.obstacles.block_at(x, y),
+        }
+    }
+
+    /// notes where a character is, the player included.
+    pub fn locate_character(&mut self, id: u64, x: i32, y: i32) {
+        if id == Self::PLAYER_ID {
+            self.confirmed = (x, y, self.confirmed.2);
+            self.settle();
+        } else {
+            self.characters.insert(id, (x, y));
+        }
+    }
+
+    pub fn remove_character(&mut self, id: u64) {
+        self.characters.remove(&id);
+    }
+
+    pub fn change_facing(&mut self, id: u64, facing: Direction) {
+        if id == Self::PLAYER_ID {
+            self.confirmed.2 = facing;
+            self.settle();
+        }
+    }
+
+    /// numbers a move and plays it out, returning the command to send in its place.
+    ///   A move that uses what is there only turns the player, as it may use nothing.
+    ///   Other commands are sent as they are.
+    /// # Examples:
+    /// ```
+    /// # use muframework::*;
+    /// # use muframework::ui::prediction::Prediction;
+    /// let mut subject = Prediction::new();
+    ///
+    /// let command = subject.predict(Command::Move(Direction::Left, MoveCommandMode::Normal));
+    ///
+    /// assert!(matches!(command, Command::Sequenced(1, _)));
+    /// assert!(matches!(subject.predict(Command::TakeItem(3)), Command::TakeItem(3)));
+    /// ```
+    pub fn predict(&mut self, command: Command) -> Command {
+        let (direction, mode) = match command {
+            Command::Move(direction, mode) => (direction, mode),
+            _ => return command,
+        };
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+
+        self.pending.push_back((sequence, direction, mode));
+        self.predicted = self.play_out(self.predicted, direction, mode);
+
+        Command::Sequenced(sequence, Box::new(command))
+    }
+
+    /// drops the move the server has reported over and replays those left from where the
+    ///   server has the player.
+    pub fn acknowledge(&mut self, sequence: u64) {
+        self.pending.retain(|(pending, _, _)| *pending != sequence);
+        self.reconcile();
+    }
+
+    /// takes up where the server has the player, unless moves are outstanding, whose
+    ///   acknowledgement will reconcile them.
+    fn settle(&mut self) {
+        if self.pending.is_empty() {
+            self.predicted = self.confirmed;
+        }
+    }
+
+    fn reconcile(&mut self) {
+        let moves: Vec<(Direction, MoveCommandMode)> = self
+            .pending
+            .iter()
+            .map(|(_, direction, mode)| (*direction, *mode))
+            .collect();
+
+        self.predicted = moves
+            .into_iter()
+            .fold(self.confirmed, |state, (direction, mode)| {
+                self.play_out(state, direction, mode)
+            });
+    }
+
+    /// moves one tile, as the server would, or only turns should the way be blocked or the
+    ///   move be one that uses what is there.
+    fn play_out(
+        &self,
+        state: (i32, i32, Direction),
+        direction: Direction,
+        mode: MoveCommandMode,
+    ) -> (i32, i32, Direction) {
+        let (x, y, _) = state;
+        let (dx, dy) = get_deltas_from_direction(direction);
+
+        match mode == MoveCommandMode::Use || self.is_blocked_at(x + dx, y + dy) {
+            true => (x, y, direction),
+            false => (x + dx, y + dy, direction),
+        }
+    }
+
+    fn is_blocked_at(&self, x: i32, y: i32) -> bool {
+        !self.obstacles.is_in_bounds(x, y)
+            || self.obstacles.is_blocked_at(x, y)
+            || self.characters.values().any(|position| *position == (x, y))
+    }
+}
+
+impl Default for Prediction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game::tile_map::TileMap;
+
+    // a room three tiles square, walled in, with the player in the middle.
+    fn subject() -> Prediction {
+        let mut map = TileMap::new();
+        map.set_dimensions(5, 5, Tile::StoneWall);
+        for y in 1..4 {
+            for x in 1..4 {
+                map.set_tile_at(x, y, Tile::DirtFloor);
+            }
+        }
+
+        let mut subject = Prediction::new();
+        subject.set_map_dimensions(5, 5);
+        subject.load_chunk(&map.chunk(0, 0).unwrap());
+        subject.locate_character(1, 2, 2);
+        subject
+    }
+
+    fn walk(direction: Direction) -> Command {
+        Command::Move(direction, MoveCommandMode::Normal)
+    }
+
+    #[test]
+    fn moves_are_shown_before_the_server_confirms_them() {
+        let mut subject = subject();
+
+        subject.predict(walk(Direction::Up));
+
+        assert_eq!(subject.position(), (2, 1));
+        assert_eq!(subject.facing(), Direction::Up);
+    }
+
+    #[test]
+    fn moves_into_walls_and_characters_only_turn() {
+        let mut subject = subject();
+        subject.locate_character(4, 3, 2);
+
+        subject.predict(walk(Direction::Right));
+        subject.predict(walk(Direction::Up));
+        subject.predict(walk(Direction::Up));
+
+        assert_eq!(subject.position(), (2, 1));
+        assert_eq!(subject.facing(), Direction::Up);
+    }
+
+    #[test]
+    fn using_what_is_there_only_turns() {
+        let mut subject = subject();
+
+        let command = subject.predict(Command::Move(Direction::Up, MoveCommandMode::Use));
+
+        assert!(matches!(command, Command::Sequenced(1, _)));
+        assert_eq!(subject.position(), (2, 2));
+        assert_eq!(subject.facing(), Direction::Up);
+
+        subject.change_facing(1, Direction::Up);
+        subject.acknowledge(1);
+        assert_eq!(subject.position(), (2, 2));
+    }
+
+    #[test]
+    fn the_map_can_not_be_entered_before_it_arrives() {
+        let mut subject = Prediction::new();
+        subject.set_map_dimensions(5, 5);
+        subject.locate_character(1, 2, 2);
+
+        subject.predict(walk(Direction::Down));
+
+        assert_eq!(subject.position(), (2, 2));
+        assert_eq!(subject.facing(), Direction::Down);
+    }
+
+    #[test]
+    fn confirmed_moves_are_not_played_out_again() {
+        let mut subject = subject();
+        subject.predict(walk(Direction::Left));
+        subject.predict(walk(Direction::Down));
+
+        subject.locate_character(1, 1, 2);
+        subject.change_facing(1, Direction::Left);
+        assert_eq!(subject.position(), (1, 3));
+
+        subject.acknowledge(1);
+
+        assert_eq!(subject.position(), (1, 3));
+        assert_eq!(subject.facing(), Direction::Down);
+    }
+
+    #[test]
+    fn moves_the_server_rejects_are_rolled_back() {
+        let mut subject = subject();
+        subject.predict(walk(Direction::Up));
+
+        // something the client had not heard of yet stood in the way.
+        subject.change_facing(1, Direction::Up);
+        subject.acknowledge(1);
+
+        assert_eq!(subject.position(), (2, 2));
+        assert_eq!(subject.facing(), Direction::Up);
+    }
+
+    #[test]
+    fn moves_still_outstanding_are_replayed_from_where_the_server_has_the_player() {
+        let mut subject = subject();
+        subject.predict(walk(Direction::Up));
+        subject.predict(walk(Direction::Left));
+
+        // the first move was turned back, so the second starts from the middle.
+        subject.acknowledge(1);
+
+        assert_eq!(subject.position(), (1, 2));
+        assert_eq!(subject.facing(), Direction::Left);
+    }
+
+    #[test]
+    fn a_move_dropped_during_a_step_rolls_back_only_itself() {
+        let mut subject = subject();
+        subject.predict(walk(Direction::Up));
+        subject.predict(walk(Direction::Left));
+        subject.predict(walk(Direction::Right));
+
+        // the second move was queued behind the first, then replaced by the third.
+        subject.acknowledge(2);
+        assert_eq!(subject.position(), (3, 1));
+
+        subject.locate_character(1, 2, 1);
+        subject.change_facing(1, Direction::Up);
+        subject.acknowledge(1);
+        assert_eq!(subject.position(), (3, 1));
+
+        subject.locate_character(1, 3, 1);
+        subject.change_facing(1, Direction::Right);
+        subject.acknowledge(3);
+        assert_eq!(subject.position(), (3, 1));
+        assert_eq!(subject.facing(), Direction::Right);
+    }
+
+    #[test]
+    fn the_server_is_followed_once_nothing_is_outstanding() {
+        let mut subject = subject();
+
+        subject.locate_character(1, 3, 3);
+
+        assert_eq!(subject.position(), (3, 3));
+    }
+}
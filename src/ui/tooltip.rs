@@ -0,0 +1,276 @@
+use super::*;
+use window::{InventoryWindowMode, ScreenObject};
+
+/// a few lines describing what is under the mouse, shown beside it.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Tooltip {
+    pub x: i32, // the point on the screen it describes
+    pub y: i32,
+    pub lines: Vec<String>,
+}
+
+impl Tooltip {
+    /// returns a tooltip at the point, or None if there is nothing to say.
+    pub fn new(x: i32, y: i32, lines: Vec<String>) -> Option<Self> {
+        match lines.is_empty() {
+            true => None,
+            false => Some(Self { x, y, lines }),
+        }
+    }
+
+    /// describes whatever is known to be at a map location: characters first, then facilities,
+    ///   then items, then the tile beneath them all.
+    pub fn describe_location(
+        x: i32,
+        y: i32,
+        characters: &SparseMap,
+        facilities: &SparseMap,
+        items: &SparseMap,
+        background: &BackgroundMap,
+    ) -> Vec<String> {
+        let mut lines: Vec<String> = vec![];
+
+        for sprites in [characters, facilities, items].iter() {
+            lines.extend(
+                sprites
+                    .ids_at(x, y)
+                    .into_iter()
+                    .filter_map(|id| sprites.describe(id).cloned()),
+            );
+        }
+
+        if let Some(tile) = background.tile_at(x, y) {
+            lines.push(Self::label(&tile.definition().name));
+        }
+        lines
+    }
+
+    /// describes an item in full, with as much of its type as the client knows.
+    /// # Examples:
+    /// ```
+    /// # use muframework::game::{Item, ItemClass};
+    /// # use muframework::ui::tooltip::Tooltip;
+    /// let mut item = Item::spawn_stack(ItemClass::Food, "Apple", 3);
+    ///
+    /// assert_eq!(Tooltip::describe_item(&item), vec!["3 Apples", "Food"]);
+    ///
+    /// item.item_type.set_speed(-2);
+    /// assert_eq!(Tooltip::describe_item(&item)[2], "Speed -2");
+    /// ```
+    pub fn describe_item(item: &Item) -> Vec<String> {
+        let item_type = &item.item_type;
+        let mut lines = vec![item.description(), Self::label(item.class().name())];
+
+        if item_type.is_container() {
+            lines.push(format!("Holds {} stacks", item_type.capacity()));
+        }
+        if item_type.speed() != 0 {
+            lines.push(format!("Speed {:+}", item_type.speed()));
+        }
+        if let Some((skill, level)) = item_type.skill_requirement() {
+            lines.push(format!("Requires {:?} {}", skill, level));
+        }
+        if !item.endorsements().is_empty() {
+            let endorsements: Vec<&str> = item
+                .endorsements()
+                .iter()
+                .map(|endorsement| endorsement.trim_start_matches(':'))
+                .collect();
+            lines.push(format!("Grants {}", endorsements.join(", ")));
+        }
+        lines
+    }
+
+    /// returns a name from the game data as a tooltip shows it.
+    /// # Examples:
+    /// ```
+    /// # use muframework::ui::tooltip::Tooltip;
+    /// assert_eq!(Tooltip::label("dirt_floor"), "Dirt floor");
+    /// ```
+    pub fn label(name: &str) -> String {
+        let name = name.replace('_', " ");
+        let mut chars = name.chars();
+
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => name,
+        }
+    }
+
+    /// draws the tooltip below and to the right of its point, kept within the screen.
+    pub fn draw(&self, context: &mut BTerm) {
+        let (screen_width, screen_height) = context.get_char_size();
+        let width = self.lines.iter().map(|line| line.len()).max().unwrap_or(0) as i32 + 1;
+        let height = self.lines.len() as i32 + 1;
+
+        let x = (self.x + 1).min(screen_width as i32 - width - 1).max(0);
+        let y = (self.y + 1).min(screen_height as i32 - height - 1).max(0);
+
+        context.draw_box(
+            x,
+            y,
+            width,
+            height,
+            RGB::named(rltk::WHITE),
+            RGB::named(rltk::BLACK),
+        );
+        for (index, line) in self.lines.iter().enumerate() {
+            context.print(x + 1, y + 1 + index as i32, line);
+        }
+    }
+}
+
+impl UIState {
+    /// returns the tooltip for a point on the screen: what lies there on the map, or the
+    ///   inventory or equipment entry listed there.
+    /// public for testing purposes.
+    pub fn tooltip_at(&self, screen: Point) -> Option<Tooltip> {
+        let lines = if self.map_window.rect().point_in_rect(screen) {
+            // a pane covers the map beneath it.
+            if self.map_window.active_pane.is_some() {
+                return None;
+            }
+            let (x, y) = self.map_window.map_x_y(screen);
+            Tooltip::describe_location(
+                x,
+                y,
+                &self.characters,
+                &self.facilities,
+                &self.items,
+                &self.background,
+            )
+        } else if self.inventory_window.rect().point_in_rect(screen) {
+            let entries = match self.inventory_window.window_mode {
+                InventoryWindowMode::Inventory => &self.inventory,
+                InventoryWindowMode::Equipment => &self.equipment,
+            };
+            self.inventory_window
+                .entry_at(screen.y - self.inventory_window.y())
+                .and_then(|index| entries.get(index))
+                .map(Tooltip::describe_item)
+                .unwrap_or_default()
+        } else {
+            vec![]
+        };
+
+        Tooltip::new(screen.x, screen.y, lines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game::MapChunk;
+    use window::MapWindowMode;
+
+    fn subject() -> UIState {
+        let (_update_tx, update_rx) = mpsc::channel();
+        let (command_tx, _command_rx) = mpsc::channel();
+        UIState::new(update_rx, command_tx)
+    }
+
+    // the map window starts unscrolled, one in from its corner for the frame.
+    fn screen_point_of(subject: &UIState, x: i32, y: i32) -> Point {
+        Point::new(
+            subject.map_window.x() + 1 + x,
+            subject.map_window.y() + 1 + y,
+        )
+    }
+
+    #[test]
+    fn hovering_over_the_map_describes_what_is_there() {
+        let mut subject = subject();
+        subject.background = BackgroundMap::blank(4, 4);
+        subject.background.load_chunk(
+            &MapChunk {
+                x: 0,
+                y: 0,
+                version: 1,
+                width: 4,
+                height: 4,
+                tiles: vec![Tile::DirtFloor; 16],
+            },
+            |_| SpriteStyle {
+                glyph: b'.',
+                fg: RGB::named(rltk::WHITE),
+                bg: RGB::named(rltk::BLACK),
+            },
+        );
+        subject
+            .characters
+            .add_character(4, CharacterType::Rat, 2, 1);
+        subject
+            .items
+            .add_item(9, &"2 Apples".to_string(), ItemClass::Food, 2, 1);
+
+        let tooltip = subject.tooltip_at(screen_point_of(&subject, 2, 1)).unwrap();
+        assert_eq!(tooltip.lines, vec!["Rat", "2 Apples (Food)", "Dirt floor"]);
+
+        let tooltip = subject.tooltip_at(screen_point_of(&subject, 0, 0)).unwrap();
+        assert_eq!(tooltip.lines, vec!["Dirt floor"]);
+    }
+
+    #[test]
+    fn nothing_is_said_of_what_is_not_yet_known() {
+        let mut subject = subject();
+        subject.background = BackgroundMap::blank(4, 4);
+
+        assert_eq!(subject.tooltip_at(screen_point_of(&subject, 1, 1)), None);
+    }
+
+    #[test]
+    fn facilities_are_described_as_they_are_updated() {
+        let mut subject = subject();
+        subject.facilities.add_facility(
+            5,
+            1,
+            1,
+            FacilityClass::ClosedChest,
+            "A closed chest".into(),
+        );
+        subject.facilities.set_description(5, "An open chest");
+
+        let tooltip = subject.tooltip_at(screen_point_of(&subject, 1, 1)).unwrap();
+        assert_eq!(tooltip.lines, vec!["An open chest"]);
+    }
+
+    #[test]
+    fn hovering_over_an_inventory_entry_describes_the_item() {
+        let mut subject = subject();
+        subject.inventory = vec![
+            Item::spawn(ItemClass::Food, "Apple"),
+            Item::spawn(ItemClass::Headwear, "Winged Cap"),
+        ];
+        let window = &subject.inventory_window;
+        let line = |index: i32| Point::new(window.x() + 2, window.y() + 3 + index);
+
+        let tooltip = subject.tooltip_at(line(1)).unwrap();
+        assert_eq!(tooltip.lines, vec!["A Winged Cap", "Headwear"]);
+        assert_eq!(subject.tooltip_at(line(2)), None);
+    }
+
+    #[test]
+    fn hovering_over_equipment_describes_it_when_equipment_is_shown() {
+        let mut subject = subject();
+        subject.equipment = vec![Item::spawn(ItemClass::Headwear, "Winged Cap")];
+        let window = &subject.inventory_window;
+        let point = Point::new(window.x() + 2, window.y() + 3);
+
+        assert_eq!(subject.tooltip_at(point), None);
+
+        subject.inventory_window.window_mode = InventoryWindowMode::Equipment;
+        assert!(subject.tooltip_at(point).is_some());
+    }
+
+    #[test]
+    fn a_pane_over_the_map_hides_it_from_tooltips() {
+        let mut subject = subject();
+        subject
+            .characters
+            .add_character(4, CharacterType::Rat, 1, 1);
+        subject.map_window.window_mode = MapWindowMode::ExternalInventory;
+        subject.map_window.active_pane = Some(Pane::new(0, 0, 10, 10, 0));
+
+        assert_eq!(subject.tooltip_at(screen_point_of(&subject, 1, 1)), None);
+    }
+}
@@ -39,6 +39,14 @@ pub trait BasicWindow: ScreenObject {
         (self.local_x(x), self.local_y(y))
     }
 
+    /// returns the map location that set draws at a point on the screen.
+    fn map_x_y(&self, screen: Point) -> (i32, i32) {
+        (
+            screen.x - self.x() - 1 + self.scroll_x(),
+            screen.y - self.y() - 1 + self.scroll_y(),
+        )
+    }
+
     fn local_x(&self, x: i32) -> i32 {
         x - self.scroll_x() + self.x()
     }
@@ -426,6 +434,8 @@ pub struct InventoryWindow {
     pub selected_equipment: Option<u8>,
     pub max_selection_items: u8,
     pub max_selection_equipment: u8,
+
+    pub quantity_prompt: Option<QuantityPrompt>,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -434,6 +444,81 @@ pub enum InventoryWindowMode {
     Equipment,
 }
 
+/// what is done with the quantity entered at a QuantityPrompt.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum QuantityAction {
+    Drop,
+    Split,
+    Transfer(u64, u64), // (src_inventory_id, dest_inventory_id)
+}
+
+/// asks how many of a stack to act upon.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct QuantityPrompt {
+    pub action: QuantityAction,
+    pub item_id: u64,
+    pub max: u8,
+    pub entry: String,
+    pub return_state: InputState, // the input state to resume once answered
+}
+
+impl QuantityPrompt {
+    pub fn new(action: QuantityAction, item_id: u64, max: u8, return_state: InputState) -> Self {
+        Self {
+            action,
+            item_id,
+            max,
+            entry: String::new(),
+            return_state,
+        }
+    }
+
+    /// adds a digit to the entry, ignoring any that would make it longer than max.
+    pub fn push_digit(&mut self, digit: u8) {
+        let entry = format!("{}{}", self.entry, digit);
+        if entry
+            .parse::<u32>()
+            .is_ok_and(|quantity| quantity <= self.max as u32)
+        {
+            self.entry = entry;
+        }
+    }
+
+    pub fn pop_digit(&mut self) {
+        self.entry.pop();
+    }
+
+    /// returns the quantity entered.  An empty entry means as many as allowed.
+    /// # Examples:
+    /// ```
+    /// # use muframework::ui::input::InputState;
+    /// # use muframework::ui::window::*;
+    /// let mut subject = QuantityPrompt::new(QuantityAction::Drop, 7, 40, InputState::Normal);
+    /// assert_eq!(subject.quantity(), 40);
+    /// subject.push_digit(4);
+    /// subject.push_digit(5);
+    /// assert_eq!(subject.quantity(), 4);
+    /// ```
+    pub fn quantity(&self) -> u8 {
+        self.entry.parse::<u8>().unwrap_or(self.max)
+    }
+
+    /// returns the command that carries out the prompt's action.
+    pub fn command(&self) -> Command {
+        match self.action {
+            QuantityAction::Drop => Command::DropItem(self.item_id, self.quantity()),
+            QuantityAction::Split => Command::SplitStack(self.item_id, self.quantity()),
+            QuantityAction::Transfer(source_id, destination_id) => {
+                Command::TransferItem(self.item_id, source_id, destination_id, self.quantity())
+            }
+        }
+    }
+
+    pub fn text(&self) -> String {
+        format!("How many (1-{})? {}_", self.max, self.entry)
+    }
+}
+
 impl InventoryWindow {
     pub fn new(x: i32, y: i32, width: i32, height: i32) -> InventoryWindow {
         InventoryWindow {
@@ -457,6 +542,8 @@ impl InventoryWindow {
             selected_equipment: None,
             max_selection_items: 0,
             max_selection_equipment: 0,
+
+            quantity_prompt: None,
         }
     }
 
@@ -546,6 +633,16 @@ impl BasicWindow for InventoryWindow {
         } else {
             self.draw_equipment_pane(context);
         }
+
+        if let Some(ref prompt) = self.quantity_prompt {
+            context.print_color(
+                self.local_x(1),
+                self.local_y(self.height - 2),
+                bg,
+                fg,
+                prompt.text(),
+            );
+        }
     }
 }
 
@@ -578,6 +675,15 @@ impl InventoryWindow {
         }
     }
 
+    /// returns the index of the entry that would be listed on the line y of the window,
+    ///   counted from its top.
+    pub fn entry_at(&self, y: i32) -> Option<usize> {
+        match y - 3 {
+            index if index >= 0 => Some(index as usize),
+            _ => None,
+        }
+    }
+
     pub fn set_max_item_selection(&mut self, max: u8) {
         self.max_selection_items = max;
 